@@ -0,0 +1,81 @@
+//! async game loop runner (feature async, no runtime dependency)
+//!
+//! adapters for tokio, async-std and others implement AsyncInput (next
+//! input of an event stream, must be cancel safe as it is raced with the
+//! timer) and AsyncTimer (sleep)
+//!
+
+use std::error::Error;
+use std::future::{Future, poll_fn};
+use std::pin::pin;
+use std::task::Poll;
+use std::time::Duration;
+
+use crate::{MineField, TViewRun};
+use crate::blink::Blinker;
+use crate::input::{Handled, Input};
+use crate::runner::Outcome;
+
+/// trait AsyncInput
+pub trait AsyncInput {
+  /// next input (None: the stream ended)
+  fn next(&mut self) ->
+    impl Future<Output=Result<Option<Input>, Box<dyn Error>>>;
+}
+
+/// trait AsyncTimer
+pub trait AsyncTimer {
+  /// sleep for d
+  fn sleep(&mut self, d: Duration) -> impl Future<Output=()>;
+}
+
+/// next input or None when the timer fires first
+async fn next_or_tick(i: &mut impl AsyncInput, t: &mut impl AsyncTimer,
+  d: Duration) -> Option<Result<Option<Input>, Box<dyn Error>>> {
+  let mut a = pin!(i.next());
+  let mut b = pin!(t.sleep(d));
+  poll_fn(|cx| {
+    if let Poll::Ready(x) = a.as_mut().poll(cx) { return Poll::Ready(Some(x)); }
+    if b.as_mut().poll(cx).is_ready() { return Poll::Ready(None); }
+    Poll::Pending
+  }).await
+}
+
+/// run a game until the end or quit (the end of the stream is quit, g is
+/// flushed before each wait)
+pub async fn run_async<T>(m: &mut MineField, g: &mut impl TViewRun<T>,
+  i: &mut impl AsyncInput, t: &mut impl AsyncTimer) ->
+  Result<Outcome, Box<dyn Error>> {
+  run_async_blink(m, g, i, t, &mut Blinker::default()).await
+}
+
+/// run_async with a blink timing (see runner::run_blink)
+pub async fn run_async_blink<T>(m: &mut MineField,
+  g: &mut impl TViewRun<T>,
+  i: &mut impl AsyncInput, t: &mut impl AsyncTimer, bl: &mut Blinker) ->
+  Result<Outcome, Box<dyn Error>> {
+  m.invalidate();
+  bl.reset(m, g)?;
+  while !m.is_end() {
+    if m.check_time().is_some() { m.ending(g)?; break; }
+    g.flush()?;
+    match next_or_tick(i, t, bl.ms).await {
+    Some(p) => match p? {
+      None => return Ok(Outcome::Quit),
+      Some(p) => match m.handle(p, g)? {
+        Handled::Quit => return Ok(Outcome::Quit),
+        Handled::None => (),
+        _ => bl.t = 0
+      }
+    },
+    None => bl.tick(m, g)?
+    }
+  }
+  while !m.cascade.is_empty() {
+    g.flush()?;
+    t.sleep(bl.ms).await;
+    bl.tick(m, g)?;
+  }
+  g.flush()?;
+  Ok(if m.is_success() { Outcome::Won } else { Outcome::Lost })
+}
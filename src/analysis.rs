@@ -0,0 +1,95 @@
+//! board classification (guess free or guess required) and difficulty
+//!
+
+use crate::MineField;
+use crate::exact::MAX_BORDER;
+use crate::solver::Known;
+
+/// BoardAnalysis
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardAnalysis {
+  /// starting click (r, c)
+  pub start: (u16, u16),
+  /// solvable without guessing from the start
+  pub guess_free: bool,
+  /// forced guesses (the safest cell is opened knowing the mines)
+  pub guesses: u32,
+  /// passes of the solver (each opens every cell known safe)
+  pub rounds: u32,
+  /// cells opened by single number deduction
+  pub basic: u32,
+  /// cells opened by exact enumeration only
+  pub advanced: u32,
+  /// mine probability of the riskiest forced guess
+  pub max_risk: f64,
+  /// 3BV of the board
+  pub bbbv: u32
+}
+
+/// MineField
+impl MineField {
+  /// analyze the board from the starting click (r, c) by the solver
+  /// (before the first click: the board of the seed with the default
+  /// placement)
+  pub fn analyze(&self, r: u16, c: u16) -> BoardAnalysis {
+    let mut f = if !self.p {
+      let mut f = MineField::from_seed(self.w, self.h, self.m, self.seed);
+      if let Some(m) = &self.mask { f.set_mask(m.clone()); }
+      f.goto(r, c);
+      f.start();
+      f
+    } else {
+      let q = self.cells().filter(|(_, _, u)| u.is_mine())
+        .map(|(r, c, _)| (r, c)).collect::<Vec<_>>();
+      let mut f = MineField::with_mines(self.w, self.h, &q);
+      if let Some(m) = &self.mask { f.set_mask(m.clone()); }
+      f
+    };
+    let mut a = BoardAnalysis{start: (r, c), guess_free: true, guesses: 0,
+      rounds: 0, basic: 0, advanced: 0, max_risk: 0.0, bbbv: f.bbbv()};
+    f.goto(r, c);
+    f.click();
+    while !f.is_end() {
+      a.rounds += 1;
+      let k = f.deduce();
+      let q = f.cells().filter(|&(r, c, u)| !u.is_open() &&
+        k[r as usize][c as usize] == Known::Safe).map(|(r, c, _)| (r, c))
+        .collect::<Vec<_>>();
+      if !q.is_empty() {
+        a.basic += f.open_all(&q);
+        continue;
+      }
+      let e = f.enumerate(MAX_BORDER);
+      let p = e.as_ref().map_or_else(|| f.probabilities(), |e| e.p.clone());
+      let q = f.cells().filter(|&(r, c, u)| !u.is_open() &&
+        p[r as usize][c as usize] == Some(0.0)).map(|(r, c, _)| (r, c))
+        .collect::<Vec<_>>();
+      if !q.is_empty() {
+        a.advanced += f.open_all(&q);
+        continue;
+      }
+      let g = f.cells().filter(|&(r, c, u)| !u.is_open() && !u.is_mine() &&
+        f.is_playable(r, c)).filter_map(|(r, c, _)|
+        p[r as usize][c as usize].map(|x| (x, r, c)))
+        .min_by(|a, b| a.0.total_cmp(&b.0));
+      let Some((x, r, c)) = g else { break; };
+      a.guess_free = false;
+      a.guesses += 1;
+      a.max_risk = a.max_risk.max(x);
+      f.goto(r, c);
+      f.click();
+    }
+    a
+  }
+
+  /// open_all cells (r, c) still closed (returns cells clicked)
+  fn open_all(&mut self, q: &[(u16, u16)]) -> u32 {
+    let mut n = 0;
+    for &(r, c) in q {
+      if self.is_opened(r, c) || !self.goto(r, c) { continue; }
+      self.click();
+      n += 1;
+    }
+    n
+  }
+}
@@ -0,0 +1,128 @@
+//! validated moves for a server keeping the canonical board
+//!
+
+use std::error::Error;
+use std::fmt;
+
+use crate::MineField;
+use crate::flag::FlagError;
+use crate::replay::Move;
+
+/// MoveError
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveError {
+  /// game over
+  Ended,
+  /// paused
+  Paused,
+  /// cell (r, c) out of the field
+  OutOfBounds(u16, u16),
+  /// cell masked out (not playable)
+  Masked,
+  /// cell already opened
+  Opened,
+  /// cell flagged (not opened by a click)
+  Flagged,
+  /// chord on a closed cell
+  NotOpened,
+  /// no flags left of the budget
+  Budget(u16),
+  /// move without effect (chord not satisfied, finish not allowed)
+  NoEffect
+}
+
+/// MoveError
+impl fmt::Display for MoveError {
+  /// fmt
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+    MoveError::Ended => write!(f, "game is over"),
+    MoveError::Paused => write!(f, "game is paused"),
+    MoveError::OutOfBounds(r, c) => write!(f, "cell ({}, {}) is out of bounds",
+      r, c),
+    MoveError::Masked => write!(f, "cell is not playable"),
+    MoveError::Opened => write!(f, "cell is already opened"),
+    MoveError::Flagged => write!(f, "cell is flagged"),
+    MoveError::NotOpened => write!(f, "cell is not opened"),
+    MoveError::Budget(n) => write!(f, "all {} flags are used", n),
+    MoveError::NoEffect => write!(f, "move has no effect")
+    }
+  }
+}
+
+/// MoveError
+impl Error for MoveError {}
+
+/// MoveError
+impl From<FlagError> for MoveError {
+  /// from
+  fn from(e: FlagError) -> Self {
+    match e {
+    FlagError::Ended => MoveError::Ended,
+    FlagError::Paused => MoveError::Paused,
+    FlagError::Opened => MoveError::Opened,
+    FlagError::Masked => MoveError::Masked,
+    FlagError::Budget(n) => MoveError::Budget(n)
+    }
+  }
+}
+
+/// MoveOutcome (exactly what changed)
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MoveOutcome {
+  /// cells opened (r, c, cell value)
+  pub opened: Vec<(u16, u16, u8)>,
+  /// cells marked (r, c, flag 0x40 / question 0x20 / none 0)
+  pub marked: Vec<(u16, u16, u8)>,
+  /// exploded by the move
+  pub exploded: bool,
+  /// won by the move
+  pub won: bool
+}
+
+/// MineField
+impl MineField {
+  /// validate a move against the current state
+  pub fn validate_move(&self, m: Move) -> Result<(), MoveError> {
+    if self.is_end() { return Err(MoveError::Ended); }
+    if self.paused { return Err(MoveError::Paused); }
+    let (r, c) = match m {
+    Move::Open(r, c) | Move::Chord(r, c) | Move::Mark(r, c) |
+    Move::Flag(r, c) => (r, c),
+    Move::Finish => return Ok(())
+    };
+    if r >= self.h || c >= self.w { return Err(MoveError::OutOfBounds(r, c)); }
+    if !self.is_playable(r, c) { return Err(MoveError::Masked); }
+    let o = self.is_opened(r, c);
+    match m {
+    Move::Chord(..) if !o => Err(MoveError::NotOpened),
+    Move::Chord(..) => Ok(()),
+    _ if o => Err(MoveError::Opened),
+    Move::Open(..) if self.is_flagged(r, c) => Err(MoveError::Flagged),
+    _ => Ok(())
+    }
+  }
+
+  /// apply a validated move (returns what changed)
+  pub fn apply_move(&mut self, m: Move) -> Result<MoveOutcome, MoveError> {
+    self.validate_move(m)?;
+    let f = self.f.clone();
+    let s = self.s;
+    match m {
+    Move::Flag(r, c) => { self.goto(r, c); self.toggle_flag()?; },
+    _ => if !m.play(self) { return Err(MoveError::NoEffect); }
+    }
+    let mut o = MoveOutcome{exploded: s & 0x8000 == 0 && self.is_explosion(),
+      won: s & 0x4000 == 0 && self.is_success(), ..Default::default()};
+    for r in 0..self.h {
+      for c in 0..self.w {
+        let a = f[r as usize][c as usize];
+        let b = self.f[r as usize][c as usize];
+        if a == b { continue; }
+        if !Self::is_o(a) && Self::is_o(b) { o.opened.push((r, c, b & 0x0f)); }
+        else if a & 0x60 != b & 0x60 { o.marked.push((r, c, b & 0x60)); }
+      }
+    }
+    Ok(o)
+  }
+}
@@ -0,0 +1,93 @@
+//! arcade rules (sweep streak)
+//!
+
+use crate::MineField;
+
+/// StreakEvent for frontends to animate
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreakEvent {
+  /// streak grows to n
+  Streak(u16),
+  /// bonus points awarded
+  Bonus(u32),
+  /// meter reached max
+  Full,
+  /// streak broken (meter empty or explosion)
+  Break(u16)
+}
+
+/// Streak meter and score
+#[derive(Debug, Clone)]
+pub struct Streak {
+  /// energy meter
+  pub e: u16,
+  /// energy meter max
+  pub emax: u16,
+  /// energy gain per open
+  pub gain: u16,
+  /// energy decay per tick
+  pub decay: u16,
+  /// streak count
+  pub n: u16,
+  /// score
+  pub score: u32,
+  /// opened cells already counted
+  pub o: u16
+}
+
+/// Streak
+impl Streak {
+  /// constructor
+  pub fn new(emax: u16, gain: u16, decay: u16) -> Self {
+    Streak{e: 0, emax, gain, decay, n: 0, score: 0, o: 0}
+  }
+
+  /// multiplier (grows every 5 streaks)
+  pub fn multiplier(&self) -> u32 { 1 + self.n as u32 / 5 }
+
+  /// opened k cells by a correct click
+  pub fn open(&mut self, k: u16) -> Vec<StreakEvent> {
+    let mut ev = vec![];
+    if k == 0 { return ev; }
+    self.n += 1;
+    ev.push(StreakEvent::Streak(self.n));
+    let full = self.e >= self.emax;
+    self.e = self.emax.min(self.e.saturating_add(self.gain));
+    if !full && self.e >= self.emax { ev.push(StreakEvent::Full); }
+    let base = k as u32;
+    let mul = self.multiplier() + if self.e >= self.emax { 1 } else { 0 };
+    self.score += base * mul;
+    if mul > 1 { ev.push(StreakEvent::Bonus(base * (mul - 1))); }
+    ev
+  }
+
+  /// break streak
+  pub fn explosion(&mut self) -> Vec<StreakEvent> {
+    let n = self.n;
+    self.n = 0;
+    self.e = 0;
+    if n > 0 { vec![StreakEvent::Break(n)] } else { vec![] }
+  }
+
+  /// decay meter (call with the blink tick or any steady cadence)
+  pub fn tick(&mut self) -> Vec<StreakEvent> {
+    if self.e == 0 { return vec![]; }
+    self.e = self.e.saturating_sub(self.decay);
+    if self.e == 0 { self.explosion() } else { vec![] }
+  }
+
+  /// update from the field after each click
+  pub fn update(&mut self, m: &MineField) -> Vec<StreakEvent> {
+    if m.is_explosion() { return self.explosion(); }
+    let o = m.opened();
+    let k = o.saturating_sub(self.o);
+    self.o = o;
+    self.open(k)
+  }
+}
+
+/// Streak
+impl Default for Streak {
+  /// default
+  fn default() -> Self { Streak::new(100, 20, 1) }
+}
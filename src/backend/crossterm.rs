@@ -0,0 +1,192 @@
+//! crossterm backend
+//!
+
+use std::error::Error;
+use std::io::{self, Write};
+use std::time::Duration;
+
+use ::crossterm::{cursor, event, execute, queue, style, terminal};
+use ::crossterm::event::{Event, KeyCode, MouseButton, MouseEventKind};
+use ::crossterm::style::{Attribute, Color};
+
+use mvc_rs::{TPacket, TView};
+
+use crate::{MineField, TViewRun, color};
+use crate::input::{self, Direction, Handled, Input};
+use crate::runner::InputSource;
+use crate::style::{BLINK, BOLD, REVERSE, UNDERLINE, attrs};
+
+/// translate a terminal event to an input (board offset ox, oy)
+pub fn translate(e: &Event, ox: u16, oy: u16) -> Option<Input> {
+  match e {
+  Event::Key(k) => match k.code {
+    KeyCode::Up | KeyCode::Char('k') => Some(Input::Up),
+    KeyCode::Down | KeyCode::Char('j') => Some(Input::Down),
+    KeyCode::Left | KeyCode::Char('h') => Some(Input::Left),
+    KeyCode::Right | KeyCode::Char('l') => Some(Input::Right),
+    KeyCode::Char('K') => Some(Input::NextClosed(Direction::Up)),
+    KeyCode::Char('J') => Some(Input::NextClosed(Direction::Down)),
+    KeyCode::Char('H') => Some(Input::NextClosed(Direction::Left)),
+    KeyCode::Char('L') => Some(Input::NextClosed(Direction::Right)),
+    KeyCode::PageUp => Some(Input::Edge(Direction::Up)),
+    KeyCode::PageDown => Some(Input::Edge(Direction::Down)),
+    KeyCode::Char('0') => Some(Input::Edge(Direction::Left)),
+    KeyCode::Char('$') | KeyCode::End => Some(Input::Edge(Direction::Right)),
+    KeyCode::Home | KeyCode::Char('g') => Some(Input::Home),
+    KeyCode::Char(' ') | KeyCode::Enter => Some(Input::Open),
+    KeyCode::Char('f') | KeyCode::Char('m') => Some(Input::Flag),
+    KeyCode::Char('c') => Some(Input::Chord),
+    KeyCode::Char('p') => Some(Input::Pause),
+    KeyCode::Char('q') | KeyCode::Esc => Some(Input::Quit),
+    _ => None
+  },
+  Event::Mouse(p) => {
+    let (x, y) = (p.column.wrapping_sub(ox), p.row.wrapping_sub(oy));
+    let b = |b: &MouseButton| match b {
+      MouseButton::Left => input::MouseButton::Left,
+      MouseButton::Right => input::MouseButton::Right,
+      MouseButton::Middle => input::MouseButton::Middle
+    };
+    let (button, action) = match &p.kind {
+    MouseEventKind::Down(k) => (b(k), input::MouseAction::Press),
+    MouseEventKind::Up(k) => (b(k), input::MouseAction::Release),
+    MouseEventKind::Drag(k) => (b(k), input::MouseAction::Move),
+    MouseEventKind::Moved => (input::MouseButton::Left, input::MouseAction::Move),
+    _ => return None
+    };
+    Some(Input::Mouse{x, y, button, action})
+  },
+  Event::Resize(w, h) =>
+    Some(Input::Resize(w.saturating_sub(ox), h.saturating_sub(oy))),
+  _ => None
+  }
+}
+
+/// CrosstermInput
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CrosstermInput {
+  /// board offset x
+  pub ox: u16,
+  /// board offset y
+  pub oy: u16
+}
+
+/// trait InputSource for CrosstermInput
+impl InputSource for CrosstermInput {
+  /// poll
+  fn poll(&mut self, timeout: Duration) ->
+    Result<Option<Input>, Box<dyn Error>> {
+    if !event::poll(timeout)? { return Ok(None); }
+    Ok(translate(&event::read()?, self.ox, self.oy))
+  }
+}
+
+/// CrosstermWR
+pub struct CrosstermWR<W: Write> {
+  /// writer
+  pub o: W,
+  /// palette
+  pub p: Vec<Color>,
+  /// board offset x
+  pub ox: u16,
+  /// board offset y
+  pub oy: u16
+}
+
+/// CrosstermWR
+impl CrosstermWR<io::Stdout> {
+  /// constructor (raw mode, alternate screen, mouse capture)
+  pub fn new() -> Result<Self, Box<dyn Error>> {
+    let mut o = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(o, terminal::EnterAlternateScreen, event::EnableMouseCapture,
+      cursor::Hide, terminal::Clear(terminal::ClearType::All))?;
+    Ok(CrosstermWR{o, p: Self::palette(), ox: 0, oy: 0})
+  }
+}
+
+/// crossterm Color
+impl From<color::Color> for Color {
+  /// from
+  fn from(c: color::Color) -> Self {
+    match c {
+    color::Color::Indexed(n) => Color::AnsiValue(n as u8),
+    color::Color::Rgb(r, g, b) => Color::Rgb{r, g, b},
+    color::Color::Named(n) => Color::AnsiValue(n.ansi())
+    }
+  }
+}
+
+/// CrosstermWR
+impl<W: Write> CrosstermWR<W> {
+  /// default palette (ids of Theme::classic_rgb)
+  pub fn palette() -> Vec<Color> { Self::colors(&color::Palette::classic()) }
+
+  /// colors of a palette (for reg)
+  pub fn colors(p: &color::Palette) -> Vec<Color> {
+    p.v.iter().map(|&c| c.into()).collect()
+  }
+
+  /// translate a terminal event to an input
+  pub fn translate(&self, e: &Event) -> Option<Input> {
+    translate(e, self.ox, self.oy)
+  }
+
+  /// wait for an event until timeout and handle it
+  pub fn poll(&mut self, m: &mut MineField, timeout: Duration) ->
+    Result<Handled, Box<dyn Error>> {
+    if !event::poll(timeout)? { return Ok(Handled::None); }
+    let h = match self.translate(&event::read()?) {
+    Some(i) => m.handle(i, self)?,
+    None => Handled::None
+    };
+    TViewRun::flush(self)?;
+    Ok(h)
+  }
+}
+
+/// CrosstermWR
+impl<W: Write> Drop for CrosstermWR<W> {
+  /// restore terminal
+  fn drop(&mut self) {
+    let _ = execute!(self.o, style::ResetColor, cursor::Show,
+      event::DisableMouseCapture, terminal::LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+  }
+}
+
+/// trait TView for CrosstermWR
+impl<W: Write> TView<Color> for CrosstermWR<W> {
+  /// wr
+  fn wr(&mut self, p: impl TPacket) -> Result<(), Box<dyn Error>> {
+    let v = p.to_vec();
+    self.wr_run(v[0], v[1], v[2], v[3], v[4], p.as_str())
+  }
+  /// reg
+  fn reg(&mut self, c: Vec<Color>) -> () { self.p = c; }
+  /// col
+  fn col(&self, n: u16) -> Color {
+    self.p.get(n as usize).copied().unwrap_or(Color::Reset)
+  }
+}
+
+/// trait TViewRun for CrosstermWR
+impl<W: Write> TViewRun<Color> for CrosstermWR<W> {
+  /// wr_run queued until flush
+  fn wr_run(&mut self, x: u16, y: u16, st: u16, bgc: u16, fgc: u16,
+    s: &str) -> Result<(), Box<dyn Error>> {
+    let (b, f) = (self.col(bgc), self.col(fgc));
+    queue!(self.o, cursor::MoveTo(self.ox + x, self.oy + y),
+      style::SetBackgroundColor(b), style::SetForegroundColor(f))?;
+    let a = attrs(st);
+    for (k, t) in [(BOLD, Attribute::Bold), (REVERSE, Attribute::Reverse),
+      (UNDERLINE, Attribute::Underlined), (BLINK, Attribute::SlowBlink)] {
+      if a & k != 0 { queue!(self.o, style::SetAttribute(t))?; }
+    }
+    queue!(self.o, style::Print(s))?;
+    if a != 0 { queue!(self.o, style::SetAttribute(Attribute::Reset))?; }
+    Ok(())
+  }
+  /// flush
+  fn flush(&mut self) -> Result<(), Box<dyn Error>> { Ok(self.o.flush()?) }
+}
@@ -0,0 +1,7 @@
+//! ready-made backends (TView implementations and input adapters)
+//!
+
+#[cfg(feature = "backend-crossterm")]
+pub mod crossterm;
+#[cfg(feature = "backend-termion")]
+pub mod termion;
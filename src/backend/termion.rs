@@ -0,0 +1,189 @@
+//! termion backend
+//!
+
+use std::error::Error;
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+use ::termion::{clear, color, cursor, style};
+use ::termion::event::{Event, Key, MouseButton, MouseEvent};
+use ::termion::input::{Events, MouseTerminal, TermRead};
+use ::termion::raw::{IntoRawMode, RawTerminal};
+use ::termion::screen::{AlternateScreen, IntoAlternateScreen};
+use ::termion::color::AnsiValue;
+
+use mvc_rs::{TPacket, TView};
+
+use crate::TViewRun;
+use crate::input::{self, Direction, Input};
+use crate::runner::InputSource;
+use crate::style::{BLINK, BOLD, REVERSE, UNDERLINE, attrs};
+use crate::color::Palette;
+
+/// terminal writer (alternate screen, mouse, raw mode)
+pub type Term = AlternateScreen<MouseTerminal<RawTerminal<io::Stdout>>>;
+
+/// TermionWR
+pub struct TermionWR<W: Write> {
+  /// writer
+  pub o: W,
+  /// palette
+  pub p: Vec<AnsiValue>,
+  /// board offset x
+  pub ox: u16,
+  /// board offset y
+  pub oy: u16
+}
+
+/// TermionWR
+impl TermionWR<Term> {
+  /// constructor (raw mode, alternate screen, mouse capture)
+  pub fn new() -> Result<Self, Box<dyn Error>> {
+    let raw = io::stdout().into_raw_mode()?;
+    let mut o = MouseTerminal::from(raw).into_alternate_screen()?;
+    write!(o, "{}{}", cursor::Hide, clear::All)?;
+    o.flush()?;
+    Ok(TermionWR{o, p: Self::palette(), ox: 1, oy: 1})
+  }
+}
+
+/// TermionWR
+impl<W: Write> TermionWR<W> {
+  /// default palette (ids of Theme::classic_rgb on 6x6x6 color cube)
+  pub fn palette() -> Vec<AnsiValue> { Self::colors(&Palette::classic()) }
+
+  /// colors of a palette (for reg, rgb on 6x6x6 color cube)
+  pub fn colors(p: &Palette) -> Vec<AnsiValue> {
+    p.v.iter().map(|c| AnsiValue(c.ansi256())).collect()
+  }
+}
+
+/// TermionInput
+pub struct TermionInput {
+  /// input events (non blocking)
+  pub i: Events<::termion::AsyncReader>,
+  /// board offset x
+  pub ox: u16,
+  /// board offset y
+  pub oy: u16,
+  /// last pressed button (termion does not tell it at release)
+  pub b: input::MouseButton
+}
+
+/// TermionInput
+impl TermionInput {
+  /// constructor (1 origin mouse coordinates)
+  pub fn new() -> Self {
+    TermionInput{i: ::termion::async_stdin().events(), ox: 1, oy: 1,
+      b: input::MouseButton::Left}
+  }
+
+  /// translate a terminal event to an input
+  pub fn translate(&mut self, e: &Event) -> Option<Input> {
+    match e {
+    Event::Key(k) => match k {
+      Key::Up | Key::Char('k') => Some(Input::Up),
+      Key::Down | Key::Char('j') => Some(Input::Down),
+      Key::Left | Key::Char('h') => Some(Input::Left),
+      Key::Right | Key::Char('l') => Some(Input::Right),
+      Key::Char('K') => Some(Input::NextClosed(Direction::Up)),
+      Key::Char('J') => Some(Input::NextClosed(Direction::Down)),
+      Key::Char('H') => Some(Input::NextClosed(Direction::Left)),
+      Key::Char('L') => Some(Input::NextClosed(Direction::Right)),
+      Key::PageUp => Some(Input::Edge(Direction::Up)),
+      Key::PageDown => Some(Input::Edge(Direction::Down)),
+      Key::Char('0') => Some(Input::Edge(Direction::Left)),
+      Key::Char('$') | Key::End => Some(Input::Edge(Direction::Right)),
+      Key::Home | Key::Char('g') => Some(Input::Home),
+      Key::Char(' ') | Key::Char('\n') => Some(Input::Open),
+      Key::Char('f') | Key::Char('m') => Some(Input::Flag),
+      Key::Char('c') => Some(Input::Chord),
+      Key::Char('p') => Some(Input::Pause),
+      Key::Char('q') | Key::Esc => Some(Input::Quit),
+      _ => None
+    },
+    Event::Mouse(p) => {
+      let (action, x, y) = match p {
+      MouseEvent::Press(b, x, y) => {
+        self.b = match b {
+        MouseButton::Left => input::MouseButton::Left,
+        MouseButton::Right => input::MouseButton::Right,
+        MouseButton::Middle => input::MouseButton::Middle,
+        _ => return None
+        };
+        (input::MouseAction::Press, x, y)
+      },
+      MouseEvent::Release(x, y) => (input::MouseAction::Release, x, y),
+      MouseEvent::Hold(x, y) => (input::MouseAction::Move, x, y)
+      };
+      let (x, y) = (x.wrapping_sub(self.ox), y.wrapping_sub(self.oy));
+      Some(Input::Mouse{x, y, button: self.b, action})
+    },
+    _ => None
+    }
+  }
+}
+
+/// TermionInput
+impl Default for TermionInput {
+  /// default
+  fn default() -> Self { Self::new() }
+}
+
+/// trait InputSource for TermionInput
+impl InputSource for TermionInput {
+  /// poll (sleep timeout when idle)
+  fn poll(&mut self, timeout: Duration) ->
+    Result<Option<Input>, Box<dyn Error>> {
+    match self.i.next() {
+    Some(e) => Ok(self.translate(&e?)),
+    None => { thread::sleep(timeout); Ok(None) }
+    }
+  }
+}
+
+/// TermionWR
+impl<W: Write> Drop for TermionWR<W> {
+  /// restore terminal
+  fn drop(&mut self) {
+    let _ = write!(self.o, "{}{}", style::Reset, cursor::Show);
+    let _ = self.o.flush();
+  }
+}
+
+/// trait TView for TermionWR
+impl<W: Write> TView<AnsiValue> for TermionWR<W> {
+  /// wr
+  fn wr(&mut self, p: impl TPacket) -> Result<(), Box<dyn Error>> {
+    let v = p.to_vec();
+    self.wr_run(v[0], v[1], v[2], v[3], v[4], p.as_str())
+  }
+  /// reg
+  fn reg(&mut self, c: Vec<AnsiValue>) -> () { self.p = c; }
+  /// col
+  fn col(&self, n: u16) -> AnsiValue {
+    self.p.get(n as usize).copied().unwrap_or(AnsiValue(7))
+  }
+}
+
+/// trait TViewRun for TermionWR
+impl<W: Write> TViewRun<AnsiValue> for TermionWR<W> {
+  /// wr_run buffered until flush
+  fn wr_run(&mut self, x: u16, y: u16, st: u16, bgc: u16, fgc: u16,
+    s: &str) -> Result<(), Box<dyn Error>> {
+    let (b, f) = (self.col(bgc), self.col(fgc));
+    write!(self.o, "{}{}{}", cursor::Goto(self.ox + x, self.oy + y),
+      color::Bg(b), color::Fg(f))?;
+    let a = attrs(st);
+    if a & BOLD != 0 { write!(self.o, "{}", style::Bold)?; }
+    if a & REVERSE != 0 { write!(self.o, "{}", style::Invert)?; }
+    if a & UNDERLINE != 0 { write!(self.o, "{}", style::Underline)?; }
+    if a & BLINK != 0 { write!(self.o, "{}", style::Blink)?; }
+    write!(self.o, "{}", s)?;
+    if a != 0 { write!(self.o, "{}", style::Reset)?; }
+    Ok(())
+  }
+  /// flush
+  fn flush(&mut self) -> Result<(), Box<dyn Error>> { Ok(self.o.flush()?) }
+}
@@ -0,0 +1,53 @@
+//! cursor blink timing (kept out of the game model)
+//!
+
+use std::time::Duration;
+
+use mvc_rs::TView;
+
+use crate::MineField;
+use crate::error::MineFieldError;
+
+/// Blinker
+#[derive(Debug, Clone)]
+pub struct Blinker {
+  /// ms timeout for idle
+  pub ms: Duration,
+  /// blink cursor count max
+  pub b: u16,
+  /// tick count about b x ms
+  pub t: u16
+}
+
+/// Blinker
+impl Default for Blinker {
+  /// default 10ms x 80
+  fn default() -> Self { Blinker::new(Duration::from_millis(10), 80) }
+}
+
+/// Blinker
+impl Blinker {
+  /// constructor
+  pub fn new(ms: Duration, b: u16) -> Self { Blinker{ms, b, t: 0} }
+
+  /// tick and control blink cursor (and the cascade animation, the ending
+  /// when the time budget expires)
+  pub fn tick<T>(&mut self, m: &mut MineField, g: &mut impl TView<T>) ->
+    Result<(), MineFieldError> {
+    self.t += 1;
+    if m.check_time().is_some() { m.ending(g)?; }
+    if m.step_cascade() { m.refresh_dirty(g)?; } // a wave per tick
+    if self.t == self.b / 2 { m.k = false; m.refresh_dirty(g)?; }
+    else if self.t >= self.b { self.reset(m, g)?; }
+    Ok(())
+  }
+
+  /// reset tick (cursor shown)
+  pub fn reset<T>(&mut self, m: &mut MineField, g: &mut impl TView<T>) ->
+    Result<(), MineFieldError> {
+    self.t = 0;
+    m.k = true;
+    m.refresh_dirty(g)?;
+    Ok(())
+  }
+}
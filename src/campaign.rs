@@ -0,0 +1,78 @@
+//! progressive endless mode (successive fields with higher mine density)
+//!
+
+use std::time::Duration;
+
+use crate::MineField;
+use crate::config::{Config, Difficulty};
+
+/// Campaign
+pub struct Campaign {
+  /// difficulty of the first field
+  pub d: Difficulty,
+  /// config for every field
+  pub cfg: Config,
+  /// base seed (field seed = seed + level)
+  pub seed: u64,
+  /// mines added per level
+  pub dm: u16,
+  /// level (0: first field)
+  pub level: u16,
+  /// score carried over
+  pub score: u64,
+  /// time of finished fields
+  pub total: Duration,
+  /// cells opened on finished fields
+  pub opened: u32,
+  /// current field
+  pub field: MineField
+}
+
+/// Campaign
+impl Campaign {
+  /// constructor (dm: w x h / 40 at least 1)
+  pub fn new(d: Difficulty, cfg: Config, seed: u64) -> Self {
+    let dm = (d.w * d.h / 40).max(1);
+    let field = Self::make(d, &cfg, seed);
+    Campaign{d, cfg, seed, dm, level: 0, score: 0, total: Duration::ZERO,
+      opened: 0, field}
+  }
+
+  /// make a field
+  fn make(d: Difficulty, cfg: &Config, seed: u64) -> MineField {
+    let mut f = MineField::from_seed(d.w, d.h, d.m, seed);
+    f.cfg = cfg.clone();
+    f
+  }
+
+  /// mines at the level (at most w x h - 1)
+  pub fn mines(&self, level: u16) -> u16 {
+    let m = self.d.m as u32 + level as u32 * self.dm as u32;
+    m.min(self.d.w as u32 * self.d.h as u32 - 1) as u16
+  }
+
+  /// advance to the next field when the current one is won
+  /// (false: still playing or lost)
+  pub fn advance(&mut self) -> bool {
+    if !self.field.is_success() { return false; }
+    self.score += self.field.m as u64 * (self.level as u64 + 1);
+    self.total += self.field.elapsed();
+    self.opened += self.field.opened() as u32;
+    self.level += 1;
+    let d = Difficulty{m: self.mines(self.level), ..self.d};
+    let seed = self.seed.wrapping_add(self.level as u64);
+    self.field = Self::make(d, &self.cfg, seed);
+    true
+  }
+
+  /// is_over (current field lost)
+  pub fn is_over(&self) -> bool {
+    self.field.is_end() && !self.field.is_success()
+  }
+
+  /// total time including the current field
+  pub fn elapsed(&self) -> Duration { self.total + self.field.elapsed() }
+
+  /// fields won
+  pub fn won(&self) -> u16 { self.level }
+}
@@ -0,0 +1,135 @@
+//! packed cell bit layout
+//!
+
+use std::error::Error;
+use std::fmt;
+
+use crate::MineField;
+use crate::error::MineFieldError;
+
+/// RawCell (stable packed bit layout of a cell in MineField::f)
+///
+/// upper 4bit
+/// - 7 1: force open at ending, 0: normal
+/// - 6 1: flag, 0: as is
+/// - 5 1: question, 0: as is
+/// - 4 1: open, 0: close
+///
+/// lower 4bit
+/// - 0-3 0: no mine around, 1-8: mines around, 9-14: invalid, 15: mine
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RawCell(u8);
+
+/// RawCellError
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawCellError {
+  /// lower 4bit 9-14
+  Value(u8),
+  /// flag and question at once
+  Mark(u8),
+  /// force open without open
+  Force(u8)
+}
+
+/// RawCellError
+impl fmt::Display for RawCellError {
+  /// fmt
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+    RawCellError::Value(u) => write!(f, "invalid value in cell {:#04x}", u),
+    RawCellError::Mark(u) => write!(f, "flag with question in cell {:#04x}", u),
+    RawCellError::Force(u) => write!(f, "force open closed cell {:#04x}", u)
+    }
+  }
+}
+
+/// RawCellError
+impl Error for RawCellError {}
+
+/// RawCell
+impl TryFrom<u8> for RawCell {
+  type Error = RawCellError;
+
+  /// try_from
+  fn try_from(u: u8) -> Result<Self, Self::Error> {
+    let v = u & 0x0f;
+    if v > 8 && v < 15 { return Err(RawCellError::Value(u)); }
+    if u & 0x60 == 0x60 { return Err(RawCellError::Mark(u)); }
+    if u & 0x90 == 0x80 { return Err(RawCellError::Force(u)); }
+    Ok(RawCell(u))
+  }
+}
+
+/// RawCell
+impl From<RawCell> for u8 {
+  /// from
+  fn from(c: RawCell) -> u8 { c.0 }
+}
+
+/// RawCell
+impl RawCell {
+  /// value (lower 4bit)
+  pub fn value(&self) -> u8 { self.0 & 0x0f }
+
+  /// is_mine
+  pub fn is_mine(&self) -> bool { self.value() == 0x0f }
+
+  /// mines around (None for mine)
+  pub fn count(&self) -> Option<u8> {
+    if self.is_mine() { None } else { Some(self.value()) }
+  }
+
+  /// is_forced (force open at ending)
+  pub fn is_forced(&self) -> bool { self.0 & 0x80 != 0 }
+
+  /// is_flag
+  pub fn is_flag(&self) -> bool { self.0 & 0x40 != 0 }
+
+  /// is_question
+  pub fn is_question(&self) -> bool { self.0 & 0x20 != 0 }
+
+  /// is_open
+  pub fn is_open(&self) -> bool { self.0 & 0x10 != 0 }
+}
+
+/// MineField
+impl MineField {
+  /// get cell (r, c) (None: out of the field)
+  pub fn get(&self, r: u16, c: u16) -> Option<RawCell> {
+    self.f.at(r, c).map(RawCell)
+  }
+
+  /// cells (r, c, cell) in row major order
+  pub fn cells(&self) -> impl Iterator<Item=(u16, u16, RawCell)> + '_ {
+    let w = self.f.width().max(1);
+    self.f.as_slice().iter().enumerate()
+      .map(move |(i, &u)| ((i / w) as u16, (i % w) as u16, RawCell(u)))
+  }
+
+  /// rows of cells
+  pub fn rows(&self) ->
+    impl Iterator<Item=impl Iterator<Item=RawCell> + '_> + '_ {
+    self.f.iter().map(|v| v.iter().map(|&u| RawCell(u)))
+  }
+
+  /// neighbor cells (r, c, cell) around (r, c) (playable only)
+  pub fn neighbor_cells(&self, r: u16, c: u16) ->
+    impl Iterator<Item=(u16, u16, RawCell)> + '_ {
+    self.neighbors(r, c)
+      .map(|(j, i)| (j, i, RawCell(self.f[j as usize][i as usize])))
+  }
+
+  /// check (r, c) is in the field
+  pub fn check(&self, r: u16, c: u16) -> Result<(), MineFieldError> {
+    if r < self.h && c < self.w { Ok(()) }
+    else { Err(MineFieldError::OutOfBounds(r, c)) }
+  }
+
+  /// try_open (Ok(false): explosion, no effect on a cell opened or
+  /// flagged)
+  pub fn try_open(&mut self, r: u16, c: u16) -> Result<bool, MineFieldError> {
+    self.check(r, c)?;
+    Ok(self.open(r, c))
+  }
+}
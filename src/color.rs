@@ -0,0 +1,124 @@
+//! colors of the abstract color ids (bgc, fgc) of Theme
+//!
+//! TView is generic over the color type of a backend (`col(id) -> T`),
+//! Palette maps the ids to Color so backends convert faithfully
+//!
+
+use crate::theme::Theme;
+
+/// NamedColor (16 terminal colors)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NamedColor {
+  /// black
+  Black,
+  /// dark red
+  DarkRed,
+  /// dark green
+  DarkGreen,
+  /// dark yellow
+  DarkYellow,
+  /// dark blue
+  DarkBlue,
+  /// dark magenta
+  DarkMagenta,
+  /// dark cyan
+  DarkCyan,
+  /// grey
+  Grey,
+  /// dark grey
+  DarkGrey,
+  /// red
+  Red,
+  /// green
+  Green,
+  /// yellow
+  Yellow,
+  /// blue
+  Blue,
+  /// magenta
+  Magenta,
+  /// cyan
+  Cyan,
+  /// white
+  White
+}
+
+/// NamedColor
+impl NamedColor {
+  /// ansi index 0-15
+  pub fn ansi(&self) -> u8 { *self as u8 }
+
+  /// rgb (xterm defaults)
+  pub fn rgb(&self) -> (u8, u8, u8) {
+    [(0, 0, 0), (205, 0, 0), (0, 205, 0), (205, 205, 0), (0, 0, 238),
+      (205, 0, 205), (0, 205, 205), (229, 229, 229), (127, 127, 127),
+      (255, 0, 0), (0, 255, 0), (255, 255, 0), (92, 92, 255), (255, 0, 255),
+      (0, 255, 255), (255, 255, 255)][self.ansi() as usize]
+  }
+}
+
+/// Color
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+  /// backend palette index
+  Indexed(u16),
+  /// true color
+  Rgb(u8, u8, u8),
+  /// terminal named color
+  Named(NamedColor)
+}
+
+/// Color
+impl Color {
+  /// rgb (None: Indexed)
+  pub fn rgb(&self) -> Option<(u8, u8, u8)> {
+    match self {
+    Color::Indexed(_) => None,
+    Color::Rgb(r, g, b) => Some((*r, *g, *b)),
+    Color::Named(n) => Some(n.rgb())
+    }
+  }
+
+  /// ansi 256 index (rgb on the 6x6x6 color cube)
+  pub fn ansi256(&self) -> u8 {
+    match self {
+    Color::Indexed(n) => *n as u8,
+    Color::Named(n) => n.ansi(),
+    Color::Rgb(r, g, b) => 16 + 36 * (r / 51) + 6 * (g / 51) + b / 51
+    }
+  }
+}
+
+/// Palette (Color of each color id)
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Palette {
+  /// colors by id
+  pub v: Vec<Color>
+}
+
+/// Palette
+impl Palette {
+  /// rgb palette
+  pub fn from_rgb(v: &[(u8, u8, u8)]) -> Self {
+    Palette{v: v.iter().map(|&(r, g, b)| Color::Rgb(r, g, b)).collect()}
+  }
+
+  /// classic (Theme::classic_rgb)
+  pub fn classic() -> Self { Self::from_rgb(&Theme::classic_rgb()) }
+
+  /// colorblind (Theme::colorblind_rgb)
+  pub fn colorblind() -> Self { Self::from_rgb(&Theme::colorblind_rgb()) }
+
+  /// 16 color terminals (ids of Theme::classic)
+  pub fn named() -> Self {
+    use NamedColor::*;
+    Palette{v: [DarkGrey, White, Grey, Black, DarkRed, Yellow, Blue, DarkGreen,
+      Red, DarkBlue, DarkRed, DarkCyan, Black, DarkGrey, Red]
+      .into_iter().map(Color::Named).collect()}
+  }
+
+  /// color of the id (Indexed when out of the palette)
+  pub fn get(&self, n: u16) -> Color {
+    self.v.get(n as usize).copied().unwrap_or(Color::Indexed(n))
+  }
+}
@@ -0,0 +1,203 @@
+//! config
+//!
+//! persisted as `key=value` lines (`#` comment, unknown keys are ignored)
+//!
+
+use std::{fmt, fs, path};
+use std::str::FromStr;
+
+use crate::error::MineFieldError::{self, ParseError};
+
+/// Difficulty
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Difficulty {
+  /// area width
+  pub w: u16,
+  /// area height
+  pub h: u16,
+  /// mines
+  pub m: u16
+}
+
+/// Difficulty
+impl Difficulty {
+  /// beginner 9 x 9 10
+  pub fn beginner() -> Self { Difficulty{w: 9, h: 9, m: 10} }
+
+  /// intermediate 16 x 16 40
+  pub fn intermediate() -> Self { Difficulty{w: 16, h: 16, m: 40} }
+
+  /// expert 30 x 16 99
+  pub fn expert() -> Self { Difficulty{w: 30, h: 16, m: 99} }
+}
+
+/// WinCondition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WinCondition {
+  /// all safe cells opened
+  #[default]
+  Opened,
+  /// all mines correctly flagged (no misplaced flags)
+  Flagged,
+  /// all mines correctly flagged and all safe cells opened
+  Both
+}
+
+/// WinCondition
+impl fmt::Display for WinCondition {
+  /// fmt
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+    WinCondition::Opened => "opened",
+    WinCondition::Flagged => "flagged",
+    WinCondition::Both => "both"
+    })
+  }
+}
+
+/// WinCondition
+impl FromStr for WinCondition {
+  type Err = MineFieldError;
+
+  /// from_str
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Ok(match s {
+    "opened" => WinCondition::Opened,
+    "flagged" => WinCondition::Flagged,
+    "both" => WinCondition::Both,
+    _ => Err(ParseError(format!("win condition: {}", s)))?
+    })
+  }
+}
+
+/// Config
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+  /// use '?' in the mark cycle (closed -> flag -> question -> closed)
+  pub question: bool,
+  /// cursor wraps around at left and right edges
+  pub wrap_x: bool,
+  /// cursor wraps around at top and bottom edges
+  pub wrap_y: bool,
+  /// reveal flood fill wave by wave over ticks
+  pub animate: bool,
+  /// reveal mines one by one over ticks at ending
+  pub slow_reveal: bool,
+  /// lives (mines hit before the explosion, defused while lives remain)
+  pub lives: u8,
+  /// flags budget (None: unlimited)
+  pub max_flags: Option<u16>,
+  /// time budget in seconds (None: no countdown)
+  pub time_limit: Option<u32>,
+  /// fog of war radius around the cursor and opened cells (None: off)
+  pub fog: Option<u16>,
+  /// treasures placed at start
+  pub treasures: u16,
+  /// power ups placed at start
+  pub powerups: u16,
+  /// win condition
+  pub win: WinCondition,
+  /// flag the remaining mines when every other cell is open
+  pub auto_finish: bool,
+  /// hold chords and finish opening cells not deduced safe until confirmed
+  /// (see confirm)
+  pub confirm: bool,
+  /// screen columns per cell (glyph at the left, padded with spaces,
+  /// keep 1 for emoji glyphs which are already 2 columns)
+  pub cell_width: u16
+}
+
+/// Config
+impl Default for Config {
+  /// default
+  fn default() -> Self {
+    Config{question: true, wrap_x: false, wrap_y: false, animate: false,
+      slow_reveal: false, lives: 1, max_flags: None,
+      time_limit: None, fog: None,
+      treasures: 0, powerups: 0, win: WinCondition::Opened,
+      auto_finish: false, confirm: false, cell_width: 1}
+  }
+}
+
+/// Config
+impl Config {
+  /// load
+  pub fn load(p: impl AsRef<path::Path>) -> Result<Self, MineFieldError> {
+    fs::read_to_string(p)?.parse()
+  }
+
+  /// save
+  pub fn save(&self, p: impl AsRef<path::Path>) ->
+    Result<(), MineFieldError> {
+    fs::write(p, self.to_string())?;
+    Ok(())
+  }
+
+  /// set a value by key
+  pub fn set(&mut self, k: &str, v: &str) -> Result<(), MineFieldError> {
+    match k {
+    "question" => self.question = v.parse()?,
+    "wrap_x" => self.wrap_x = v.parse()?,
+    "wrap_y" => self.wrap_y = v.parse()?,
+    "animate" => self.animate = v.parse()?,
+    "slow_reveal" => self.slow_reveal = v.parse()?,
+    "lives" => self.lives = v.parse()?,
+    "max_flags" => self.max_flags =
+      if v.is_empty() { None } else { Some(v.parse()?) },
+    "time_limit" => self.time_limit =
+      if v.is_empty() { None } else { Some(v.parse()?) },
+    "fog" => self.fog = if v.is_empty() { None } else { Some(v.parse()?) },
+    "treasures" => self.treasures = v.parse()?,
+    "powerups" => self.powerups = v.parse()?,
+    "win" => self.win = v.parse()?,
+    "auto_finish" => self.auto_finish = v.parse()?,
+    "confirm" => self.confirm = v.parse()?,
+    "cell_width" => self.cell_width = v.parse()?,
+    _ => () // unknown keys are ignored for forward compatibility
+    }
+    Ok(())
+  }
+}
+
+/// Config
+impl fmt::Display for Config {
+  /// fmt
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "question={}", self.question)?;
+    writeln!(f, "wrap_x={}", self.wrap_x)?;
+    writeln!(f, "wrap_y={}", self.wrap_y)?;
+    writeln!(f, "animate={}", self.animate)?;
+    writeln!(f, "slow_reveal={}", self.slow_reveal)?;
+    writeln!(f, "lives={}", self.lives)?;
+    writeln!(f, "max_flags={}",
+      self.max_flags.map_or(String::new(), |n| n.to_string()))?;
+    writeln!(f, "time_limit={}",
+      self.time_limit.map_or(String::new(), |n| n.to_string()))?;
+    writeln!(f, "fog={}", self.fog.map_or(String::new(), |n| n.to_string()))?;
+    writeln!(f, "treasures={}", self.treasures)?;
+    writeln!(f, "powerups={}", self.powerups)?;
+    writeln!(f, "win={}", self.win)?;
+    writeln!(f, "auto_finish={}", self.auto_finish)?;
+    writeln!(f, "confirm={}", self.confirm)?;
+    writeln!(f, "cell_width={}", self.cell_width)?;
+    Ok(())
+  }
+}
+
+/// Config
+impl FromStr for Config {
+  type Err = MineFieldError;
+
+  /// from_str
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut cfg = Config::default();
+    for l in s.lines() {
+      let l = l.trim();
+      if l.is_empty() || l.starts_with('#') { continue; }
+      let (k, v) = l.split_once('=')
+        .ok_or_else(|| ParseError(format!("config: {}", l)))?;
+      cfg.set(k.trim(), v.trim())?;
+    }
+    Ok(cfg)
+  }
+}
@@ -0,0 +1,86 @@
+//! two phase chords and finish (config confirm)
+//!
+//! a chord or finish opening a cell not deduced safe from the opened
+//! numbers (solver::deduce, flags not trusted) is held as pending and
+//! reported with the risky cells, confirm plays it, cancel drops it
+//!
+
+use crate::MineField;
+use crate::replay::Move;
+use crate::solver::Known;
+
+/// Outcome of a chord or finish asked to confirm
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+  /// played (the result of chord or finish)
+  Done(bool),
+  /// held as pending, cells (r, c) it would open not deduced safe
+  NeedsConfirmation(Vec<(u16, u16)>)
+}
+
+/// MineField
+impl MineField {
+  /// cells (r, c) the move would open (chord at (r, c) or finish, empty
+  /// when it would do nothing)
+  pub fn would_open(&self, m: Move) -> Vec<(u16, u16)> {
+    if self.is_end() || self.paused { return vec![]; }
+    let closed = |&(r, c): &(u16, u16)|
+      !self.is_opened(r, c) && !self.is_flagged(r, c);
+    match m {
+    Move::Chord(r, c) => {
+      if r >= self.h || c >= self.w || !self.is_opened(r, c) { return vec![]; }
+      let v = Self::get_v(self.f[r as usize][c as usize]) as usize;
+      let q = self.neighbors(r, c).collect::<Vec<_>>();
+      if q.iter().filter(|&&(j, i)| self.is_flagged(j, i)).count() != v {
+        return vec![];
+      }
+      q.into_iter().filter(closed).collect()
+    },
+    Move::Finish => {
+      if !self.p || self.flags() + self.defused() != self.m { return vec![]; }
+      (0..self.h).flat_map(|r| (0..self.w).map(move |c| (r, c)))
+        .filter(|&(r, c)| self.is_playable(r, c)).filter(closed).collect()
+    },
+    _ => vec![]
+    }
+  }
+
+  /// risky cells (r, c) the move would open (not deduced safe)
+  pub fn risky(&self, m: Move) -> Vec<(u16, u16)> {
+    let q = self.would_open(m);
+    if q.is_empty() { return q; }
+    let k = self.deduce();
+    q.into_iter()
+      .filter(|&(r, c)| k[r as usize][c as usize] != Known::Safe).collect()
+  }
+
+  /// ask to play the move (held as pending when config confirm is set
+  /// and it is risky, played at once otherwise)
+  fn ask(&mut self, m: Move) -> Outcome {
+    self.pending = None;
+    if self.cfg.confirm {
+      let q = self.risky(m);
+      if !q.is_empty() {
+        self.pending = Some(m);
+        return Outcome::NeedsConfirmation(q);
+      }
+    }
+    Outcome::Done(m.play(self))
+  }
+
+  /// try_chord at the cursor (see ask)
+  pub fn try_chord(&mut self) -> Outcome {
+    self.ask(Move::Chord(self.r, self.c))
+  }
+
+  /// try_finish (see ask)
+  pub fn try_finish(&mut self) -> Outcome { self.ask(Move::Finish) }
+
+  /// confirm the pending move (false: nothing pending or no effect)
+  pub fn confirm(&mut self) -> bool {
+    self.pending.take().is_some_and(|m| m.play(self))
+  }
+
+  /// cancel the pending move (false: nothing pending)
+  pub fn cancel(&mut self) -> bool { self.pending.take().is_some() }
+}
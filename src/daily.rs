@@ -0,0 +1,87 @@
+//! daily puzzle, board fingerprint and board codes
+//!
+//! board code `WxH-M-SEED[-R.C]` (seed 16 hex digits, R.C the first click
+//! when mines are placed, uniform placement)
+//!
+
+use crate::MineField;
+use crate::config::Difficulty;
+use crate::error::MineFieldError::{self, ParseError};
+
+/// FNV-1a 64
+pub fn fnv1a(b: &[u8]) -> u64 {
+  b.iter().fold(0xcbf29ce484222325, |h, &x|
+    (h ^ x as u64).wrapping_mul(0x100000001b3))
+}
+
+/// days in month (Gregorian)
+pub fn days_in_month(y: u16, m: u8) -> u8 {
+  let leap = y.is_multiple_of(4) &&
+    (!y.is_multiple_of(100) || y.is_multiple_of(400));
+  match m {
+  2 if leap => 29,
+  2 => 28,
+  4 | 6 | 9 | 11 => 30,
+  _ => 31
+  }
+}
+
+/// MineField
+impl MineField {
+  /// daily seed of a calendar date (stable, never change)
+  pub fn daily_seed(y: u16, m: u8, d: u8) -> Result<u64, MineFieldError> {
+    if !(1..=12).contains(&m) || d == 0 || d > days_in_month(y, m) {
+      Err(ParseError(format!("daily: invalid date {:04}-{:02}-{:02}",
+        y, m, d)))?
+    }
+    Ok(fnv1a(format!("minefield daily {:04}-{:02}-{:02}", y, m, d).as_bytes()))
+  }
+
+  /// daily puzzle (intermediate, mines placed at once, the center is safe)
+  pub fn daily(y: u16, m: u8, d: u8) -> Result<Self, MineFieldError> {
+    let k = Difficulty::intermediate();
+    let mut f = MineField::from_seed(k.w, k.h, k.m, Self::daily_seed(y, m, d)?);
+    f.goto(k.h / 2, k.w / 2);
+    f.start();
+    Ok(f)
+  }
+
+  /// fingerprint (stable hash of dimensions and mine layout, FNV-1a 64 of
+  /// w, h as u16 le and row major index as u32 le of every mine)
+  pub fn fingerprint(&self) -> u64 {
+    let mut b = vec![];
+    b.extend_from_slice(&self.w.to_le_bytes());
+    b.extend_from_slice(&self.h.to_le_bytes());
+    for (i, &u) in self.f.iter().flatten().enumerate() {
+      if Self::is_mine(u) { b.extend_from_slice(&(i as u32).to_le_bytes()); }
+    }
+    fnv1a(&b)
+  }
+
+  /// board code to share and reproduce the board
+  pub fn board_code(&self) -> String {
+    let s = format!("{}x{}-{}-{:016x}", self.w, self.h, self.m, self.seed);
+    match self.first {
+    Some((r, c)) => format!("{}-{}.{}", s, r, c),
+    None => s
+    }
+  }
+
+  /// from_board_code (mines placed at once when the first click is given)
+  pub fn from_board_code(s: &str) -> Result<Self, MineFieldError> {
+    let e = || ParseError(format!("board code: {}", s));
+    let v = s.trim().split('-').collect::<Vec<_>>();
+    if v.len() < 3 || v.len() > 4 { Err(e())? }
+    let (w, h) = v[0].split_once('x').ok_or_else(e)?;
+    let (w, h): (u16, u16) = (w.parse()?, h.parse()?);
+    let m: u16 = v[1].parse()?;
+    let mut f = MineField::try_from_seed(w, h, m,
+      u64::from_str_radix(v[2], 16)?)?;
+    if let Some(p) = v.get(3) {
+      let (r, c) = p.split_once('.').ok_or_else(e)?;
+      if !f.goto(r.parse()?, c.parse()?) { Err(e())? }
+      f.start();
+    }
+    Ok(f)
+  }
+}
@@ -0,0 +1,73 @@
+//! screen reader friendly text descriptions of the board
+//!
+
+use std::ops::Range;
+
+use crate::MineField;
+
+/// n thing(s)
+fn plural(n: usize, s: &str) -> String {
+  format!("{} {}{}", n, s, if n == 1 { "" } else { "s" })
+}
+
+/// MineField
+impl MineField {
+  /// state word of (r, c) as seen by the player ("outside the field" as
+  /// describe)
+  pub fn describe_state(&self, r: u16, c: u16) -> &'static str {
+    let Some(u) = self.f.at(r, c) else { return "outside the field"; };
+    let u = if self.hidden.contains(&(r, c)) { u & !0x10 } else { u };
+    let v = Self::get_v(u);
+    if self.paused { "paused" }
+    else if !self.is_playable(r, c) { "not playable" }
+    else if self.x == Some((r, c)) { "exploded mine" }
+    else if Self::is_e(u) && Self::is_f(u) && !Self::is_mine(v) { "wrong flag" }
+    else if Self::is_f(u) { "flagged" }
+    else if Self::is_o(u) && Self::is_mine(v) && !Self::is_e(u) {
+      "defused mine"
+    }
+    else if Self::is_o(u) && Self::is_mine(v) { "mine" }
+    else if Self::is_o(u) { "open" }
+    else if Self::is_q(u) && self.cfg.question { "question mark" }
+    else { "unopened" }
+  }
+
+  /// describe (r, c) ("row 3 column 5: open, 2 adjacent mines; ...")
+  pub fn describe(&self, r: u16, c: u16) -> String {
+    if r >= self.h || c >= self.w { return String::from("outside the field"); }
+    let st = self.describe_state(r, c);
+    let mut s = format!("row {} column {}: {}", r + 1, c + 1, st);
+    if st == "open" {
+      let v = Self::get_v(self.f[r as usize][c as usize]) as usize;
+      if v == 0 { s.push_str(", no adjacent mines"); }
+      else { s.push_str(&format!(", {}", plural(v, "adjacent mine"))); }
+      let n = self.neighbors(r, c).filter(|&(j, i)|
+        self.describe_state(j, i) == "unopened").count();
+      let f = self.neighbors(r, c).filter(|&(j, i)|
+        self.is_flagged(j, i)).count();
+      s.push_str(&format!("; {}", plural(n, "unopened neighbor")));
+      if f > 0 { s.push_str(&format!(", {}", plural(f, "flagged neighbor"))); }
+    }
+    s
+  }
+
+  /// describe_region rows r x columns c (clamped to the field) as a summary
+  pub fn describe_region(&self, r: Range<u16>, c: Range<u16>) -> String {
+    let (r, c) = (r.start..r.end.min(self.h), c.start..c.end.min(self.w));
+    if r.is_empty() || c.is_empty() { return String::from("empty region"); }
+    let mut n = [0usize; 3]; // open, unopened, flagged
+    for j in r.clone() {
+      for i in c.clone() {
+        match self.describe_state(j, i) {
+        "not playable" => (),
+        "open" => n[0] += 1,
+        "flagged" => n[2] += 1,
+        _ => n[1] += 1
+        }
+      }
+    }
+    format!("rows {} to {}, columns {} to {}: {} open, {} unopened, {}",
+      r.start + 1, r.end, c.start + 1, c.end, n[0], n[1],
+      plural(n[2], "flag"))
+  }
+}
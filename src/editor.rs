@@ -0,0 +1,99 @@
+//! board editor (mines added and removed on an un-started board)
+//!
+
+use std::fmt;
+
+use crate::MineField;
+use crate::error::MineFieldError;
+
+/// Editor
+pub struct Editor {
+  /// board being designed (mines placed, nothing opened)
+  pub f: MineField
+}
+
+/// Editor
+impl Editor {
+  /// constructor (empty board, see MineField::check_size)
+  pub fn new(w: u16, h: u16) -> Result<Self, MineFieldError> {
+    MineField::check_size(w, h)?;
+    Ok(Editor{f: MineField::try_with_mines(w, h, &[])?})
+  }
+
+  /// is_mine at (r, c) (false: out of the field)
+  pub fn is_mine(&self, r: u16, c: u16) -> bool {
+    self.f.get(r, c).is_some_and(|u| u.is_mine())
+  }
+
+  /// add_mine at (r, c) (see MineField::add_mine)
+  pub fn add_mine(&mut self, r: u16, c: u16) -> bool { self.f.add_mine(r, c) }
+
+  /// remove_mine at (r, c) (see MineField::remove_mine)
+  pub fn remove_mine(&mut self, r: u16, c: u16) -> bool {
+    self.f.remove_mine(r, c)
+  }
+
+  /// mines at (r, c)
+  pub fn mines(&self) -> Vec<(u16, u16)> {
+    (0..self.f.h).flat_map(|r| (0..self.f.w).map(move |c| (r, c)))
+      .filter(|&(r, c)| self.is_mine(r, c)).collect()
+  }
+
+  /// into_minefield to start playing the designed board
+  pub fn into_minefield(self) -> MineField { self.f }
+}
+
+/// MineField
+impl MineField {
+  /// add_mine at (r, c) updating the 8 neighbor counts only
+  /// (false: out of the field, masked, opened, already a mine or no safe
+  /// cell would be left)
+  pub fn add_mine(&mut self, r: u16, c: u16) -> bool {
+    let Some(u) = self.get(r, c) else { return false; };
+    if !self.is_playable(r, c) || u.is_open() || u.is_mine() ||
+      self.m + 1 >= self.playable() { return false; }
+    Self::set_m(&mut self.f[r as usize][c as usize]);
+    self.adjust(r, c, true);
+    self.m += 1;
+    true
+  }
+
+  /// remove_mine at (r, c) updating the 8 neighbor counts only
+  /// (false: no mine there)
+  pub fn remove_mine(&mut self, r: u16, c: u16) -> bool {
+    if !self.get(r, c).is_some_and(|u| u.is_mine()) { return false; }
+    let k = Self::get_k(self.w, self.h, &self.f, r, c);
+    let u = &mut self.f[r as usize][c as usize];
+    *u = (*u & 0xf0) | k;
+    self.adjust(r, c, false);
+    self.m -= 1;
+    true
+  }
+
+  /// adjust neighbor counts around (r, c) incrementally
+  fn adjust(&mut self, r: u16, c: u16, add: bool) -> () {
+    for j in r.saturating_sub(1)..=(r + 1).min(self.h - 1) {
+      for i in c.saturating_sub(1)..=(c + 1).min(self.w - 1) {
+        let u = &mut self.f[j as usize][i as usize];
+        if (j, i) == (r, c) || Self::is_mine(*u) { continue; }
+        if add { *u += 1; } else { *u -= 1; }
+      }
+    }
+  }
+}
+
+/// Editor
+impl fmt::Display for Editor {
+  /// fmt every cell as opened (lower glyphs, masked out as void)
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let g = &self.f.glyphs;
+    for (r, v) in self.f.f.iter().enumerate() {
+      if r > 0 { writeln!(f)?; }
+      for (c, u) in v.iter().enumerate() {
+        if !self.f.is_playable(r as u16, c as u16) { f.write_str(&g.void)?; }
+        else { f.write_str(&g.lower[MineField::get_v(*u) as usize])?; }
+      }
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,83 @@
+//! typed errors
+//!
+
+use std::error::Error;
+use std::{fmt, io, num, str};
+
+/// MineFieldError
+#[derive(Debug)]
+pub enum MineFieldError {
+  /// cell (r, c) out of the field
+  OutOfBounds(u16, u16),
+  /// invalid dimensions (w, h)
+  InvalidDimensions(u16, u16),
+  /// too many mines for the field
+  TooManyMines(u16),
+  /// mine density out of range
+  InvalidDensity(f64),
+  /// error of the view (TView::wr)
+  RenderError(Box<dyn Error>),
+  /// parse error
+  ParseError(String),
+  /// io error
+  IoError(io::Error)
+}
+
+/// MineFieldError
+impl fmt::Display for MineFieldError {
+  /// fmt
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+    MineFieldError::OutOfBounds(r, c) =>
+      write!(f, "cell ({}, {}) is out of bounds", r, c),
+    MineFieldError::InvalidDimensions(w, h) =>
+      write!(f, "invalid dimensions {}x{}", w, h),
+    MineFieldError::TooManyMines(m) => write!(f, "too many mines {}", m),
+    MineFieldError::InvalidDensity(d) =>
+      write!(f, "density {} out of range", d),
+    MineFieldError::RenderError(e) => write!(f, "render: {}", e),
+    MineFieldError::ParseError(s) => write!(f, "parse: {}", s),
+    MineFieldError::IoError(e) => write!(f, "io: {}", e)
+    }
+  }
+}
+
+/// MineFieldError
+impl Error for MineFieldError {
+  /// source
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    match self {
+    MineFieldError::RenderError(e) => Some(e.as_ref()),
+    MineFieldError::IoError(e) => Some(e),
+    _ => None
+    }
+  }
+}
+
+/// MineFieldError (errors of TView::wr)
+impl From<Box<dyn Error>> for MineFieldError {
+  /// from
+  fn from(e: Box<dyn Error>) -> Self { MineFieldError::RenderError(e) }
+}
+
+/// MineFieldError
+impl From<io::Error> for MineFieldError {
+  /// from
+  fn from(e: io::Error) -> Self { MineFieldError::IoError(e) }
+}
+
+/// MineFieldError
+impl From<num::ParseIntError> for MineFieldError {
+  /// from
+  fn from(e: num::ParseIntError) -> Self {
+    MineFieldError::ParseError(e.to_string())
+  }
+}
+
+/// MineFieldError
+impl From<str::ParseBoolError> for MineFieldError {
+  /// from
+  fn from(e: str::ParseBoolError) -> Self {
+    MineFieldError::ParseError(e.to_string())
+  }
+}
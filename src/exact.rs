@@ -0,0 +1,197 @@
+//! exact probabilities by enumerating mine arrangements of the border
+//!
+//! closed cells not deduced by solver::deduce next to an opened number
+//! (border) are enumerated by backtracking on the numbers around them,
+//! each arrangement of k mines weighted by the ways C(I, L - k) to place
+//! the L mines left in the I other closed cells
+//!
+
+use crate::MineField;
+use crate::solver::Known;
+
+/// max border cells enumerated by default
+pub const MAX_BORDER: usize = 30;
+
+/// Exact
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exact {
+  /// mine probabilities [r][c] of closed playable cells (None: opened or
+  /// masked)
+  pub p: Vec<Vec<Option<f64>>>,
+  /// border cells enumerated
+  pub border: usize,
+  /// consistent arrangements of the border (unweighted)
+  pub arrangements: u64,
+  /// click minimizing the loss chance (None: nothing closed)
+  pub best: Option<(u16, u16)>,
+  /// mine probability of best
+  pub risk: f64
+}
+
+/// Border (closed cells of a position split for the enumeration)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Border {
+  /// knowledge [r][c] by solver::deduce
+  pub k: Vec<Vec<Known>>,
+  /// border cells not deduced
+  pub cells: Vec<(u16, u16)>,
+  /// other closed cells not deduced
+  pub interior: Vec<(u16, u16)>,
+  /// constraints of the numbers (border cell indices, mines needed)
+  pub cs: Vec<(Vec<usize>, usize)>,
+  /// constraint indices of each border cell
+  pub of: Vec<Vec<usize>>,
+  /// closed cells deduced as mines
+  pub known: usize,
+  /// mines left for the border and the interior
+  pub left: usize
+}
+
+/// ln C(n, k)
+fn ln_choose(n: u32, k: u32) -> f64 {
+  (1..=k).map(|i| ((n - k + i) as f64 / i as f64).ln()).sum()
+}
+
+/// Search state of the backtracking
+struct Search<'a> {
+  /// constraints (cell indices, mines needed)
+  cs: &'a [(Vec<usize>, usize)],
+  /// constraints of each cell
+  of: &'a [Vec<usize>],
+  /// mines left
+  left: usize,
+  /// assignment
+  a: Vec<bool>,
+  /// mines assigned per constraint
+  mines: Vec<usize>,
+  /// cells unassigned per constraint
+  open: Vec<usize>,
+  /// arrangements per mines count
+  n: Vec<u64>,
+  /// mine counts per mines count per cell
+  k: Vec<Vec<u64>>
+}
+
+/// Search
+impl Search<'_> {
+  /// assign cell i and deeper (k: mines assigned so far)
+  fn run(&mut self, i: usize, k: usize) -> () {
+    if i == self.a.len() {
+      self.n[k] += 1;
+      for (j, &b) in self.a.iter().enumerate() {
+        if b { self.k[k][j] += 1; }
+      }
+      return;
+    }
+    let of = &self.of[i];
+    for b in [false, true] {
+      if b && k >= self.left { continue; }
+      let ok = of.iter().all(|&j| {
+        let m = self.mines[j] + b as usize;
+        m <= self.cs[j].1 && m + self.open[j] > self.cs[j].1
+      });
+      if !ok { continue; }
+      self.a[i] = b;
+      for &j in of {
+        self.mines[j] += b as usize;
+        self.open[j] -= 1;
+      }
+      self.run(i + 1, k + b as usize);
+      for &j in of {
+        self.mines[j] -= b as usize;
+        self.open[j] += 1;
+      }
+    }
+    self.a[i] = false;
+  }
+}
+
+/// MineField
+impl MineField {
+  /// border of the position (see Border)
+  pub fn border(&self) -> Border {
+    let k = self.deduce();
+    let (mut known, mut border, mut interior) = (0, vec![], vec![]);
+    let mut id = vec![vec![usize::MAX; self.w as usize]; self.h as usize];
+    for (r, c, u) in self.cells() {
+      if u.is_open() || !self.is_playable(r, c) { continue; }
+      let (ru, cu) = (r as usize, c as usize);
+      match k[ru][cu] {
+      Known::Safe => (),
+      Known::Mine => known += 1,
+      Known::Unknown if self.neighbors(r, c).any(|(j, i)|
+        self.is_opened(j, i) && !self.get(j, i).is_some_and(|u| u.is_mine())
+      ) => { id[ru][cu] = border.len(); border.push((r, c)); },
+      Known::Unknown => interior.push((r, c))
+      }
+    }
+    let left = (self.m as usize)
+      .saturating_sub(known + self.defused() as usize);
+    let mut cs = vec![];
+    for (r, c, u) in self.cells() {
+      if !u.is_open() || u.is_mine() { continue; }
+      let q = self.neighbors(r, c).filter_map(|(j, i)|
+        Some(id[j as usize][i as usize]).filter(|&n| n != usize::MAX))
+        .collect::<Vec<_>>();
+      if q.is_empty() { continue; }
+      let m = self.neighbors(r, c)
+        .filter(|&(j, i)| k[j as usize][i as usize] == Known::Mine).count();
+      cs.push((q, (u.value() as usize).saturating_sub(m)));
+    }
+    let mut of = vec![vec![]; border.len()];
+    for (j, (q, _)) in cs.iter().enumerate() {
+      for &i in q { of[i].push(j); }
+    }
+    Border{k, cells: border, interior, cs, of, known, left}
+  }
+
+  /// exact probabilities and the safest click (None: not started or more
+  /// than max border cells)
+  pub fn enumerate(&self, max: usize) -> Option<Exact> {
+    if !self.p { return None; }
+    let Border{k, cells: border, interior, cs, of, left, ..} = self.border();
+    if border.len() > max { return None; }
+    let mut p = vec![vec![None; self.w as usize]; self.h as usize];
+    for (r, c, u) in self.cells() {
+      if u.is_open() || !self.is_playable(r, c) { continue; }
+      let (ru, cu) = (r as usize, c as usize);
+      match k[ru][cu] {
+      Known::Safe => p[ru][cu] = Some(0.0),
+      Known::Mine => p[ru][cu] = Some(1.0),
+      Known::Unknown => ()
+      }
+    }
+    let b = border.len();
+    let open = cs.iter().map(|(q, _)| q.len()).collect();
+    let mut s = Search{cs: &cs, of: &of, left, a: vec![false; b],
+      mines: vec![0; cs.len()], open, n: vec![0; b + 1],
+      k: vec![vec![0; b]; b + 1]};
+    s.run(0, 0);
+    let i = interior.len() as u32;
+    let ws = (0..=b).map(|m| if s.n[m] == 0 || m > left ||
+      left - m > i as usize { None }
+      else { Some(ln_choose(i, (left - m) as u32)) }).collect::<Vec<_>>();
+    let top = ws.iter().flatten().fold(f64::MIN, |a, &w| a.max(w));
+    let ws = ws.iter().map(|w| w.map_or(0.0, |w| (w - top).exp()))
+      .collect::<Vec<_>>();
+    let total: f64 = (0..=b).map(|m| s.n[m] as f64 * ws[m]).sum();
+    if total > 0.0 {
+      for (j, &(r, c)) in border.iter().enumerate() {
+        let x: f64 = (0..=b).map(|m| s.k[m][j] as f64 * ws[m]).sum();
+        p[r as usize][c as usize] = Some(x / total);
+      }
+      if i > 0 {
+        let x: f64 = (0..=b).map(|m| s.n[m] as f64 * ws[m] *
+          left.saturating_sub(m) as f64 / i as f64).sum();
+        for &(r, c) in &interior {
+          p[r as usize][c as usize] = Some(x / total);
+        }
+      }
+    }
+    let best = self.cells().filter_map(|(r, c, _)|
+      p[r as usize][c as usize].map(|x| (x, (r, c))))
+      .min_by(|a, b| a.0.total_cmp(&b.0));
+    Some(Exact{p, border: b, arrangements: s.n.iter().sum(),
+      best: best.map(|x| x.1), risk: best.map_or(0.0, |x| x.0)})
+  }
+}
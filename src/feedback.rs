@@ -0,0 +1,45 @@
+//! feedback hooks (bell, sounds) called by the core at game events
+//!
+
+use std::io::Write;
+
+use crate::treasure::Item;
+
+/// trait Feedback (all hooks default to no-op)
+pub trait Feedback {
+  /// on_open n cells opened from (r, c)
+  fn on_open(&mut self, _r: u16, _c: u16, _n: u16) -> () {}
+  /// on_item found at (r, c)
+  fn on_item(&mut self, _r: u16, _c: u16, _item: Item) -> () {}
+  /// on_flag (r, c) flagged (true) or unflagged (false)
+  fn on_flag(&mut self, _r: u16, _c: u16, _b: bool) -> () {}
+  /// on_defuse mine hit at (r, c) while lives remain
+  fn on_defuse(&mut self, _r: u16, _c: u16) -> () {}
+  /// on_explode at (r, c)
+  fn on_explode(&mut self, _r: u16, _c: u16) -> () {}
+  /// on_expire time budget
+  fn on_expire(&mut self) -> () {}
+  /// on_win
+  fn on_win(&mut self) -> () {}
+  /// on_restart same board (true) or new game (false)
+  fn on_restart(&mut self, _same: bool) -> () {}
+}
+
+/// Bell (rings BEL on explode and win)
+pub struct Bell<W: Write>(pub W);
+
+/// Bell
+impl<W: Write> Bell<W> {
+  /// ring
+  pub fn ring(&mut self) -> () {
+    let _ = self.0.write_all(b"\x07").and_then(|_| self.0.flush());
+  }
+}
+
+/// Bell
+impl<W: Write> Feedback for Bell<W> {
+  /// on_explode
+  fn on_explode(&mut self, _r: u16, _c: u16) -> () { self.ring(); }
+  /// on_win
+  fn on_win(&mut self) -> () { self.ring(); }
+}
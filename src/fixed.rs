@@ -0,0 +1,228 @@
+//! fixed size board without heap allocation (const generics)
+//!
+//! the rules of opening, flagging and chording are written once in trait
+//! Logic against Storage, used by MineFieldConst (cells in an array) and
+//! MineField alike (rectangular boards, no mask, timer nor history)
+//!
+
+use crate::MineField;
+use crate::grid::Grid;
+use crate::rng::Rng;
+use crate::storage::Storage;
+
+/// ConstGrid (cells in a fixed array H x W)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstGrid<const W: usize, const H: usize> {
+  /// cells [r][c]
+  pub a: [[u8; W]; H]
+}
+
+/// ConstGrid
+impl<const W: usize, const H: usize> Default for ConstGrid<W, H> {
+  /// default (all close)
+  fn default() -> Self { ConstGrid{a: [[0; W]; H]} }
+}
+
+/// Storage for ConstGrid
+impl<const W: usize, const H: usize> Storage for ConstGrid<W, H> {
+  /// with_size (must be W x H)
+  fn with_size(w: u16, h: u16) -> Self {
+    assert!((w as usize, h as usize) == (W, H), "size must be W x H");
+    Self::default()
+  }
+  /// width
+  fn width(&self) -> u16 { W as u16 }
+  /// height
+  fn height(&self) -> u16 { H as u16 }
+  /// cell
+  fn cell(&self, r: u16, c: u16) -> u8 { self.a[r as usize][c as usize] }
+  /// set_cell
+  fn set_cell(&mut self, r: u16, c: u16, u: u8) -> () {
+    self.a[r as usize][c as usize] = u;
+  }
+}
+
+/// trait Logic (game rules on a storage, status bits as MineField::s)
+pub trait Logic {
+  /// storage
+  type S: Storage;
+  /// storage
+  fn store(&self) -> &Self::S;
+  /// storage mutable
+  fn store_mut(&mut self) -> &mut Self::S;
+  /// status (0x8000 explosion, 0x4000 success, opened count)
+  fn counter(&self) -> u16;
+  /// status mutable
+  fn counter_mut(&mut self) -> &mut u16;
+  /// mines
+  fn mine_count(&self) -> u16;
+
+  /// opened count
+  fn opened_count(&self) -> u16 { self.counter() & 0x3fff }
+
+  /// ended (explosion or success)
+  fn ended(&self) -> bool { self.counter() >= 0x4000 }
+
+  /// won
+  fn won(&self) -> bool { self.counter() & 0x4000 != 0 }
+
+  /// exploded
+  fn exploded(&self) -> bool { self.counter() & 0x8000 != 0 }
+
+  /// count neighbors of every cell not a mine from the mine bits
+  fn count_all(&mut self) -> () {
+    let s = self.store_mut();
+    let (w, h) = (s.width(), s.height());
+    for r in 0..h {
+      for c in 0..w {
+        let u = s.cell(r, c);
+        if u & 0x0f == 0x0f { continue; }
+        let n = (r.saturating_sub(1)..=(r + 1).min(h - 1)).map(|j|
+          (c.saturating_sub(1)..=(c + 1).min(w - 1))
+          .filter(|&i| s.cell(j, i) & 0x0f == 0x0f).count()).sum::<usize>();
+        s.set_cell(r, c, (u & 0xf0) | n as u8);
+      }
+    }
+  }
+
+  /// open_at a closed cell not flagged, zeros flooded by sweeps without a
+  /// queue (false: explosion)
+  fn open_at(&mut self, r: u16, c: u16) -> bool {
+    if self.ended() { return true; }
+    let u = self.store().cell(r, c);
+    if u & 0x50 != 0 { return true; }
+    if u & 0x0f == 0x0f {
+      self.store_mut().set_cell(r, c, u | 0x10);
+      *self.counter_mut() |= 0x8000;
+      return false;
+    }
+    self.store_mut().set_cell(r, c, u | 0x10);
+    let mut n = 1;
+    let (w, h) = (self.store().width(), self.store().height());
+    let mut changed = u & 0x0f == 0;
+    while changed {
+      changed = false;
+      for j in 0..h {
+        for i in 0..w {
+          let s = self.store_mut();
+          let v = s.cell(j, i);
+          if v & 0x50 != 0 || v & 0x0f == 0x0f { continue; }
+          let z = (j.saturating_sub(1)..=(j + 1).min(h - 1)).any(|y|
+            (i.saturating_sub(1)..=(i + 1).min(w - 1))
+            .any(|x| s.cell(y, x) & 0x1f == 0x10));
+          if !z { continue; }
+          s.set_cell(j, i, v | 0x10);
+          n += 1;
+          changed = true;
+        }
+      }
+    }
+    *self.counter_mut() += n;
+    let (m, o) = (self.mine_count() as u32, self.opened_count() as u32);
+    if o + m == w as u32 * h as u32 { *self.counter_mut() |= 0x4000; }
+    true
+  }
+
+  /// flag_at toggle on a closed cell (false: opened or ended)
+  fn flag_at(&mut self, r: u16, c: u16) -> bool {
+    let u = self.store().cell(r, c);
+    if self.ended() || u & 0x10 != 0 { return false; }
+    self.store_mut().set_cell(r, c, (u ^ 0x40) & !0x20);
+    true
+  }
+
+  /// chord_at an opened number with as many flags around (false:
+  /// explosion)
+  fn chord_at(&mut self, r: u16, c: u16) -> bool {
+    let s = self.store();
+    let (w, h, u) = (s.width(), s.height(), s.cell(r, c));
+    if u & 0x10 == 0 { return true; }
+    let (rs, re) = (r.saturating_sub(1), (r + 1).min(h - 1));
+    let (cs, ce) = (c.saturating_sub(1), (c + 1).min(w - 1));
+    let f = (rs..=re).map(|j| (cs..=ce)
+      .filter(|&i| s.cell(j, i) & 0x40 != 0).count()).sum::<usize>();
+    if f != (u & 0x0f) as usize { return true; }
+    let mut b = true;
+    for j in rs..=re {
+      for i in cs..=ce { b &= self.open_at(j, i); }
+    }
+    b
+  }
+}
+
+/// MineFieldConst (W x H board in a fixed array, no heap)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MineFieldConst<const W: usize, const H: usize> {
+  /// status (see Logic::counter)
+  pub s: u16,
+  /// mines
+  pub m: u16,
+  /// cells
+  pub f: ConstGrid<W, H>
+}
+
+/// MineFieldConst
+impl<const W: usize, const H: usize> MineFieldConst<W, H> {
+  /// constructor with mines at (r, c) (duplicates ignored)
+  pub fn with_mines(q: &[(u16, u16)]) -> Self {
+    let mut b = MineFieldConst{s: 0, m: 0, f: ConstGrid::default()};
+    for &(r, c) in q {
+      if b.f.cell(r, c) == 0x0f { continue; }
+      b.f.set_cell(r, c, 0x0f);
+      b.m += 1;
+    }
+    b.count_all();
+    b
+  }
+
+  /// constructor with m mines by the seed (at most W x H - 1, not the
+  /// placement of MineField)
+  pub fn from_seed(m: u16, seed: u64) -> Self {
+    let n = (W * H) as u32;
+    let mut b = MineFieldConst{s: 0, m: m.min(n.saturating_sub(1) as u16),
+      f: ConstGrid::default()};
+    let mut g = Rng::new(seed);
+    let mut k = 0;
+    while k < b.m {
+      let i = g.below(n) as usize;
+      let (r, c) = ((i / W) as u16, (i % W) as u16);
+      if b.f.cell(r, c) == 0x0f { continue; }
+      b.f.set_cell(r, c, 0x0f);
+      k += 1;
+    }
+    b.count_all();
+    b
+  }
+}
+
+/// trait Logic for MineFieldConst
+impl<const W: usize, const H: usize> Logic for MineFieldConst<W, H> {
+  type S = ConstGrid<W, H>;
+
+  /// store
+  fn store(&self) -> &Self::S { &self.f }
+  /// store_mut
+  fn store_mut(&mut self) -> &mut Self::S { &mut self.f }
+  /// counter
+  fn counter(&self) -> u16 { self.s }
+  /// counter_mut
+  fn counter_mut(&mut self) -> &mut u16 { &mut self.s }
+  /// mine_count
+  fn mine_count(&self) -> u16 { self.m }
+}
+
+/// trait Logic for MineField
+impl Logic for MineField {
+  type S = Grid;
+
+  /// store
+  fn store(&self) -> &Self::S { &self.f }
+  /// store_mut
+  fn store_mut(&mut self) -> &mut Self::S { &mut self.f }
+  /// counter
+  fn counter(&self) -> u16 { self.s }
+  /// counter_mut
+  fn counter_mut(&mut self) -> &mut u16 { &mut self.s }
+  /// mine_count
+  fn mine_count(&self) -> u16 { self.m }
+}
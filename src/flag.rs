@@ -0,0 +1,74 @@
+//! flag toggle with a limited flag budget
+//!
+
+use std::error::Error;
+use std::fmt;
+
+use crate::MineField;
+use crate::replay::Move;
+
+/// FlagError
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagError {
+  /// game over
+  Ended,
+  /// paused
+  Paused,
+  /// cell already opened
+  Opened,
+  /// cell masked out (not playable)
+  Masked,
+  /// no flags left of the budget
+  Budget(u16)
+}
+
+/// FlagError
+impl fmt::Display for FlagError {
+  /// fmt
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+    FlagError::Ended => write!(f, "game is over"),
+    FlagError::Paused => write!(f, "game is paused"),
+    FlagError::Opened => write!(f, "cell is already opened"),
+    FlagError::Masked => write!(f, "cell is not playable"),
+    FlagError::Budget(n) => write!(f, "all {} flags are used", n)
+    }
+  }
+}
+
+/// FlagError
+impl Error for FlagError {}
+
+/// MineField
+impl MineField {
+  /// flags left of cfg.max_flags (None: unlimited)
+  pub fn flags_left(&self) -> Option<u16> {
+    self.cfg.max_flags.map(|n| n.saturating_sub(self.flags()))
+  }
+
+  /// toggle_flag at the cursor (Ok(true): flagged, Ok(false): unflagged)
+  pub fn toggle_flag(&mut self) -> Result<bool, FlagError> {
+    if self.is_end() { return Err(FlagError::Ended); }
+    if self.paused { return Err(FlagError::Paused); }
+    self.record(Move::Flag(self.r, self.c));
+    let b = self.toggle();
+    if b.is_ok() { self.ck.flags += 1; } else { self.ck.wasted += 1; }
+    b
+  }
+
+  /// toggle flag at the cursor on a game in play
+  fn toggle(&mut self) -> Result<bool, FlagError> {
+    let (r, c) = (self.r, self.c);
+    if self.is_opened(r, c) { return Err(FlagError::Opened); }
+    if !self.is_playable(r, c) { return Err(FlagError::Masked); }
+    let b = !self.is_flagged(r, c);
+    if b && self.flags_left() == Some(0) {
+      return Err(FlagError::Budget(self.cfg.max_flags.unwrap_or(0)));
+    }
+    let u = &mut self.f[r as usize][c as usize];
+    if b { *u = (*u & !0x20) | 0x40; } else { *u &= !0x40; }
+    if let Some(fb) = &mut self.fb { fb.on_flag(r, c, b); }
+    self.check_win();
+    Ok(b)
+  }
+}
@@ -0,0 +1,61 @@
+//! frame snapshot for external renderers (exactly what refresh draws)
+//!
+
+use crate::MineField;
+use crate::error::MineFieldError;
+use crate::tile::Tile;
+
+/// FrameCell
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameCell {
+  /// glyph
+  pub glyph: String,
+  /// background color
+  pub bgc: u16,
+  /// foreground color
+  pub fgc: u16,
+  /// style (see style)
+  pub st: u16
+}
+
+/// Frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+  /// viewport width in cells
+  pub w: u16,
+  /// viewport height in cells
+  pub h: u16,
+  /// screen columns per cell
+  pub cw: u16,
+  /// cells [y][x] of the viewport
+  pub cells: Vec<Vec<FrameCell>>,
+  /// cursor (x, y) in the viewport (None: out of the viewport)
+  pub cursor: Option<(u16, u16)>,
+  /// status line text
+  pub status: String,
+  /// tiles [y][x] of the viewport (None: glyphs only, see frame_tiles)
+  pub tiles: Option<Vec<Vec<Tile>>>
+}
+
+/// MineField
+impl MineField {
+  /// frame snapshot of the viewport
+  pub fn frame(&self) -> Result<Frame, MineFieldError> {
+    let cells = (0..self.vp.h).map(|y| (0..self.vp.w).map(|x| {
+      let (r, c) = (self.vp.y + y, self.vp.x + x);
+      let (glyph, bgc, fgc) = self.compose(r, c, x, y)?;
+      Ok(FrameCell{glyph, bgc, fgc, st: self.style(r, c)})
+    }).collect()).collect::<Result<_, MineFieldError>>()?;
+    let x = self.c.wrapping_sub(self.vp.x);
+    let y = self.r.wrapping_sub(self.vp.y);
+    let cursor = if x < self.vp.w && y < self.vp.h { Some((x, y)) }
+      else { None };
+    Ok(Frame{w: self.vp.w, h: self.vp.h, cw: self.cw(), cells, cursor,
+      status: self.status(), tiles: None})
+  }
+
+  /// frame snapshot of the viewport with the tiles
+  pub fn frame_tiles(&self) -> Result<Frame, MineFieldError> {
+    Ok(Frame{tiles: Some(self.tiles()?), ..self.frame()?})
+  }
+}
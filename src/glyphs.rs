@@ -0,0 +1,87 @@
+//! glyph tables indexed by 4bit upper / lower of a cell (see MineField::c)
+//!
+
+/// Glyphs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Glyphs {
+  /// 4 bit upper (0: close, 1: explosion, 2-3: question, 4-7: flag,
+  /// 8-15: unused, the cursor is drawn by colors)
+  pub upper: Vec<String>,
+  /// 4 bit lower (0: no mine around, 1-8: num, 9-14: skip, 15: mine)
+  pub lower: Vec<String>,
+  /// concealed cell while paused
+  pub pause: String,
+  /// message while paused (one char per cell on the middle row)
+  pub pause_msg: String,
+  /// misplaced flag at ending
+  pub wrong: String,
+  /// mine defused by a life
+  pub defused: String,
+  /// cell hidden by the fog of war
+  pub fog: String,
+  /// cell masked out of the board shape
+  pub void: String
+}
+
+/// Glyphs
+impl Default for Glyphs {
+  /// default
+  fn default() -> Self { Glyphs::ascii() }
+}
+
+/// Glyphs
+impl Glyphs {
+  /// constructor (multi char glyphs are allowed)
+  pub fn new(upper: [&str; 16], lower: [&str; 16]) -> Self {
+    Glyphs{upper: upper.iter().map(|s| s.to_string()).collect(),
+      lower: lower.iter().map(|s| s.to_string()).collect(),
+      pause: String::from(" "), pause_msg: String::from("PAUSED"),
+      wrong: String::from("X"), defused: String::from("%"),
+      fog: String::from("~"), void: String::from(" ")}
+  }
+
+  /// constructor from tables of single char glyphs
+  pub fn from_chars(upper: &str, lower: &str) -> Self {
+    let t = |s: &str| s.chars().map(|c| c.to_string()).collect::<Vec<_>>();
+    let (u, l) = (t(upper), t(lower));
+    assert!(u.len() == 16 && l.len() == 16, "glyph tables must be 16 chars");
+    Glyphs{upper: u, lower: l,
+      pause: String::from(" "), pause_msg: String::from("PAUSED"),
+      wrong: String::from("X"), defused: String::from("%"),
+      fog: String::from("~"), void: String::from(" ")}
+  }
+
+  /// ascii
+  pub fn ascii() -> Self {
+    Glyphs::from_chars("L*??PPPP++++++++", "_12345678......@")
+  }
+
+  /// high contrast (every state distinct without colors)
+  pub fn high_contrast() -> Self {
+    Glyphs::from_chars("#!??FFFF@@@@@@@@", ".12345678      *")
+  }
+
+  /// box drawing and geometric shapes
+  pub fn boxes() -> Self {
+    let mut g = Glyphs::from_chars("▒✱??▶▶▶▶████████", "·12345678······◆");
+    g.wrong = String::from("✗");
+    g.defused = String::from("◇");
+    g.fog = String::from("░");
+    g
+  }
+
+  /// emoji (2 columns for each glyph)
+  pub fn emoji() -> Self {
+    let (c, q, f) = ("🔲", "❓", "🚩");
+    let mut g = Glyphs::new(["⬜", "💥", q, q, f, f, f, f, c, c, c, c, c, c, c, c],
+      ["　", "１", "２", "３", "４", "５", "６", "７", "８",
+       "・", "・", "・", "・", "・", "・", "💣"]);
+    g.pause = String::from("　");
+    g.pause_msg = String::from("ＰＡＵＳＥＤ");
+    g.wrong = String::from("❌");
+    g.defused = String::from("🛡");
+    g.fog = String::from("⬛");
+    g.void = String::from("　");
+    g
+  }
+}
@@ -0,0 +1,106 @@
+//! flat contiguous cell storage
+//!
+//! cells in a single `Vec<u8>` (row major, index r * w + c), rows are
+//! still reachable as slices by `g[r][c]` and `iter()` for compatibility
+//! with the former `Vec<Vec<u8>>` (prefer at, set and the MineField
+//! accessors)
+//!
+
+use std::ops::{Index, IndexMut};
+
+/// Grid
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Grid {
+  /// width
+  w: usize,
+  /// cells row major
+  v: Vec<u8>
+}
+
+/// Grid
+impl Grid {
+  /// constructor (all 0)
+  pub fn new(w: u16, h: u16) -> Self {
+    Grid{w: w as usize, v: vec![0; w as usize * h as usize]}
+  }
+
+  /// width
+  pub fn width(&self) -> usize { self.w }
+
+  /// rows count
+  pub fn len(&self) -> usize { self.v.len().checked_div(self.w).unwrap_or(0) }
+
+  /// is_empty
+  pub fn is_empty(&self) -> bool { self.v.is_empty() }
+
+  /// index of (r, c)
+  pub fn idx(&self, r: u16, c: u16) -> usize {
+    r as usize * self.w + c as usize
+  }
+
+  /// cell at (r, c) (None: out of the grid)
+  pub fn at(&self, r: u16, c: u16) -> Option<u8> {
+    if c as usize >= self.w { return None; }
+    self.v.get(self.idx(r, c)).copied()
+  }
+
+  /// set cell at (r, c)
+  pub fn set(&mut self, r: u16, c: u16, u: u8) -> () {
+    let i = self.idx(r, c);
+    self.v[i] = u;
+  }
+
+  /// cells row major
+  pub fn as_slice(&self) -> &[u8] { &self.v }
+
+  /// cells row major mutable
+  pub fn as_mut_slice(&mut self) -> &mut [u8] { &mut self.v }
+
+  /// rows (compatibility view)
+  pub fn iter(&self) -> std::slice::Chunks<'_, u8> {
+    self.v.chunks(self.w.max(1))
+  }
+
+  /// rows mutable (compatibility view)
+  pub fn iter_mut(&mut self) -> std::slice::ChunksMut<'_, u8> {
+    self.v.chunks_mut(self.w.max(1))
+  }
+
+  /// rows copied (former layout)
+  pub fn to_rows(&self) -> Vec<Vec<u8>> {
+    self.iter().map(|r| r.to_vec()).collect()
+  }
+}
+
+/// Grid
+impl Index<usize> for Grid {
+  type Output = [u8];
+
+  /// row r
+  fn index(&self, r: usize) -> &[u8] { &self.v[r * self.w..(r + 1) * self.w] }
+}
+
+/// Grid
+impl IndexMut<usize> for Grid {
+  /// row r
+  fn index_mut(&mut self, r: usize) -> &mut [u8] {
+    &mut self.v[r * self.w..(r + 1) * self.w]
+  }
+}
+
+/// Grid (from the former layout, rows of the same length)
+impl From<Vec<Vec<u8>>> for Grid {
+  /// from
+  fn from(v: Vec<Vec<u8>>) -> Self {
+    let w = v.first().map_or(0, |r| r.len());
+    Grid{w, v: v.into_iter().flatten().collect()}
+  }
+}
+
+/// Grid
+impl PartialEq<Vec<Vec<u8>>> for Grid {
+  /// eq
+  fn eq(&self, o: &Vec<Vec<u8>>) -> bool {
+    self.len() == o.len() && self.iter().zip(o).all(|(a, b)| a == b.as_slice())
+  }
+}
@@ -0,0 +1,78 @@
+//! headless renderers for tests and simulations
+//!
+
+use std::error::Error;
+
+use mvc_rs::{TPacket, TView};
+
+use crate::TViewRun;
+
+/// NullWR (draws nothing)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NullWR;
+
+/// trait TView for NullWR
+impl TView<u16> for NullWR {
+  /// wr
+  fn wr(&mut self, _p: impl TPacket) -> Result<(), Box<dyn Error>> { Ok(()) }
+  /// reg
+  fn reg(&mut self, _c: Vec<u16>) -> () {}
+  /// col
+  fn col(&self, n: u16) -> u16 { n }
+}
+
+/// trait TViewRun for NullWR
+impl TViewRun<u16> for NullWR {}
+
+/// BufferWR (records the last glyph and colors per cell, a run of glyphs
+/// split into cells)
+#[derive(Debug, Clone, Default)]
+pub struct BufferWR {
+  /// buffer [y][x] (glyph, bgc, fgc)
+  pub b: Vec<Vec<Option<(String, u16, u16)>>>,
+  /// count of wr
+  pub n: usize
+}
+
+/// BufferWR
+impl BufferWR {
+  /// get (x, y)
+  pub fn get(&self, x: u16, y: u16) -> Option<&(String, u16, u16)> {
+    self.b.get(y as usize)?.get(x as usize)?.as_ref()
+  }
+
+  /// glyph (x, y) or ' '
+  pub fn glyph(&self, x: u16, y: u16) -> &str {
+    self.get(x, y).map_or(" ", |p| p.0.as_str())
+  }
+
+  /// lines of glyphs
+  pub fn lines(&self) -> Vec<String> {
+    self.b.iter().map(|v| v.iter().map(|p|
+      p.as_ref().map_or(" ", |p| p.0.as_str())).collect()).collect()
+  }
+}
+
+/// trait TView for BufferWR
+impl TView<u16> for BufferWR {
+  /// wr
+  fn wr(&mut self, p: impl TPacket) -> Result<(), Box<dyn Error>> {
+    let v = p.to_vec();
+    let (x, y) = (v[0] as usize, v[1] as usize);
+    if self.b.len() <= y { self.b.resize(y + 1, vec![]); }
+    let row = &mut self.b[y];
+    for (i, ch) in p.as_str().chars().enumerate() {
+      if row.len() <= x + i { row.resize(x + i + 1, None); }
+      row[x + i] = Some((ch.to_string(), v[3], v[4]));
+    }
+    self.n += 1;
+    Ok(())
+  }
+  /// reg
+  fn reg(&mut self, _c: Vec<u16>) -> () {}
+  /// col
+  fn col(&self, n: u16) -> u16 { n }
+}
+
+/// trait TViewRun for BufferWR (wr splits a run into cells)
+impl TViewRun<u16> for BufferWR {}
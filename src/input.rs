@@ -0,0 +1,212 @@
+//! input
+//!
+
+use mvc_rs::TView;
+
+use crate::MineField;
+use crate::confirm::Outcome;
+use crate::error::MineFieldError;
+use crate::replay::Move;
+
+/// MouseButton
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+  /// left (open)
+  Left,
+  /// right (flag toggle by the mark cycle)
+  Right,
+  /// middle (chord)
+  Middle
+}
+
+/// MouseAction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseAction {
+  /// press
+  Press,
+  /// release
+  Release,
+  /// move (or drag)
+  Move
+}
+
+/// Direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+  /// up
+  Up,
+  /// down
+  Down,
+  /// left
+  Left,
+  /// right
+  Right
+}
+
+/// Direction
+impl Direction {
+  /// delta (row, column)
+  pub fn delta(&self) -> (i32, i32) {
+    match self {
+    Direction::Up => (-1, 0),
+    Direction::Down => (1, 0),
+    Direction::Left => (0, -1),
+    Direction::Right => (0, 1)
+    }
+  }
+}
+
+/// Input (translated from raw terminal events by frontends)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input {
+  /// cursor up
+  Up,
+  /// cursor down
+  Down,
+  /// cursor left
+  Left,
+  /// cursor right
+  Right,
+  /// cursor to the edge of the field
+  Edge(Direction),
+  /// cursor to the next closed cell
+  NextClosed(Direction),
+  /// cursor to the top left
+  Home,
+  /// open at the cursor
+  Open,
+  /// flag toggle (mark cycle) at the cursor
+  Flag,
+  /// chord at the cursor
+  Chord,
+  /// pause or resume
+  Pause,
+  /// quit
+  Quit,
+  /// screen resized to (cols, rows) for the board
+  Resize(u16, u16),
+  /// mouse at screen (x, y) in the viewport
+  Mouse{
+    /// x
+    x: u16,
+    /// y
+    y: u16,
+    /// button
+    button: MouseButton,
+    /// action
+    action: MouseAction
+  }
+}
+
+/// Handled (what happened)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Handled {
+  /// nothing (out of the viewport or no effect)
+  None,
+  /// cursor moved
+  Moved,
+  /// opened
+  Opened,
+  /// marked
+  Marked,
+  /// chorded
+  Chorded,
+  /// risky chord held until chorded again (config confirm, see confirm)
+  Confirm,
+  /// paused or resumed
+  Paused,
+  /// viewport resized (redrawn in full)
+  Resized,
+  /// quit requested
+  Quit
+}
+
+/// MouseButton
+impl MouseButton {
+  /// bit in MineField::mb
+  pub fn bit(&self) -> u8 {
+    match self {
+    MouseButton::Left => 1,
+    MouseButton::Right => 2,
+    MouseButton::Middle => 4
+    }
+  }
+}
+
+/// MineField
+impl MineField {
+  /// handle an input, redraw, and run the ending when the game is over
+  pub fn handle<T>(&mut self, i: Input, g: &mut impl TView<T>) ->
+    Result<Handled, MineFieldError> {
+    let e = self.is_end();
+    if !matches!(i, Input::Chord | Input::Mouse{..}) { self.pending = None; }
+    let h = match i {
+    Input::Up => { self.up(); Handled::Moved },
+    Input::Down => { self.down(); Handled::Moved },
+    Input::Left => { self.left(); Handled::Moved },
+    Input::Right => { self.right(); Handled::Moved },
+    Input::Edge(d) =>
+      if self.jump_to_edge(d) { Handled::Moved } else { Handled::None },
+    Input::NextClosed(d) =>
+      if self.jump_to_next_closed(d) { Handled::Moved } else { Handled::None },
+    Input::Home => if self.home() { Handled::Moved } else { Handled::None },
+    Input::Open => if self.click() { Handled::Opened } else { Handled::None },
+    Input::Flag => if self.mark() { Handled::Marked } else { Handled::None },
+    Input::Chord => self.chord_input(),
+    Input::Pause => {
+      if !self.resume() && !self.pause() { Handled::None }
+      else { Handled::Paused }
+    },
+    Input::Quit => return Ok(Handled::Quit),
+    Input::Resize(w, h) => { self.on_resize(w, h); Handled::Resized },
+    Input::Mouse{x, y, button, action} => self.mouse(x, y, button, action)
+    };
+    if h != Handled::None { // cursor visible at once
+      self.k = true;
+      self.refresh_dirty(g)?;
+    }
+    if !e && self.is_end() { self.ending(g)?; }
+    Ok(h)
+  }
+
+  /// mouse at screen (x, y) in the viewport
+  ///
+  /// - left press: open
+  /// - right press: flag toggle (mark cycle)
+  /// - middle press or left + right held: chord
+  pub fn mouse(&mut self, x: u16, y: u16, b: MouseButton, a: MouseAction) ->
+    Handled {
+    let k = b.bit();
+    match a {
+    MouseAction::Release => { self.mb &= !k; },
+    MouseAction::Press => { self.mb |= k; },
+    MouseAction::Move => ()
+    }
+    if !self.update_m(x, y) { return Handled::None; }
+    if a != MouseAction::Press { return Handled::Moved; }
+    if self.mb & 3 == 3 || b == MouseButton::Middle {
+      return match self.chord_input() {
+      Handled::None => Handled::Moved,
+      h => h
+      };
+    }
+    self.pending = None;
+    let r = if b == MouseButton::Left { (self.click(), Handled::Opened) }
+    else { (self.mark(), Handled::Marked) };
+    if r.0 { r.1 } else { Handled::Moved }
+  }
+
+  /// chord at the cursor by an input (a risky one is held when config
+  /// confirm is set, chording the same cell again confirms it)
+  fn chord_input(&mut self) -> Handled {
+    let b = if self.pending == Some(Move::Chord(self.r, self.c)) {
+      self.confirm()
+    } else {
+      match self.try_chord() {
+      Outcome::Done(b) => b,
+      Outcome::NeedsConfirmation(_) => return Handled::Confirm
+      }
+    };
+    if b { Handled::Chorded } else { Handled::None }
+  }
+}
@@ -0,0 +1,231 @@
+//! stable JSON game state (feature serde, schema written by hand)
+//!
+//! schema version 1, an object with
+//! - `version` schema version (required)
+//! - `w` `h` `m` area width, height and mines (required)
+//! - `seed` generation seed as a decimal string (u64 beyond JS numbers)
+//! - `status` opened count with 0x8000 explosion, 0x4000 success
+//! - `placed` mines placed, `first` [r, c] of the first click or null
+//! - `cursor` [r, c], `detonated` [r, c] or null
+//! - `hits` mines hit, `expired` time budget expired, `paused`
+//! - `elapsed_ms` elapsed time in milliseconds
+//! - `cells` rows of cell bytes as MineField::f (required)
+//! - `mask` rows of playable booleans or null
+//! - `items` [{`r`, `c`, `item`: `treasure` | `powerup`}]
+//! - `clicks` {`opens`, `chords`, `flags`, `wasted`}
+//! - `history` [{`ms`, `move` in the notation of replay}]
+//! - `config` {`question`, `wrap_x`, `wrap_y`, `animate`, `slow_reveal`,
+//!   `lives`, `max_flags`, `time_limit`, `fog`, `treasures`, `powerups`,
+//!   `win` (`opened` | `flagged` | `both`), `auto_finish`, `confirm`,
+//!   `cell_width`}
+//!
+//! forward compatibility: fields are only added within a version, readers
+//! ignore unknown fields and take defaults for missing optional ones, a
+//! version newer than VERSION is rejected (fields changed meaning)
+//!
+//! theme, glyphs, viewport, layers, hooks and placement are not part of
+//! the state
+//!
+
+use std::time::Duration;
+
+use serde_json::{Map, Value, json};
+
+use crate::MineField;
+use crate::config::Config;
+use crate::error::MineFieldError::{self, ParseError};
+use crate::metrics::Clicks;
+use crate::replay::Move;
+use crate::treasure::Item;
+
+/// JSON schema version
+pub const VERSION: u64 = 1;
+
+/// [r, c] or null
+fn cell(p: Option<(u16, u16)>) -> Value {
+  p.map_or(Value::Null, |(r, c)| json!([r, c]))
+}
+
+/// field k as u64 (None: missing or null)
+fn num(o: &Map<String, Value>, k: &str) ->
+  Result<Option<u64>, MineFieldError> {
+  match o.get(k) {
+  None | Some(Value::Null) => Ok(None),
+  Some(v) => Ok(Some(v.as_u64()
+    .ok_or_else(|| ParseError(format!("json {}: {}", k, v)))?))
+  }
+}
+
+/// field k as u16 (None: missing or null)
+fn n16(o: &Map<String, Value>, k: &str) ->
+  Result<Option<u16>, MineFieldError> {
+  num(o, k)?.map(|n| u16::try_from(n).map_err(|_|
+    ParseError(format!("json {}: {}", k, n)))).transpose()
+}
+
+/// field k as bool (None: missing or null)
+fn flag(o: &Map<String, Value>, k: &str) ->
+  Result<Option<bool>, MineFieldError> {
+  match o.get(k) {
+  None | Some(Value::Null) => Ok(None),
+  Some(v) => Ok(Some(v.as_bool()
+    .ok_or_else(|| ParseError(format!("json {}: {}", k, v)))?))
+  }
+}
+
+/// field k as [r, c] (None: missing or null)
+fn pos(o: &Map<String, Value>, k: &str) ->
+  Result<Option<(u16, u16)>, MineFieldError> {
+  let Some(v) = o.get(k).filter(|v| !v.is_null()) else { return Ok(None); };
+  let e = || ParseError(format!("json {}: {}", k, v));
+  let a = v.as_array().filter(|a| a.len() == 2).ok_or_else(e)?;
+  let p = a.iter().map(|n| n.as_u64().and_then(|n| u16::try_from(n).ok()))
+    .collect::<Option<Vec<_>>>().ok_or_else(e)?;
+  Ok(Some((p[0], p[1])))
+}
+
+/// rows of field k as h x w (None: missing or null)
+fn rows<T>(o: &Map<String, Value>, k: &str, w: u16, h: u16,
+  get: impl Fn(&Value) -> Option<T>) ->
+  Result<Option<Vec<Vec<T>>>, MineFieldError> {
+  let Some(v) = o.get(k).filter(|v| !v.is_null()) else { return Ok(None); };
+  let e = || ParseError(format!("json {}: must be {} rows of {}", k, h, w));
+  let a = v.as_array().filter(|a| a.len() == h as usize).ok_or_else(e)?;
+  a.iter().map(|r| r.as_array().filter(|r| r.len() == w as usize)
+    .and_then(|r| r.iter().map(&get).collect::<Option<Vec<_>>>())
+    .ok_or_else(e)).collect::<Result<Vec<_>, _>>().map(Some)
+}
+
+/// config of the object (defaults for missing fields)
+fn config(o: &Map<String, Value>) -> Result<Config, MineFieldError> {
+  let mut g = Config::default();
+  if let Some(b) = flag(o, "question")? { g.question = b; }
+  if let Some(b) = flag(o, "wrap_x")? { g.wrap_x = b; }
+  if let Some(b) = flag(o, "wrap_y")? { g.wrap_y = b; }
+  if let Some(b) = flag(o, "animate")? { g.animate = b; }
+  if let Some(b) = flag(o, "slow_reveal")? { g.slow_reveal = b; }
+  if let Some(n) = n16(o, "lives")? {
+    g.lives = u8::try_from(n)
+      .map_err(|_| ParseError(format!("json lives: {}", n)))?;
+  }
+  g.max_flags = n16(o, "max_flags")?;
+  g.time_limit = num(o, "time_limit")?.map(|n| n as u32);
+  g.fog = n16(o, "fog")?;
+  if let Some(n) = n16(o, "treasures")? { g.treasures = n; }
+  if let Some(n) = n16(o, "powerups")? { g.powerups = n; }
+  if let Some(s) = o.get("win").and_then(|v| v.as_str()) {
+    g.win = s.parse()?;
+  }
+  if let Some(b) = flag(o, "auto_finish")? { g.auto_finish = b; }
+  if let Some(b) = flag(o, "confirm")? { g.confirm = b; }
+  if let Some(n) = n16(o, "cell_width")? { g.cell_width = n; }
+  Ok(g)
+}
+
+/// MineField
+impl MineField {
+  /// to_json value of the game state (see the module schema)
+  pub fn to_json_value(&self) -> Value {
+    let g = &self.cfg;
+    let items = self.items.iter().map(|(&(r, c), &i)| json!({"r": r, "c": c,
+      "item": if i == Item::Treasure { "treasure" } else { "powerup" }}))
+      .collect::<Vec<_>>();
+    let hist = self.hist.iter().map(|(t, m)|
+      json!({"ms": t.as_millis() as u64, "move": m.to_string()}))
+      .collect::<Vec<_>>();
+    json!({
+      "version": VERSION,
+      "w": self.w, "h": self.h, "m": self.m, "seed": self.seed.to_string(),
+      "status": self.s, "placed": self.p, "first": cell(self.first),
+      "cursor": [self.r, self.c], "detonated": cell(self.x),
+      "hits": self.hits, "expired": self.expired, "paused": self.paused,
+      "elapsed_ms": self.elapsed().as_millis() as u64,
+      "cells": self.f.to_rows(),
+      "mask": self.mask, "items": items,
+      "clicks": {"opens": self.ck.opens, "chords": self.ck.chords,
+        "flags": self.ck.flags, "wasted": self.ck.wasted},
+      "history": hist,
+      "config": {"question": g.question, "wrap_x": g.wrap_x,
+        "wrap_y": g.wrap_y, "animate": g.animate,
+        "slow_reveal": g.slow_reveal, "lives": g.lives,
+        "max_flags": g.max_flags, "time_limit": g.time_limit, "fog": g.fog,
+        "treasures": g.treasures, "powerups": g.powerups,
+        "win": g.win.to_string(), "auto_finish": g.auto_finish,
+        "confirm": g.confirm, "cell_width": g.cell_width}
+    })
+  }
+
+  /// to_json (see the module schema)
+  pub fn to_json(&self) -> String { self.to_json_value().to_string() }
+
+  /// from_json value (see the module schema, the timer stopped at the
+  /// elapsed time, invariants validated)
+  pub fn from_json_value(v: &Value) -> Result<Self, MineFieldError> {
+    let e = |s: &str| ParseError(format!("json: {}", s));
+    let o = v.as_object().ok_or_else(|| e("object expected"))?;
+    let ver = num(o, "version")?.ok_or_else(|| e("version required"))?;
+    if ver > VERSION { Err(e(&format!("version {} > {}", ver, VERSION)))? }
+    let req = |k| -> Result<u16, MineFieldError> {
+      n16(o, k)?.ok_or_else(|| e(&format!("{} required", k)))
+    };
+    let (w, h, m) = (req("w")?, req("h")?, req("m")?);
+    let seed = match o.get("seed") {
+    Some(Value::String(s)) => s.parse()?,
+    Some(v) => v.as_u64().ok_or_else(|| e(&format!("seed {}", v)))?,
+    None => 0
+    };
+    let mut f = MineField::try_from_seed(w, h, m, seed)?;
+    let cells = rows(o, "cells", w, h, |u| u.as_u64()
+      .and_then(|u| u8::try_from(u).ok()))?
+      .ok_or_else(|| e("cells required"))?;
+    f.f.as_mut_slice().copy_from_slice(&cells.concat());
+    f.mask = rows(o, "mask", w, h, |b| b.as_bool())?;
+    f.s = n16(o, "status")?.unwrap_or(0);
+    f.p = flag(o, "placed")?
+      .unwrap_or_else(|| f.f.as_slice().iter().any(|&u| Self::is_mine(u)));
+    f.first = pos(o, "first")?;
+    (f.r, f.c) = pos(o, "cursor")?.unwrap_or((0, 0));
+    f.x = pos(o, "detonated")?;
+    f.hits = n16(o, "hits")?.unwrap_or(0).min(u8::MAX as u16) as u8;
+    f.expired = flag(o, "expired")?.unwrap_or(false);
+    f.paused = flag(o, "paused")?.unwrap_or(false);
+    f.tm.acc = Duration::from_millis(num(o, "elapsed_ms")?.unwrap_or(0));
+    for i in o.get("items").and_then(|v| v.as_array()).into_iter().flatten() {
+      let i = i.as_object().ok_or_else(|| e("items object expected"))?;
+      let (r, c) = (n16(i, "r")?.unwrap_or(0), n16(i, "c")?.unwrap_or(0));
+      let item = match i.get("item").and_then(|v| v.as_str()) {
+      Some("treasure") => Item::Treasure,
+      Some("powerup") => Item::PowerUp,
+      s => Err(e(&format!("item {:?}", s)))?
+      };
+      f.items.insert((r, c), item);
+    }
+    if let Some(k) = o.get("clicks").and_then(|v| v.as_object()) {
+      let n = |s| num(k, s).map(|n| n.unwrap_or(0) as u32);
+      f.ck = Clicks{opens: n("opens")?, chords: n("chords")?,
+        flags: n("flags")?, wasted: n("wasted")?};
+    }
+    for y in o.get("history").and_then(|v| v.as_array()).into_iter().flatten() {
+      let y = y.as_object().ok_or_else(|| e("history object expected"))?;
+      let t = Duration::from_millis(num(y, "ms")?.unwrap_or(0));
+      let mv = y.get("move").and_then(|v| v.as_str())
+        .ok_or_else(|| e("history move required"))?.parse::<Move>()?;
+      f.hist.push((t, mv));
+    }
+    if let Some(g) = o.get("config").and_then(|v| v.as_object()) {
+      f.cfg = config(g)?;
+    }
+    for (r, c) in f.first.iter().chain(&f.x).chain(f.items.keys()) {
+      f.check(*r, *c)?;
+    }
+    f.set_viewport(w, h);
+    f.validate().map_err(|i| e(&i.to_string()))?;
+    Ok(f)
+  }
+
+  /// from_json (see the module schema)
+  pub fn from_json(s: &str) -> Result<Self, MineFieldError> {
+    Self::from_json_value(&serde_json::from_str(s)
+      .map_err(|e| ParseError(format!("json: {}", e)))?)
+  }
+}
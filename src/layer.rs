@@ -0,0 +1,172 @@
+//! render layers composited over the board before hitting WR
+//!
+//! board (cells by MineField::c), heat (probability heatmap kept by
+//! overlay_probabilities), overlay (hints, ghost cursor at field (r, c),
+//! scrolls with the board) and ui (status, menus at viewport (x, y), fixed
+//! on the screen), upper layers paint over lower ones so refresh and blink
+//! keep what a frontend has put on them
+//!
+
+use std::collections::BTreeMap;
+
+use crate::MineField;
+use crate::error::MineFieldError;
+
+/// Layer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+  /// cells of the field (drawn by MineField::c, not paintable)
+  Board,
+  /// heatmap at field (r, c)
+  Heat,
+  /// overlay at field (r, c)
+  Overlay,
+  /// ui at viewport (x, y)
+  Ui
+}
+
+/// Paint (None keeps the value of the lower layer)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Paint {
+  /// glyph
+  pub glyph: Option<String>,
+  /// background color
+  pub bgc: Option<u16>,
+  /// foreground color
+  pub fgc: Option<u16>
+}
+
+/// Paint
+impl Paint {
+  /// glyph with the colors
+  pub fn new(glyph: &str, bgc: u16, fgc: u16) -> Self {
+    Paint{glyph: Some(glyph.to_string()), bgc: Some(bgc), fgc: Some(fgc)}
+  }
+
+  /// glyph only
+  pub fn glyph(glyph: &str) -> Self {
+    Paint{glyph: Some(glyph.to_string()), ..Paint::default()}
+  }
+
+  /// background color only (heatmap, ghost cursor)
+  pub fn bg(bgc: u16) -> Self { Paint{bgc: Some(bgc), ..Paint::default()} }
+
+  /// foreground color only
+  pub fn fg(fgc: u16) -> Self { Paint{fgc: Some(fgc), ..Paint::default()} }
+
+  /// apply over the lower (glyph, bgc, fgc)
+  pub fn apply(&self, o: &mut (String, u16, u16)) -> () {
+    if let Some(g) = &self.glyph { o.0.clone_from(g); }
+    if let Some(b) = self.bgc { o.1 = b; }
+    if let Some(f) = self.fgc { o.2 = f; }
+  }
+}
+
+/// Layers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Layers {
+  /// heatmap at field (r, c)
+  pub heat: BTreeMap<(u16, u16), Paint>,
+  /// overlay at field (r, c)
+  pub ov: BTreeMap<(u16, u16), Paint>,
+  /// ui at viewport (x, y)
+  pub ui: BTreeMap<(u16, u16), Paint>
+}
+
+/// Layers
+impl Layers {
+  /// map of the layer (None: Board)
+  fn map(&mut self, l: Layer) -> Option<&mut BTreeMap<(u16, u16), Paint>> {
+    match l {
+    Layer::Board => None,
+    Layer::Heat => Some(&mut self.heat),
+    Layer::Overlay => Some(&mut self.ov),
+    Layer::Ui => Some(&mut self.ui)
+    }
+  }
+
+  /// set paint p at (a, b) of the layer (false: Board)
+  pub fn set(&mut self, l: Layer, a: u16, b: u16, p: Paint) -> bool {
+    self.map(l).map(|m| m.insert((a, b), p)).is_some()
+  }
+
+  /// unset (a, b) of the layer (false: nothing there)
+  pub fn unset(&mut self, l: Layer, a: u16, b: u16) -> bool {
+    self.map(l).is_some_and(|m| m.remove(&(a, b)).is_some())
+  }
+
+  /// clear the layer
+  pub fn clear(&mut self, l: Layer) -> () {
+    if let Some(m) = self.map(l) { m.clear(); }
+  }
+
+  /// is_empty (nothing over the board)
+  pub fn is_empty(&self) -> bool {
+    self.heat.is_empty() && self.ov.is_empty() && self.ui.is_empty()
+  }
+}
+
+/// Heat (how overlay_probabilities shows mine likelihood)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Heat {
+  /// tint background by ids from unlikely to likely
+  Tint(Vec<u16>),
+  /// digit 0-9 (probability x 10, 9 for certain)
+  Digit
+}
+
+/// Heat
+impl Heat {
+  /// tint by ids of Theme::classic (green, yellow, red)
+  pub fn tint() -> Self { Heat::Tint(vec![7, 5, 8]) }
+
+  /// paint of probability p
+  pub fn paint(&self, p: f64) -> Paint {
+    match self {
+    Heat::Tint(v) if v.is_empty() => Paint::default(),
+    Heat::Tint(v) => {
+      let i = (p.clamp(0.0, 1.0) * (v.len() - 1) as f64).round() as usize;
+      Paint::bg(v[i])
+    },
+    Heat::Digit => Paint::glyph(&((p * 10.0) as u8).min(9).to_string())
+    }
+  }
+}
+
+/// MineField
+impl MineField {
+  /// set_heatmap mode (None: off) kept up to date after each open
+  pub fn set_heatmap(&mut self, h: Option<Heat>) -> () {
+    self.heat = h;
+    self.overlay_probabilities();
+  }
+
+  /// overlay_probabilities of closed cells on the heat layer by the mode
+  pub fn overlay_probabilities(&mut self) -> () {
+    self.ly.clear(Layer::Heat);
+    let Some(h) = &self.heat else { return; };
+    for (r, v) in self.probabilities().into_iter().enumerate() {
+      for (c, p) in v.into_iter().enumerate() {
+        let Some(p) = p else { continue; };
+        self.ly.heat.insert((r as u16, c as u16), h.paint(p));
+      }
+    }
+  }
+
+  /// composite cell (r, c) at viewport (x, y) (board, heat, peek,
+  /// overlay, ui)
+  pub fn compose(&self, r: u16, c: u16, x: u16, y: u16) ->
+    Result<(String, u16, u16), MineFieldError> {
+    let u = self.f[r as usize][c as usize];
+    let mut o = self.c(r, c, u)?;
+    if let Some(p) = self.ly.heat.get(&(r, c)) { p.apply(&mut o); }
+    if self.peek && u & 0xd0 == 0 && Self::is_mine(u) &&
+      !self.paused && self.is_playable(r, c) && self.is_visible(r, c) {
+      let k = self.theme.peek;
+      o = (self.glyphs.lower[15].clone(), k.0, k.1);
+    }
+    if let Some(p) = self.ly.ov.get(&(r, c)) { p.apply(&mut o); }
+    if let Some(p) = self.ly.ui.get(&(x, y)) { p.apply(&mut o); }
+    Ok(o)
+  }
+}
@@ -0,0 +1,120 @@
+//! local leaderboard (top times per difficulty)
+//!
+//! persisted as `WxH-M millis YYYY-MM-DD name` lines (`#` comment)
+//!
+
+use std::collections::BTreeMap;
+use std::{fmt, fs, path};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::config::Difficulty;
+use crate::error::MineFieldError::{self, ParseError};
+use crate::stats::Stats;
+
+/// Entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+  /// player name
+  pub name: String,
+  /// time
+  pub t: Duration,
+  /// date (year, month, day)
+  pub date: (u16, u8, u8)
+}
+
+/// Leaderboard
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leaderboard {
+  /// entries per difficulty key (see Stats::key) sorted by time
+  pub tbl: BTreeMap<String, Vec<Entry>>,
+  /// max entries per difficulty
+  pub n: usize
+}
+
+/// Leaderboard
+impl Default for Leaderboard {
+  /// default
+  fn default() -> Self { Leaderboard::new(10) }
+}
+
+/// Leaderboard
+impl Leaderboard {
+  /// constructor
+  pub fn new(n: usize) -> Self { Leaderboard{tbl: BTreeMap::new(), n} }
+
+  /// entries of a difficulty
+  pub fn top(&self, d: Difficulty) -> &[Entry] {
+    self.tbl.get(&Stats::key(d)).map_or(&[], |v| v.as_slice())
+  }
+
+  /// rank (1 origin) a time would achieve, None when out of the table
+  pub fn rank(&self, d: Difficulty, t: Duration) -> Option<usize> {
+    let v = self.top(d);
+    let i = v.iter().position(|e| t < e.t).unwrap_or(v.len());
+    if i < self.n { Some(i + 1) } else { None }
+  }
+
+  /// insert (returns the achieved rank 1 origin, None when out of the table)
+  pub fn insert(&mut self, d: Difficulty, name: &str, t: Duration,
+    date: (u16, u8, u8)) -> Option<usize> {
+    let i = self.rank(d, t)?;
+    let v = self.tbl.entry(Stats::key(d)).or_default();
+    v.insert(i - 1, Entry{name: name.to_string(), t, date});
+    v.truncate(self.n);
+    Some(i)
+  }
+
+  /// load
+  pub fn load(p: impl AsRef<path::Path>) -> Result<Self, MineFieldError> {
+    fs::read_to_string(p)?.parse()
+  }
+
+  /// save
+  pub fn save(&self, p: impl AsRef<path::Path>) ->
+    Result<(), MineFieldError> {
+    fs::write(p, self.to_string())?;
+    Ok(())
+  }
+}
+
+/// Leaderboard
+impl fmt::Display for Leaderboard {
+  /// fmt
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (k, v) in &self.tbl {
+      for e in v {
+        let (y, m, d) = e.date;
+        writeln!(f, "{} {} {:04}-{:02}-{:02} {}",
+          k, e.t.as_millis(), y, m, d, e.name)?;
+      }
+    }
+    Ok(())
+  }
+}
+
+/// Leaderboard
+impl FromStr for Leaderboard {
+  type Err = MineFieldError;
+
+  /// from_str (keeps the default table size)
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut b = Leaderboard::default();
+    for l in s.lines() {
+      let l = l.trim();
+      if l.is_empty() || l.starts_with('#') { continue; }
+      let e = || format!("leaderboard: {}", l);
+      let mut w = l.splitn(4, ' ');
+      let (Some(k), Some(t), Some(date), Some(name)) =
+        (w.next(), w.next(), w.next(), w.next()) else { Err(ParseError(e()))? };
+      let q: Vec<&str> = date.split('-').collect();
+      if q.len() != 3 { Err(ParseError(e()))? }
+      let date = (q[0].parse()?, q[1].parse()?, q[2].parse()?);
+      let t = Duration::from_millis(t.parse()?);
+      let v = b.tbl.entry(k.to_string()).or_default();
+      v.push(Entry{name: name.to_string(), t, date});
+      v.sort_by_key(|e| e.t);
+    }
+    Ok(b)
+  }
+}
@@ -2,28 +2,117 @@
 //! minefield abstract layer for mine sweeper by Rust
 //!
 
-use std::error::Error;
-use std::time;
+#![allow(clippy::unused_unit)]
 
-use rand;
-use rand::prelude::SliceRandom;
+use std::error::Error;
+use std::fmt;
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use mvc_rs::{TPacket, TView};
 
-/// Packet
+use config::{Config, WinCondition};
+use error::MineFieldError;
+use feedback::Feedback;
+use input::Direction;
+use layer::{Heat, Layers};
+use metrics::Clicks;
+use placement::Placement;
+use sink::ViewSink;
+use replay::Move;
+use glyphs::Glyphs;
+use grid::Grid;
+use theme::Theme;
+use timer::{Splits, Timer, TimerEvent};
+use treasure::{Found, Item};
+use viewport::Viewport;
+
+pub mod analysis;
+pub mod apply;
+#[cfg(feature = "async")]
+pub mod aio;
+pub mod arcade;
+pub mod backend;
+pub mod blink;
+pub mod campaign;
+pub mod cell;
+pub mod color;
+pub mod config;
+pub mod confirm;
+pub mod daily;
+pub mod describe;
+pub mod editor;
+pub mod error;
+pub mod exact;
+pub mod feedback;
+pub mod fixed;
+pub mod flag;
+pub mod frame;
+pub mod glyphs;
+pub mod grid;
+pub mod headless;
+pub mod input;
+pub mod layer;
+pub mod leaderboard;
+pub mod lifecycle;
+pub mod messages;
+pub mod metrics;
+pub mod montecarlo;
+pub mod panel;
+pub mod placement;
+pub mod replay;
+pub mod rng;
+pub mod runner;
+pub mod save;
+pub mod shared;
+pub mod sim;
+pub mod sink;
+pub mod solver;
+pub mod spectator;
+pub mod stats;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod stencil;
+pub mod storage;
+pub mod style;
+pub mod sync;
+pub mod theme;
+pub mod tile;
+pub mod timer;
+pub mod treasure;
+pub mod tutorial;
+pub mod validate;
+pub mod versus;
+pub mod viewport;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod widget;
+pub mod wire;
+#[cfg(feature = "interop")]
+pub mod mbf;
+#[cfg(feature = "serde")]
+pub mod json;
+
+/// most cells of a field (the opened count is the low 14 bits of s)
+pub const MAX_CELLS: u32 = 0x3fff;
+
+/// Drawn (glyph, bgc, fgc and style of a cell last drawn)
+pub type Drawn = ((String, u16, u16), u16);
+
+/// Packet (msg is a glyph of a cell, or a run of glyphs of consecutive
+/// cells on one row from x when written by TViewRun::wr_run)
 pub struct Packet<'a> {
   /// x
   pub x: u16,
   /// y
   pub y: u16,
-  /// style
+  /// style (style::BASE with attribute bits)
   pub st: u16,
   /// bgc abstract id
   pub bgc: u16,
   /// fgc abstract id
   pub fgc: u16,
   /// msg
-  pub msg: &'a String
+  pub msg: &'a str
 }
 
 /// trait TPacket for Packet
@@ -38,10 +127,40 @@ impl TPacket for Packet<'_> {
   }
   /// as_str
   fn as_str(&self) -> &str {
-    self.msg.as_str()
+    self.msg
   }
 }
 
+/// trait TViewRun (batched writes on TView)
+pub trait TViewRun<T>: TView<T> {
+  /// wr_run a run of glyphs from (x, y) with the same style and colors
+  /// (default: wr for each glyph, override to coalesce escape sequences)
+  fn wr_run(&mut self, x: u16, y: u16, st: u16, bgc: u16, fgc: u16,
+    s: &str) -> Result<(), Box<dyn Error>> {
+    let mut b = [0u8; 4];
+    for (i, ch) in s.chars().enumerate() {
+      let msg = ch.encode_utf8(&mut b);
+      self.wr(Packet{x: x + i as u16, y, st, bgc, fgc, msg})?;
+    }
+    Ok(())
+  }
+
+  /// flush the writes buffered since the last flush (once per refresh_run
+  /// and per frame of the runners, default: nothing buffered)
+  fn flush(&mut self) -> Result<(), Box<dyn Error>> { Ok(()) }
+}
+
+/// OpenResult (what open_report revealed)
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct OpenResult {
+  /// cells opened in BFS order of the flood fill (a defused mine included)
+  pub opened: Vec<(u16, u16)>,
+  /// exploded
+  pub exploded: bool,
+  /// game ended by the open
+  pub finished: bool
+}
+
 /// MineField
 pub struct MineField {
   /// status
@@ -52,119 +171,570 @@ pub struct MineField {
   pub h: u16,
   /// mines
   pub m: u16,
-  /// field w x h
-  pub f: Vec<Vec<u8>>,
+  /// field w x h (flat storage, f[r][c] row view)
+  pub f: Grid,
   /// cursor row
   pub r: u16,
   /// cursor column
   pub c: u16,
-  /// ms timeout for idle
-  pub ms: time::Duration,
-  /// blink cursor count max
-  pub b: u16,
-  /// tick count about b x ms
-  pub t: u16
+  /// cursor shown (blink phase driven by blink::Blinker)
+  pub k: bool,
+  /// mines placed
+  pub p: bool,
+  /// config
+  pub cfg: Config,
+  /// last drawn cells and styles for refresh_dirty (empty: full redraw)
+  pub d: Vec<Vec<Option<Drawn>>>,
+  /// generation seed (see rng)
+  pub seed: u64,
+  /// color theme
+  pub theme: Theme,
+  /// glyph tables
+  pub glyphs: Glyphs,
+  /// viewport (screen x, y = column - vp.x, row - vp.y)
+  pub vp: Viewport,
+  /// mouse buttons held (see input::MouseButton::bit)
+  pub mb: u8,
+  /// timer
+  pub tm: Timer,
+  /// paused (board concealed)
+  pub paused: bool,
+  /// pending waves of the open cascade animation
+  pub cascade: VecDeque<Vec<(u16, u16)>>,
+  /// opened cells still drawn closed by the animation
+  pub hidden: HashSet<(u16, u16)>,
+  /// detonated cell
+  pub x: Option<(u16, u16)>,
+  /// feedback hooks
+  pub fb: Option<Box<dyn Feedback>>,
+  /// mines hit (defused while below cfg.lives)
+  pub hits: u8,
+  /// time budget expired (lost without explosion)
+  pub expired: bool,
+  /// items not found yet (treasure layer)
+  pub items: HashMap<(u16, u16), Item>,
+  /// items found not taken yet (see take_found)
+  pub found: Vec<Found>,
+  /// playable cells [r][c] for irregular shapes (None: all playable)
+  pub mask: Option<Vec<Vec<bool>>>,
+  /// mine distribution strategy used by start
+  pub placement: Box<dyn Placement>,
+  /// first click (r, c) when mines are placed by start
+  pub first: Option<(u16, u16)>,
+  /// clicks counted during play
+  pub ck: Clicks,
+  /// split times (None: not tracked, see set_splits)
+  pub sp: Option<Splits>,
+  /// history of moves (see replay)
+  pub hist: Vec<(std::time::Duration, Move)>,
+  /// overlay and ui layers composited over the board (see layer)
+  pub ly: Layers,
+  /// probability heatmap mode (None: off, see set_heatmap)
+  pub heat: Option<Heat>,
+  /// mines drawn through closed cells for debugging (see reveal_mines)
+  pub peek: bool,
+  /// chord or finish held until confirmed (see confirm)
+  pub pending: Option<Move>
 }
 
 /// MineField
 impl MineField {
-  /// constructor
+  /// constructor (random seed, feature rand)
+  #[cfg(feature = "rand")]
   pub fn new(w: u16, h: u16, m: u16) -> Self {
-    let f = (0..h).into_iter().map(|_r|
-      (0..w).into_iter().map(|_c|
-        0).collect()).collect(); // all close
+    Self::from_seed(w, h, m, rand::random())
+  }
+
+  /// constructor checked (random seed, feature rand, see try_from_seed)
+  #[cfg(feature = "rand")]
+  pub fn try_new(w: u16, h: u16, m: u16) -> Result<Self, MineFieldError> {
+    Self::try_from_seed(w, h, m, rand::random())
+  }
+
+  /// check_size w x h (cells count, 1 to MAX_CELLS)
+  pub fn check_size(w: u16, h: u16) -> Result<u32, MineFieldError> {
+    let n = w as u32 * h as u32;
+    if n == 0 || n > MAX_CELLS {
+      return Err(MineFieldError::InvalidDimensions(w, h));
+    }
+    Ok(n)
+  }
+
+  /// constructor checked with generation seed (at most w x h - 1 mines,
+  /// w x h within MAX_CELLS)
+  pub fn try_from_seed(w: u16, h: u16, m: u16, seed: u64) ->
+    Result<Self, MineFieldError> {
+    let n = Self::check_size(w, h)?;
+    if m as u32 >= n { return Err(MineFieldError::TooManyMines(m)); }
+    Ok(Self::from_seed(w, h, m, seed))
+  }
+
+  /// constructor with a mine density 0.0 <= d < 1.0 (rounded, at most
+  /// w x h - 1 mines, random seed, feature rand)
+  #[cfg(feature = "rand")]
+  pub fn new_density(w: u16, h: u16, d: f64) ->
+    Result<Self, MineFieldError> {
+    let n = Self::check_size(w, h)?;
+    if !(0.0..1.0).contains(&d) {
+      return Err(MineFieldError::InvalidDensity(d));
+    }
+    let m = ((n as f64 * d).round() as u32).min(n - 1);
+    Self::try_new(w, h, m as u16)
+  }
+
+  /// constructor with generation seed
+  pub fn from_seed(w: u16, h: u16, m: u16, seed: u64) -> Self {
+    let f = Grid::new(w, h); // all close
     MineField{s: 0, w, h, m, f, r: 0, c: 0,
-      ms: time::Duration::from_millis(10), b: 80, t: 0}
+      k: true, p: false,
+      cfg: Config::default(), d: vec![], seed, theme: Theme::default(),
+      glyphs: Glyphs::default(), vp: Viewport::new(w, h),
+      mb: 0, tm: Timer::default(), paused: false,
+      cascade: VecDeque::new(), hidden: HashSet::new(), x: None, fb: None,
+      hits: 0, expired: false, items: HashMap::new(), found: vec![],
+      mask: None, placement: Box::new(placement::Uniform),
+      first: None, ck: Clicks::default(), sp: None, hist: vec![],
+      ly: Layers::default(), heat: None, peek: false, pending: None}
+  }
+
+  /// constructor with mines at (r, c) (no random placement, duplicates
+  /// are ignored, panics when out of the field)
+  pub fn with_mines(w: u16, h: u16, q: &[(u16, u16)]) -> Self {
+    let mut q = q.to_vec();
+    q.sort();
+    q.dedup();
+    let mut f = Self::from_seed(w, h, q.len() as u16, 0);
+    f.place(&q);
+    f
+  }
+
+  /// constructor with mines at (r, c) checked (duplicates are ignored,
+  /// see check_size, at least a safe cell is left)
+  pub fn try_with_mines(w: u16, h: u16, q: &[(u16, u16)]) ->
+    Result<Self, MineFieldError> {
+    let n = Self::check_size(w, h)?;
+    if let Some(&(r, c)) = q.iter().find(|&&(r, c)| r >= h || c >= w) {
+      return Err(MineFieldError::OutOfBounds(r, c));
+    }
+    let f = Self::with_mines(w, h, q);
+    if f.m as u32 >= n { return Err(MineFieldError::TooManyMines(f.m)); }
+    Ok(f)
   }
 
-  /// refresh
+  /// refresh (cells in the viewport, see draw)
   pub fn refresh<T>(&self, g: &mut impl TView<T>) ->
-    Result<(), Box<dyn Error>> {
-    for (r, v) in self.f.iter().enumerate() {
-      for (c, u) in v.iter().enumerate() {
-        let ur = r as u16;
-        let uc = c as u16;
-        let (s, bgc, fgc) = self.c(ur, uc, *u)?;
-        g.wr(Packet{x: uc, y: ur, st: 3, bgc, fgc, msg: &s})?;
+    Result<(), MineFieldError> {
+    Ok(self.draw(&mut ViewSink::new(g))?)
+  }
+
+  /// screen columns per cell (cfg.cell_width at least 1)
+  pub fn cw(&self) -> u16 { self.cfg.cell_width.max(1) }
+
+  /// wr_cell glyph and colors o of the cell at viewport (x, y)
+  /// (screen x * cw, padded with spaces)
+  pub fn wr_cell<T>(&self, g: &mut impl TView<T>, x: u16, y: u16,
+    o: &(String, u16, u16)) -> Result<(), MineFieldError> {
+    Ok(self.put_cell(&mut ViewSink::new(g), x, y, style::BASE, o)?)
+  }
+
+  /// set_viewport size (full redraw at the next refresh_dirty)
+  pub fn set_viewport(&mut self, w: u16, h: u16) -> () {
+    self.vp.resize(w, h, self.w, self.h);
+    self.vp.follow(self.r, self.c);
+    self.invalidate();
+  }
+
+  /// reveal_mines through closed cells not flagged in theme.peek colors
+  /// (debug only, the state is not altered, full redraw at the next
+  /// refresh_dirty)
+  pub fn reveal_mines(&mut self, b: bool) -> () {
+    self.peek = b;
+    self.invalidate();
+  }
+
+  /// on_resize of the screen to cols x rows for the board (viewport of
+  /// cols / cw x rows cells, cursor kept in view, full redraw at the next
+  /// refresh_dirty)
+  pub fn on_resize(&mut self, cols: u16, rows: u16) -> () {
+    self.set_viewport(cols / self.cw(), rows);
+  }
+
+  /// scroll viewport by (dx, dy) for manual panning
+  pub fn scroll(&mut self, dx: i32, dy: i32) -> bool {
+    let b = self.vp.scroll(dx, dy, self.w, self.h);
+    if b { self.invalidate(); }
+    b
+  }
+
+  /// auto scroll to keep the cursor visible
+  pub fn follow(&mut self) -> bool {
+    let b = self.vp.follow(self.r, self.c);
+    if b { self.invalidate(); }
+    b
+  }
+
+  /// render visible board to a String (rows joined by '\n')
+  pub fn render_string(&self) -> String { self.to_string() }
+
+  /// set_theme (full redraw at the next refresh_dirty)
+  pub fn set_theme(&mut self, theme: Theme) -> () {
+    self.theme = theme;
+    self.invalidate();
+  }
+
+  /// set_mask of playable cells [r][c] (full redraw at the next
+  /// refresh_dirty)
+  pub fn set_mask(&mut self, mask: Vec<Vec<bool>>) -> () {
+    assert!(mask.len() == self.h as usize &&
+      mask.iter().all(|v| v.len() == self.w as usize), "mask must be h x w");
+    self.mask = Some(mask);
+    self.invalidate();
+  }
+
+  /// is_playable (not masked out)
+  pub fn is_playable(&self, r: u16, c: u16) -> bool {
+    self.get(r, c).is_some() &&
+      self.mask.as_ref().is_none_or(|v| v[r as usize][c as usize])
+  }
+
+  /// playable cells count
+  pub fn playable(&self) -> u16 {
+    self.mask.as_ref().map_or(self.w*self.h, |v|
+      v.iter().flatten().filter(|&&b| b).count() as u16)
+  }
+
+  /// set_placement strategy (before the first click)
+  pub fn set_placement(&mut self, p: impl Placement + 'static) -> () {
+    self.placement = Box::new(p);
+  }
+
+  /// set_feedback hooks
+  pub fn set_feedback(&mut self, fb: impl Feedback + 'static) -> () {
+    self.fb = Some(Box::new(fb));
+  }
+
+  /// set_glyphs (full redraw at the next refresh_dirty)
+  pub fn set_glyphs(&mut self, glyphs: Glyphs) -> () {
+    self.glyphs = glyphs;
+    self.invalidate();
+  }
+
+  /// refresh by runs of the same style and colors on each row
+  /// (multi char glyphs are written one by one)
+  pub fn refresh_run<T>(&self, g: &mut impl TViewRun<T>) ->
+    Result<(), MineFieldError> {
+    let cw = self.cw();
+    for y in 0..self.vp.h {
+      let r = self.vp.y + y;
+      let mut run = (0, 0, 0, 0, String::new());
+      for x in 0..self.vp.w {
+        let c = self.vp.x + x;
+        let (s, bgc, fgc) = self.compose(r, c, x, y)?;
+        let st = self.style(r, c);
+        let one = s.chars().count() == 1;
+        let same = (st, bgc, fgc) == (run.1, run.2, run.3);
+        if !run.4.is_empty() && (!one || !same) {
+          g.wr_run(run.0, y, run.1, run.2, run.3, &run.4)?;
+          run.4.clear();
+        }
+        if !one { g.wr_run(x * cw, y, st, bgc, fgc, &s)?; continue; }
+        if run.4.is_empty() { run = (x * cw, st, bgc, fgc, run.4); }
+        run.4.push_str(&format!("{:<1$}", s, cw as usize));
+      }
+      if !run.4.is_empty() {
+        g.wr_run(run.0, y, run.1, run.2, run.3, &run.4)?;
       }
     }
-    Ok(())
+    Ok(g.flush()?)
+  }
+
+  /// refresh only cells changed since the last refresh_dirty (see
+  /// draw_dirty)
+  pub fn refresh_dirty<T>(&mut self, g: &mut impl TView<T>) ->
+    Result<(), MineFieldError> {
+    Ok(self.draw_dirty(&mut ViewSink::new(g))?)
+  }
+
+  /// invalidate (full redraw at the next refresh_dirty)
+  pub fn invalidate(&mut self) -> () {
+    self.d = vec![vec![None; self.vp.w as usize]; self.vp.h as usize];
   }
 
   /// c
+  ///
   /// upper 4bit
   /// - 7 1: force open at ending, 0: normal
   /// - 6 1: flag, 0: as is
   /// - 5 1: question, 0: as is
   /// - 4 1: open, 0: close
+  ///
   /// lower 4bit
   /// - 0-3 0: '_', 1-8: num, 9-14: skip, 15: '@' mine
   pub fn c(&self, r: u16, c: u16, u: u8) ->
-    Result<(String, u16, u16), Box<dyn Error>> {
-    let f = "L*??PPPP++++++++".chars().collect::<Vec<_>>(); // 4 bit upper
-    let s = "_12345678......@".chars().collect::<Vec<_>>(); // 4 bit lower
+    Result<(String, u16, u16), MineFieldError> {
+    self.check(r, c)?;
+    if self.paused { return Ok(self.c_paused(r, c)); }
+    if !self.is_visible(r, c) {
+      let k = self.theme.fog;
+      return Ok((self.glyphs.fog.clone(), k.0, k.1));
+    }
+    let u = if self.hidden.contains(&(r, c)) { u & !0x10 } else { u };
+    let f = &self.glyphs.upper; // 4 bit upper
+    let s = &self.glyphs.lower; // 4 bit lower
     let v = Self::get_v(u);
-    let n = if self.is_opened(r, c) { s[v as usize] } else { f[0] };
+    let k = if self.cfg.question { u >> 4 } else { (u >> 4) & !0x02 };
+    let n = if Self::is_o(u) { &s[v as usize] } else { &f[k as usize] };
     let curs = r == self.r && c == self.c;
-    let o = if !curs || self.is_success() { n } else { // through
-      if self.is_explosion() && Self::is_mine(v) { f[1] } // may be always mine
-      else { if self.is_blink() { f[15] } else { n } } // blink or through
-    };
-    let (bgc, fgc): (u16, u16) = if Self::is_e(u) { (4, 5) }
-      else if Self::is_o(u) { (2, 3) }
-      else { (0, 1) };
-    Ok((String::from_utf8(vec![o as u8])?, bgc, fgc))
+    let mut k = self.theme.get(if self.cfg.question { u } else { u & !0x20 });
+    let n = if !self.is_playable(r, c) {
+      k = self.theme.void;
+      &self.glyphs.void
+    } else if Self::is_o(u) && !Self::is_e(u) && Self::is_mine(v) {
+      k = self.theme.defused;
+      &self.glyphs.defused
+    } else { n };
+    if self.x == Some((r, c)) {
+      let k = self.theme.detonated;
+      return Ok((f[1].clone(), k.0, k.1));
+    }
+    if Self::is_e(u) && Self::is_f(u) && !Self::is_mine(v) {
+      let k = self.theme.wrong;
+      return Ok((self.glyphs.wrong.clone(), k.0, k.1));
+    }
+    if curs && !self.is_success() && self.is_blink() { // the true cell
+      k = self.theme.cursor.unwrap_or((k.1, k.0)); // inverse video
+    }
+    Ok((n.clone(), k.0, k.1))
   }
 
-  /// is_blink
-  pub fn is_blink(&self) -> bool { self.t < self.b / 2 }
+  /// c while paused (message on the middle row of the viewport)
+  pub fn c_paused(&self, r: u16, c: u16) -> (String, u16, u16) {
+    let g = &self.glyphs;
+    let n = g.pause_msg.chars().count() as u16;
+    let x = self.vp.x + self.vp.w.saturating_sub(n) / 2;
+    let o = if r != self.vp.y + self.vp.h / 2 || c < x { None }
+      else { g.pause_msg.chars().nth((c - x) as usize) };
+    let k = self.theme.closed;
+    (o.map_or_else(|| g.pause.clone(), |ch| ch.to_string()), k.0, k.1)
+  }
 
-  /// tick and control blink cursor
-  pub fn tick<T>(&mut self, g: &mut impl TView<T>) ->
-    Result<(), Box<dyn Error>> {
-    self.t += 1;
-    if self.t == self.b / 2 { self.refresh(g)?; }
-    else if self.t >= self.b { self.reset_tick(g)?; }
-    Ok(())
+  /// is_visible through the fog of war (within cfg.fog of the cursor or
+  /// an opened cell, everything after the end)
+  pub fn is_visible(&self, r: u16, c: u16) -> bool {
+    let Some(d) = self.cfg.fog else { return true; };
+    if self.is_end() || (r.abs_diff(self.r) <= d && c.abs_diff(self.c) <= d) {
+      return true;
+    }
+    let (re, ce) = (r.saturating_add(d).min(self.h - 1),
+      c.saturating_add(d).min(self.w - 1));
+    (r.saturating_sub(d)..=re).any(|j|
+      (c.saturating_sub(d)..=ce).any(|i| self.is_opened(j, i)))
   }
 
-  /// reset tick
-  pub fn reset_tick<T>(&mut self, g: &mut impl TView<T>) ->
-    Result<(), Box<dyn Error>> {
-    self.t = 0;
-    self.refresh(g)?;
-    Ok(())
+  /// is_paused
+  pub fn is_paused(&self) -> bool { self.paused }
+
+  /// pause (timer stopped, board concealed)
+  pub fn pause(&mut self) -> bool {
+    if self.paused || self.is_end() { return false; }
+    self.paused = true;
+    self.tm.stop();
+    true
+  }
+
+  /// resume
+  pub fn resume(&mut self) -> bool {
+    if !self.paused { return false; }
+    self.paused = false;
+    if self.p { self.tm.start(); }
+    true
+  }
+
+  /// elapsed time
+  pub fn elapsed(&self) -> std::time::Duration { self.tm.elapsed() }
+
+  /// remaining time of cfg.time_limit (None: no countdown)
+  pub fn remaining(&self) -> Option<std::time::Duration> {
+    self.cfg.time_limit.map(|n|
+      std::time::Duration::from_secs(n as u64).saturating_sub(self.elapsed()))
+  }
+
+  /// check_time and expire when the time budget is over
+  pub fn check_time(&mut self) -> Option<TimerEvent> {
+    if self.is_end() || self.remaining()? > std::time::Duration::ZERO {
+      return None;
+    }
+    self.expire();
+    Some(TimerEvent::TimeExpired)
   }
 
+  /// expire (lost by the time budget)
+  pub fn expire(&mut self) -> () {
+    self.expired = true;
+    self.tm.stop();
+    self.update_splits();
+    if let Some(fb) = &mut self.fb { fb.on_expire(); }
+  }
+
+  /// is_blink (cursor shown in theme.cursor or inverse colors)
+  pub fn is_blink(&self) -> bool { self.k }
+
   /// up
-  pub fn up(&mut self) -> () { if self.r > 0 { self.r -= 1; } }
+  pub fn up(&mut self) -> () {
+    if self.r > 0 { self.r -= 1; }
+    else if self.cfg.wrap_y { self.r = self.h - 1; }
+    self.follow();
+  }
 
   /// down
-  pub fn down(&mut self) -> () { if self.r < self.h - 1 { self.r += 1; } }
+  pub fn down(&mut self) -> () {
+    if self.r < self.h - 1 { self.r += 1; }
+    else if self.cfg.wrap_y { self.r = 0; }
+    self.follow();
+  }
 
   /// left
-  pub fn left(&mut self) -> () { if self.c > 0 { self.c -= 1; } }
+  pub fn left(&mut self) -> () {
+    if self.c > 0 { self.c -= 1; }
+    else if self.cfg.wrap_x { self.c = self.w - 1; }
+    self.follow();
+  }
 
   /// right
-  pub fn right(&mut self) -> () { if self.c < self.w - 1 { self.c += 1; } }
+  pub fn right(&mut self) -> () {
+    if self.c < self.w - 1 { self.c += 1; }
+    else if self.cfg.wrap_x { self.c = 0; }
+    self.follow();
+  }
+
+  /// jump_to_edge of the field in direction d (false: already there)
+  pub fn jump_to_edge(&mut self, d: Direction) -> bool {
+    let (r, c) = match d {
+    Direction::Up => (0, self.c),
+    Direction::Down => (self.h - 1, self.c),
+    Direction::Left => (self.r, 0),
+    Direction::Right => (self.r, self.w - 1)
+    };
+    if (r, c) == (self.r, self.c) { return false; }
+    self.goto(r, c)
+  }
+
+  /// jump_to_next_closed (not opened nor flagged) cell in direction d
+  /// skipping across opened regions (false: none, cursor stays)
+  pub fn jump_to_next_closed(&mut self, d: Direction) -> bool {
+    let (dr, dc) = d.delta();
+    let (mut r, mut c) = (self.r as i32, self.c as i32);
+    loop {
+      r += dr;
+      c += dc;
+      if r < 0 || c < 0 || r >= self.h as i32 || c >= self.w as i32 {
+        return false;
+      }
+      let (r, c) = (r as u16, c as u16);
+      if !self.is_opened(r, c) && !self.is_flagged(r, c) &&
+        self.is_playable(r, c) { return self.goto(r, c); }
+    }
+  }
+
+  /// home (top left, false: already there)
+  pub fn home(&mut self) -> bool {
+    if (self.r, self.c) == (0, 0) { return false; }
+    self.goto(0, 0)
+  }
 
   /// click
   pub fn click(&mut self) -> bool {
-    if self.s == 0 { self.start(); } // at the first time
-    if !self.is_opened(self.r, self.c) {
-      if !self.open(self.r, self.c) { self.explosion(); }
-      else {
-        if self.s + self.m == self.w*self.h { self.success(); } // not '>='
-      }
+    if self.is_end() || self.paused { return false; }
+    if !self.p { self.start(); } // at the first time
+    self.tm.start();
+    let (r, c) = (self.r, self.c);
+    self.record(Move::Open(r, c));
+    let b = self.is_playable(r, c);
+    if !self.is_opened(r, c) && !self.is_flagged(r, c) && b {
+      self.ck.opens += 1;
+      if !self.open(r, c) { self.hit(r, c); }
+      else { self.check_win(); }
     }
+    else { self.ck.wasted += 1; }
+    self.update_splits();
+    self.overlay_probabilities();
     true
   }
 
-  /// update_m
+  /// chord (open closed neighbors when flags around the cursor match)
+  pub fn chord(&mut self) -> bool {
+    let (r, c) = (self.r, self.c);
+    if self.is_end() || self.paused { return false; }
+    self.record(Move::Chord(r, c));
+    let v = Self::get_v(self.f[r as usize][c as usize]) as usize;
+    let q = self.neighbors(r, c).collect::<Vec<_>>();
+    if !self.is_opened(r, c) ||
+      q.iter().filter(|&&(j, i)| self.is_flagged(j, i)).count() != v {
+      self.ck.wasted += 1;
+      return false;
+    }
+    let mut b = false;
+    for (j, i) in q {
+      if self.is_opened(j, i) || self.is_flagged(j, i) { continue; }
+      b = true;
+      if !self.open(j, i) && self.hit(j, i) { break; }
+    }
+    if b { self.ck.chords += 1; } else { self.ck.wasted += 1; }
+    self.check_win();
+    self.update_splits();
+    self.overlay_probabilities();
+    b
+  }
+
+  /// lives remaining
+  pub fn lives(&self) -> u8 { self.cfg.lives.max(1).saturating_sub(self.hits) }
+
+  /// hit a mine at (r, c) (defused while lives remain, true: explosion,
+  /// (r, c) in the field)
+  fn hit(&mut self, r: u16, c: u16) -> bool {
+    self.hits = self.hits.saturating_add(1);
+    if self.lives() > 0 {
+      Self::set_o(&mut self.f[r as usize][c as usize], false); // not counted
+      if let Some(fb) = &mut self.fb { fb.on_defuse(r, c); }
+      self.check_win();
+      return false;
+    }
+    self.x = Some((r, c));
+    self.explosion();
+    true
+  }
+
+  /// defused mines count
+  pub fn defused(&self) -> u16 {
+    self.cells()
+      .filter(|(_, _, u)| u.is_open() && !u.is_forced() && u.is_mine())
+      .count() as u16
+  }
+
+  /// neighbors (r, c) around (playable only)
+  pub fn neighbors(&self, r: u16, c: u16) ->
+    impl Iterator<Item=(u16, u16)> + '_ {
+    let (re, ce) = ((r + 1).min(self.h - 1), (c + 1).min(self.w - 1));
+    (r.saturating_sub(1)..=re).flat_map(move |j|
+      (c.saturating_sub(1)..=ce).map(move |i| (j, i)))
+      .filter(move |&(j, i)| (j, i) != (r, c) && self.is_playable(j, i))
+  }
+
+  /// goto (board r, c)
+  pub fn goto(&mut self, r: u16, c: u16) -> bool {
+    if r >= self.h || c >= self.w { return false; }
+    self.r = r;
+    self.c = c;
+    self.follow();
+    true
+  }
+
+  /// update_m (screen x, y in the viewport, x / cw for the column)
   pub fn update_m(&mut self, x: u16, y: u16) -> bool {
-    if x < self.w && y < self.h { // always ( x >= 0 && y >= 0 )
-      self.c = x;
-      self.r = y;
+    let x = x / self.cw();
+    if x < self.vp.w && y < self.vp.h { // always ( x >= 0 && y >= 0 )
+      self.c = self.vp.x + x;
+      self.r = self.vp.y + y;
       true
     } else {
       false
@@ -173,28 +743,175 @@ impl MineField {
 
   /// is_opened
   pub fn is_opened(&self, r: u16, c: u16) -> bool {
-    Self::is_o(self.f[r as usize][c as usize])
+    self.get(r, c).is_some_and(|u| u.is_open())
   }
 
-  /// open
+  /// open (nothing opened out of the field, see try_open)
   pub fn open(&mut self, r: u16, c: u16) -> bool {
-    let n = &mut self.f[r as usize][c as usize];
-    let v = Self::get_v(*n);
-    if Self::is_mine(v) { return false; } // explosion
-    Self::set_o(n, false);
+    self.open_cells(r, c).is_some()
+  }
+
+  /// open and return the cells opened in BFS order (None: explosion,
+  /// nothing for a cell opened, flagged or out of the field)
+  pub fn open_cells(&mut self, r: u16, c: u16) -> Option<Vec<(u16, u16)>> {
+    if self.check(r, c).is_err() || self.is_opened(r, c) ||
+      self.is_flagged(r, c) { return Some(vec![]); }
+    let w = self.open_waves(r, c)?; // explosion
+    self.collect(&w);
+    let q: Vec<(u16, u16)> = w.iter().flatten().copied().collect();
+    if let Some(fb) = &mut self.fb { fb.on_open(r, c, q.len() as u16); }
+    if self.cfg.animate && w.len() > 1 {
+      for v in &w[1..] { self.hidden.extend(v.iter().copied()); }
+      self.cascade.extend(w.into_iter().skip(1));
+    }
+    Some(q)
+  }
+
+  /// open_report (open a closed cell as a click does and report what was
+  /// revealed, nothing for a cell opened, flagged, masked or out of the
+  /// field, or a game over)
+  pub fn open_report(&mut self, r: u16, c: u16) -> OpenResult {
+    if self.is_end() || self.is_opened(r, c) || self.is_flagged(r, c) ||
+      !self.is_playable(r, c) { return OpenResult::default(); }
+    if !self.p { self.goto(r, c); self.start(); }
+    self.tm.start();
+    let opened = match self.open_cells(r, c) {
+    Some(q) => { self.check_win(); q },
+    None => if self.hit(r, c) { vec![] } else { vec![(r, c)] }
+    };
+    OpenResult{opened, exploded: self.is_explosion(), finished: self.is_end()}
+  }
+
+  /// open and return flood fill waves in BFS order (None: explosion,
+  /// (r, c) closed in the field)
+  fn open_waves(&mut self, r: u16, c: u16) -> Option<Vec<Vec<(u16, u16)>>> {
+    if Self::is_mine(self.f[r as usize][c as usize]) { return None; }
+    Self::set_o(&mut self.f[r as usize][c as usize], false);
     self.s += 1;
-    if v == 0 {
-      let rs = if r > 0 { r - 1 } else { r };
-      let re = if r < self.h - 1 { r + 1 } else { r };
-      let cs = if c > 0 { c - 1 } else { c };
-      let ce = if c < self.w - 1 { c + 1 } else { c };
-      for j in rs..=re {
-        for i in cs..=ce {
-          if j == r && i == c { continue; }
-          if !self.is_opened(j, i) { self.open(j, i); } // always success
+    let mut w = vec![vec![(r, c)]];
+    loop {
+      let mut n = vec![];
+      for &(j, i) in w.last().unwrap() {
+        if Self::get_v(self.f[j as usize][i as usize]) != 0 { continue; }
+        for (y, x) in self.neighbors(j, i).collect::<Vec<_>>() {
+          if self.is_opened(y, x) || self.is_flagged(y, x) { continue; }
+          Self::set_o(&mut self.f[y as usize][x as usize], false); // no mine
+          self.s += 1;
+          n.push((y, x));
         }
       }
+      if n.is_empty() { break; }
+      w.push(n);
+    }
+    Some(w)
+  }
+
+  /// reveal the next wave of the cascade animation (false: nothing pending)
+  pub fn step_cascade(&mut self) -> bool {
+    let Some(v) = self.cascade.pop_front() else { return false; };
+    for p in v { self.hidden.remove(&p); }
+    true
+  }
+
+  /// is_flagged
+  pub fn is_flagged(&self, r: u16, c: u16) -> bool {
+    self.get(r, c).is_some_and(|u| u.is_flag())
+  }
+
+  /// flags count
+  pub fn flags(&self) -> u16 {
+    self.cells().filter(|(_, _, u)| u.is_flag()).count() as u16
+  }
+
+  /// mark cycle closed -> flag -> question (when cfg.question) -> closed
+  /// (flag is skipped when no flags are left of cfg.max_flags)
+  pub fn mark(&mut self) -> bool {
+    if self.is_end() || self.paused { return false; }
+    self.record(Move::Mark(self.r, self.c));
+    if self.is_opened(self.r, self.c) || !self.is_playable(self.r, self.c) {
+      self.ck.wasted += 1;
+      return false;
+    }
+    let e = self.flags_left() == Some(0);
+    let q = self.cfg.question;
+    let u = &mut self.f[self.r as usize][self.c as usize];
+    if Self::is_f(*u) { *u &= !0x40; if q { *u |= 0x20; } }
+    else if Self::is_q(*u) { *u &= !0x20; }
+    else if !e { *u |= 0x40; }
+    else if q { *u |= 0x20; }
+    else { self.ck.wasted += 1; return false; }
+    let b = Self::is_f(*u);
+    self.ck.flags += 1;
+    if let Some(fb) = &mut self.fb { fb.on_flag(self.r, self.c, b); }
+    self.check_win();
+    true
+  }
+
+  /// is_won by cfg.win (mines must be placed)
+  pub fn is_won(&self) -> bool {
+    let o = self.opened() + self.m == self.playable(); // not '>='
+    let f = || self.f.iter().flatten().all(|&u|
+      if Self::is_mine(u) { Self::is_f(u) || Self::is_o(u) } // or defused
+      else { !Self::is_f(u) });
+    self.p && match self.cfg.win {
+    WinCondition::Opened => o,
+    WinCondition::Flagged => f(),
+    WinCondition::Both => o && f()
+    }
+  }
+
+  /// check_win and set success (false: not won or already ended)
+  pub fn check_win(&mut self) -> bool {
+    if self.is_end() || !self.is_won() { return false; }
+    self.success();
+    true
+  }
+
+  /// auto_flag closed neighbors of every open number satisfied by its
+  /// closed neighbors count (returns flagged count)
+  pub fn auto_flag(&mut self) -> u16 {
+    if self.is_end() || self.paused { return 0; }
+    let mut q = vec![];
+    for r in 0..self.h {
+      for c in 0..self.w {
+        let u = self.f[r as usize][c as usize];
+        let v = Self::get_v(u) as usize;
+        if !Self::is_o(u) || v == 0 { continue; }
+        let n = self.neighbors(r, c).filter(|&(j, i)| !self.is_opened(j, i))
+          .collect::<Vec<_>>();
+        if n.len() == v { q.extend(n); }
+      }
     }
+    let (flags, mut k) = (self.flags(), 0);
+    for (r, c) in q {
+      let u = &mut self.f[r as usize][c as usize];
+      if Self::is_f(*u) { continue; }
+      if self.cfg.max_flags.is_some_and(|n| flags + k >= n) { break; }
+      *u = (*u & !0x20) | 0x40;
+      k += 1;
+      if let Some(fb) = &mut self.fb { fb.on_flag(r, c, true); }
+    }
+    self.check_win();
+    k
+  }
+
+  /// finish (open all closed cells not flagged when the flags count equals
+  /// the mines, explosion when a flag is wrong)
+  pub fn finish(&mut self) -> bool {
+    if self.is_end() || self.paused || !self.p ||
+      self.flags() + self.defused() != self.m { return false; }
+    self.tm.start();
+    self.record(Move::Finish);
+    for r in 0..self.h {
+      for c in 0..self.w {
+        if self.is_opened(r, c) || self.is_flagged(r, c) ||
+          !self.is_playable(r, c) { continue; }
+        if !self.open(r, c) && self.hit(r, c) { break; }
+      }
+    }
+    self.check_win();
+    self.update_splits();
+    self.overlay_probabilities();
     true
   }
 
@@ -202,52 +919,125 @@ impl MineField {
   pub fn is_explosion(&self) -> bool { self.s & 0x8000 != 0 }
 
   /// explosion
-  pub fn explosion(&mut self) -> () { self.s |= 0x8000; }
+  pub fn explosion(&mut self) -> () {
+    self.s |= 0x8000;
+    self.tm.stop();
+    let (r, c) = self.x.unwrap_or((self.r, self.c));
+    if let Some(fb) = &mut self.fb { fb.on_explode(r, c); }
+  }
 
   /// is_success
   pub fn is_success(&self) -> bool { self.s & 0x4000 != 0 }
 
   /// success
-  pub fn success(&mut self) -> () { self.s |= 0x4000; }
+  pub fn success(&mut self) -> () {
+    if self.cfg.auto_finish {
+      for u in self.f.iter_mut().flatten() {
+        if Self::is_mine(*u) { *u = (*u & !0x20) | 0x40; }
+      }
+    }
+    self.s |= 0x4000;
+    self.tm.stop();
+    if let Some(fb) = &mut self.fb { fb.on_win(); }
+  }
+
+  /// opened cells count
+  pub fn opened(&self) -> u16 { self.s & 0x3fff }
 
   /// is_end
-  pub fn is_end(&self) -> bool { self.s >= 0x4000 }
+  pub fn is_end(&self) -> bool { self.s >= 0x4000 || self.expired }
 
-  /// ending
+  /// ending (flagged mines stay, misplaced flags X, mines one by one
+  /// over ticks when cfg.slow_reveal)
   pub fn ending<T>(&mut self, g: &mut impl TView<T>) ->
-    Result<(), Box<dyn Error>> {
-    for v in &mut self.f { for u in v { Self::set_o(u, true); } } // all open
+    Result<(), MineFieldError> {
+    self.cascade.clear();
+    self.hidden.clear();
+    for (r, v) in self.f.iter_mut().enumerate() {
+      for (c, u) in v.iter_mut().enumerate() {
+        if self.mask.as_ref().is_some_and(|m| !m[r][c]) { continue; }
+        let mine = Self::is_mine(*u);
+        if mine && (Self::is_f(*u) || Self::is_o(*u)) {
+          continue; // correctly flagged or defused
+        }
+        Self::set_o(u, true); // force open (misplaced flag is rendered X)
+        let p = (r as u16, c as u16);
+        if mine && self.cfg.slow_reveal && self.x != Some(p) {
+          self.hidden.insert(p);
+          self.cascade.push_back(vec![p]);
+        }
+      }
+    }
     self.refresh(g)?;
     Ok(())
   }
 
   /// start
   pub fn start(&mut self) -> () {
-    let e = self.m >= self.w*self.h; // fill all when mine full
-    let mut p: Vec<u16> = (0..self.w*self.h).into_iter().collect();
-    p.shuffle(&mut rand::thread_rng());
-    let mut n = 0;
-    for i in 0..=self.m as usize {
-      if n >= self.m || i >= p.len() { break; }
-      let r = p[i] / self.w;
-      let c = p[i] % self.w;
-      if e || r != self.r || c != self.c { // fill all when mine full
-        Self::set_m(&mut self.f[r as usize][c as usize]);
-        n += 1;
-      }
-    }
-    let f = self.f.clone();
-    for (r, v) in self.f.iter_mut().enumerate() {
-      for (c, u) in v.iter_mut().enumerate() {
-        if Self::is_mine(f[r][c]) { continue; }
-        *u = Self::get_k(self.w, self.h, &f, r as u16, c as u16);
-      }
+    self.first = Some((self.r, self.c));
+    let mut g = rng::Rng::new(self.seed);
+    let q = self.placement.mines(self, &mut g);
+    self.place(&q);
+    let mut p: Vec<u16> = (0..self.w*self.h).collect();
+    g.shuffle(&mut p);
+    self.place_items(&p);
+    ()
+  }
+
+  /// place mines at (r, c) and count neighbors (without a copy of the
+  /// field, rows in parallel with the rayon feature)
+  pub fn place(&mut self, q: &[(u16, u16)]) -> () {
+    for u in self.f.as_mut_slice() {
+      if !Self::is_mine(*u) { *u &= 0xf0; }
     }
+    for &(r, c) in q { Self::set_m(&mut self.f[r as usize][c as usize]); }
+    self.count_neighbors(q);
+    self.p = true;
     ()
   }
 
+  /// count neighbors by the mine positions
+  #[cfg(not(feature = "rayon"))]
+  fn count_neighbors(&mut self, q: &[(u16, u16)]) -> () {
+    for &(r, c) in q {
+      for j in r.saturating_sub(1)..=(r + 1).min(self.h - 1) {
+        for i in c.saturating_sub(1)..=(c + 1).min(self.w - 1) {
+          let u = &mut self.f[j as usize][i as usize];
+          if !Self::is_mine(*u) { *u += 1; }
+        }
+      }
+    }
+  }
+
+  /// count neighbors by a mine bitset of the positions (rows in parallel)
+  #[cfg(feature = "rayon")]
+  fn count_neighbors(&mut self, q: &[(u16, u16)]) -> () {
+    use rayon::prelude::*;
+    let (w, h) = (self.w as usize, self.h as usize);
+    if w == 0 { return; }
+    let mut b = vec![0u64; (w * h).div_ceil(64)];
+    for &(r, c) in q {
+      let i = r as usize * w + c as usize;
+      b[i / 64] |= 1 << (i % 64);
+    }
+    let mine = |j: usize, i: usize| {
+      let k = j * w + i;
+      b[k / 64] >> (k % 64) & 1
+    };
+    self.f.as_mut_slice().par_chunks_mut(w).enumerate().for_each(|(r, v)| {
+      for (c, u) in v.iter_mut().enumerate() {
+        if Self::is_mine(*u) { continue; }
+        let mut n = 0;
+        for j in r.saturating_sub(1)..=(r + 1).min(h - 1) {
+          for i in c.saturating_sub(1)..=(c + 1).min(w - 1) { n += mine(j, i); }
+        }
+        *u |= n as u8;
+      }
+    });
+  }
+
   /// get_k
-  pub fn get_k(w: u16, h: u16, f: &Vec<Vec<u8>>, r: u16, c: u16) -> u8 {
+  pub fn get_k(w: u16, h: u16, f: &Grid, r: u16, c: u16) -> u8 {
     let mut n = 0u8;
     let rs = if r > 0 { r - 1 } else { r };
     let re = if r < h - 1 { r + 1 } else { r };
@@ -277,24 +1067,2184 @@ impl MineField {
   /// is_o
   pub fn is_o(u: u8) -> bool { u & 0x10 != 0 }
 
-  /// set m
-  pub fn set_m(u: &mut u8) -> () { *u = 0x0f; }
+  /// is_f
+  pub fn is_f(u: u8) -> bool { u & 0x40 != 0 }
+
+  /// is_q
+  pub fn is_q(u: u8) -> bool { u & 0x20 != 0 }
+
+  /// set m (keep upper 4bit)
+  pub fn set_m(u: &mut u8) -> () { *u = (*u & 0xf0) | 0x0f; }
 
-  /// is_mine
-  pub fn is_mine(u: u8) -> bool { u == 0x0f }
+  /// is_mine (lower 4bit)
+  pub fn is_mine(u: u8) -> bool { u & 0x0f == 0x0f }
 
   /// get v
   pub fn get_v(u: u8) -> u8 { u & 0x0f }
 }
 
+/// MineField
+impl fmt::Display for MineField {
+  /// fmt as the same glyphs of c()
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (r, v) in self.f.iter().enumerate() {
+      if r > 0 { writeln!(f)?; }
+      for (c, u) in v.iter().enumerate() {
+        let (s, _, _) = self.c(r as u16, c as u16, *u).map_err(|_| fmt::Error)?;
+        f.write_str(&s)?;
+      }
+    }
+    Ok(())
+  }
+}
+
 /// test with [-- --nocapture] or [-- --show-output]
 #[cfg(test)]
 mod tests {
-  // use super::*;
+  use super::*;
+  use std::time;
 
   /// test a
   #[test]
   fn test_a() {
     assert_eq!(true, true);
   }
+  /// test streak
+  #[test]
+  fn test_streak() {
+    let mut k = arcade::Streak::new(30, 20, 10);
+    assert_eq!(k.open(3), vec![arcade::StreakEvent::Streak(1)]);
+    assert_eq!(k.open(1), vec![arcade::StreakEvent::Streak(2),
+      arcade::StreakEvent::Full, arcade::StreakEvent::Bonus(1)]);
+    assert_eq!(k.score, 5);
+    assert_eq!(k.tick(), vec![]);
+    assert_eq!(k.tick(), vec![]);
+    assert_eq!(k.tick(), vec![arcade::StreakEvent::Break(2)]);
+  }
+  /// test mbf
+  #[cfg(feature = "interop")]
+  #[test]
+  fn test_mbf() {
+    let b = vec![3, 2, 0, 2, 0, 0, 2, 1];
+    let f = MineField::from_mbf(&b).unwrap();
+    assert_eq!(f.f, vec![vec![0x0f, 2, 1], vec![1, 2, 0x0f]]);
+    assert_eq!(f.to_mbf().unwrap(), b);
+    assert!(MineField::from_mbf(&[3, 2, 0, 1, 3, 0]).is_err());
+    assert!(matches!(MineField::from_mbf(&[255, 255, 0, 0]),
+      Err(MineFieldError::InvalidDimensions(255, 255))));
+    assert!(matches!(MineField::from_mbf(&[2, 1, 0, 2, 0, 0, 1, 0]),
+      Err(MineFieldError::TooManyMines(2))));
+    assert!(matches!(MineField::from_mbf(&[2, 1, 0, 1, 2, 0]),
+      Err(MineFieldError::OutOfBounds(0, 2))));
+    assert!(matches!(MineField::from_mbf(&[3, 1, 0, 2, 1, 0, 1, 0]),
+      Err(MineFieldError::ParseError(_))));
+    let mut f = MineField::from_seed(3, 2, 1, 0);
+    assert!(matches!(f.to_mbf(), Err(MineFieldError::ParseError(_))));
+    f.click();
+    assert_eq!(f.to_mbf().unwrap()[..4], [3, 2, 0, 1]);
+  }
+  /// test mark
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_mark() {
+    let mut f = MineField::new(3, 1, 0);
+    f.k = false; // cursor without blink
+    assert!(f.mark());
+    assert_eq!(f.c(0, 0, f.f[0][0]).unwrap().0, "P");
+    f.mark();
+    assert_eq!(f.c(0, 0, f.f[0][0]).unwrap().0, "?");
+    f.mark();
+    f.cfg.question = false;
+    f.mark();
+    f.mark();
+    assert_eq!(f.f[0][0], 0);
+    f.mark();
+    f.click(); // flagged cell is not opened
+    assert_eq!(f.opened(), 0);
+    let cfg: Config = "# c\nquestion = false\nunknown=1\n".parse().unwrap();
+    assert_eq!(cfg, Config{question: false, ..Config::default()});
+    assert_eq!(cfg.to_string().parse::<Config>().unwrap(), cfg);
+  }
+  /// test refresh_dirty
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_refresh_dirty() {
+    struct V(usize);
+    impl TView<u16> for V {
+      fn wr(&mut self, _p: impl TPacket) -> Result<(), Box<dyn Error>> {
+        self.0 += 1;
+        Ok(())
+      }
+      fn reg(&mut self, _c: Vec<u16>) -> () {}
+      fn col(&self, n: u16) -> u16 { n }
+    }
+    let mut f = MineField::new(4, 3, 0);
+    let mut v = V(0);
+    f.refresh_dirty(&mut v).unwrap();
+    assert_eq!(v.0, 12);
+    f.refresh_dirty(&mut v).unwrap();
+    assert_eq!(v.0, 12);
+    f.k = false; // blink phase changes the cursor cell only
+    f.refresh_dirty(&mut v).unwrap();
+    assert_eq!(v.0, 13);
+    f.invalidate();
+    f.refresh_dirty(&mut v).unwrap();
+    assert_eq!(v.0, 25);
+  }
+  /// test refresh_run
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_refresh_run() {
+    struct V(Vec<String>);
+    impl TView<u16> for V {
+      fn wr(&mut self, p: impl TPacket) -> Result<(), Box<dyn Error>> {
+        self.0.push(p.as_str().to_string());
+        Ok(())
+      }
+      fn reg(&mut self, _c: Vec<u16>) -> () {}
+      fn col(&self, n: u16) -> u16 { n }
+    }
+    impl TViewRun<u16> for V {
+      fn wr_run(&mut self, _x: u16, _y: u16, _st: u16, _bgc: u16, _fgc: u16,
+        s: &str) -> Result<(), Box<dyn Error>> {
+        self.0.push(s.to_string());
+        Ok(())
+      }
+    }
+    let mut f = MineField::new(4, 2, 0);
+    f.k = false;
+    let mut v = V(vec![]);
+    f.refresh_run(&mut v).unwrap();
+    assert_eq!(v.0, vec!["LLLL", "LLLL"]);
+  }
+  /// test finale
+  #[test]
+  fn test_finale() {
+    let mut p = panel::Finale::new(true, time::Duration::from_millis(42300));
+    p.best = Some(time::Duration::from_secs(40));
+    p.code = Some(String::from("AB12"));
+    assert_eq!(p.lines(), vec!["YOU WIN", "time 42.3s", "best +2.3s", "code AB12"]);
+    assert_eq!(p.size(), (14, 6));
+  }
+  /// test seed
+  #[test]
+  fn test_seed() {
+    let mut a = MineField::from_seed(9, 9, 10, 42);
+    let mut b = MineField::from_seed(9, 9, 10, 42);
+    a.click();
+    b.click();
+    assert_eq!(a.f, b.f);
+    let mut p: Vec<u16> = (0..8).collect();
+    rng::Rng::new(0).shuffle(&mut p);
+    assert_eq!(p, vec![3, 2, 5, 7, 1, 0, 4, 6]); // pinned by the spec
+  }
+  /// test sweep
+  #[test]
+  fn test_sweep() {
+    let d = config::Difficulty::beginner();
+    let v = sim::sweep(0..20, d, &Config::default(), &mut sim::Basic);
+    assert_eq!(v.len(), 20);
+    assert!(v.iter().all(|p| p.outcome != sim::Outcome::Lost));
+    assert!(v.iter().any(|p| p.outcome == sim::Outcome::Won));
+    assert!(v.iter().all(|p| p.unsolvable == (p.outcome != sim::Outcome::Won)));
+  }
+  /// test raw cell
+  #[test]
+  fn test_raw_cell() {
+    for u in 0..=255u8 {
+      if let Ok(c) = cell::RawCell::try_from(u) { assert_eq!(u8::from(c), u); }
+    }
+    let c = cell::RawCell::try_from(0x4fu8).unwrap();
+    assert!(c.is_mine() && c.is_flag() && !c.is_open());
+    assert_eq!(cell::RawCell::try_from(0x13u8).unwrap().count(), Some(3));
+    assert_eq!(cell::RawCell::try_from(0x0a), Err(cell::RawCellError::Value(0x0a)));
+    assert_eq!(cell::RawCell::try_from(0x61), Err(cell::RawCellError::Mark(0x61)));
+    assert_eq!(cell::RawCell::try_from(0x81), Err(cell::RawCellError::Force(0x81)));
+    let mut f = MineField::from_seed(9, 9, 10, 1);
+    f.click();
+    assert!(f.f.iter().flatten().all(|&u| cell::RawCell::try_from(u).is_ok()));
+  }
+  /// test wasm state json
+  #[cfg(feature = "wasm")]
+  #[test]
+  fn test_state_json() {
+    let mut f = MineField::new(2, 1, 0);
+    f.mark();
+    assert_eq!(f.state_json(), "{\"w\":2,\"h\":1,\"m\":0,\"state\":\"playing\",\
+      \"opened\":0,\"r\":0,\"c\":0,\"cells\":[[-2,-1]]}");
+    let mut w = wasm::WasmField::new(3, 1, 0);
+    assert!(w.open(2, 0));
+    assert!(w.updates_json().unwrap().starts_with("[{\"x\":0,\"y\":0,"));
+    assert_eq!(w.updates_json().unwrap(), "[]");
+  }
+  /// test buffer
+  #[test]
+  fn test_buffer() {
+    let mut f = MineField::from_seed(3, 2, 0, 0);
+    f.k = false;
+    let mut b = headless::BufferWR::default();
+    f.refresh(&mut b).unwrap();
+    assert_eq!(b.lines(), vec!["LLL", "LLL"]);
+    f.click();
+    f.refresh(&mut b).unwrap();
+    assert_eq!(b.lines(), vec!["___", "___"]);
+    assert_eq!(b.get(1, 1), Some(&(String::from("_"), 2, 3)));
+    assert_eq!(b.n, 12);
+    f.refresh(&mut headless::NullWR).unwrap();
+  }
+  /// test render string
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_render_string() {
+    let mut f = MineField::new(3, 2, 1);
+    f.place(&[(1, 2)]);
+    f.k = false;
+    f.click();
+    assert_eq!(f.render_string(), "_1L\n_1L");
+    f.k = true;
+    assert_eq!(format!("{}", f), "_1L\n_1L");
+    assert_eq!(f.c(0, 0, f.f[0][0]).unwrap(), (String::from("_"), 3, 2));
+  }
+  /// test theme
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_theme() {
+    let mut f = MineField::new(3, 2, 1);
+    f.place(&[(1, 2)]);
+    f.click();
+    f.right();
+    f.right();
+    f.set_theme(Theme::classic());
+    assert_eq!(f.c(0, 1, f.f[0][1]).unwrap(), (String::from("1"), 2, 6));
+    assert_eq!(f.c(0, 0, f.f[0][0]).unwrap(), (String::from("_"), 2, 3));
+    f.mark();
+    let p = f.glyphs.upper[4].clone();
+    assert_eq!(f.c(0, 2, f.f[0][2]).unwrap(), (p.clone(), 14, 0));
+    f.theme.cursor = Some((7, 7));
+    assert_eq!(f.c(0, 2, f.f[0][2]).unwrap(), (p, 7, 7));
+    assert_eq!(Theme::classic_rgb().len(), 15);
+    assert_eq!(Theme::colorblind_rgb().len(), 24);
+    let (cb, cl) = (Theme::colorblind(), Theme::classic());
+    assert!(cb.num.iter().zip(cl.num.iter()).all(|(a, b)| a != b));
+    let rgb = Theme::colorblind_rgb();
+    assert_eq!(rgb[cb.num[0].1 as usize], (0, 114, 178));
+    f.set_theme(Theme::high_contrast());
+    f.set_glyphs(Glyphs::high_contrast());
+    assert_eq!(f.c(0, 2, f.f[0][2]).unwrap(), (String::from("F"), 1, 0));
+    f.k = false;
+    assert_eq!(f.render_string(), ".1F\n.1#");
+  }
+  /// test glyphs
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_glyphs() {
+    let mut f = MineField::new(3, 1, 1);
+    f.place(&[(0, 1)]);
+    f.k = false;
+    f.set_glyphs(Glyphs::emoji());
+    f.click();
+    f.right();
+    f.mark();
+    assert_eq!(f.render_string(), "１🚩⬜");
+    assert_eq!(Glyphs::boxes().upper.len(), 16);
+  }
+  /// test viewport
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_viewport() {
+    let mut f = MineField::new(10, 10, 0);
+    f.k = false;
+    f.set_viewport(4, 3);
+    let mut b = headless::BufferWR::default();
+    f.refresh(&mut b).unwrap();
+    assert_eq!(b.lines(), vec!["LLLL"; 3]);
+    for _ in 0..5 { f.right(); f.down(); }
+    assert_eq!((f.vp.x, f.vp.y), (2, 3));
+    assert!(f.scroll(100, -1));
+    assert_eq!((f.vp.x, f.vp.y), (6, 2));
+    assert!(f.update_m(0, 0));
+    assert_eq!((f.r, f.c), (2, 6));
+    assert!(!f.update_m(4, 0));
+  }
+  /// test wrap
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_wrap() {
+    let mut f = MineField::new(3, 2, 0);
+    f.left();
+    f.up();
+    assert_eq!((f.r, f.c), (0, 0));
+    f.cfg.wrap_x = true;
+    f.left();
+    f.up();
+    assert_eq!((f.r, f.c), (0, 2));
+    f.right();
+    assert_eq!(f.c, 0);
+  }
+  /// test mouse
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_mouse() {
+    use input::{Handled, MouseAction::*, MouseButton::*};
+    let mut f = MineField::new(3, 3, 1);
+    f.place(&[(0, 0)]);
+    assert_eq!(f.mouse(1, 1, Left, Press), Handled::Opened);
+    assert_eq!(f.mouse(1, 1, Left, Release), Handled::Moved);
+    assert_eq!(f.mouse(0, 0, Right, Press), Handled::Marked);
+    assert!(f.is_flagged(0, 0));
+    assert_eq!(f.mouse(1, 1, Middle, Press), Handled::Chorded);
+    assert!(f.is_success());
+    let mut f = MineField::new(3, 3, 1);
+    f.place(&[(0, 0)]);
+    f.mouse(1, 1, Left, Press);
+    f.mouse(1, 1, Left, Release);
+    f.mouse(0, 0, Right, Press);
+    f.mouse(0, 0, Right, Release);
+    f.mouse(1, 1, Left, Press);
+    assert_eq!(f.mouse(1, 1, Right, Press), Handled::Chorded);
+    assert_eq!(f.mouse(5, 5, Left, Release), Handled::None);
+  }
+  /// test handle
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_handle() {
+    use input::{Handled, Input};
+    let mut f = MineField::new(4, 1, 1);
+    f.place(&[(0, 2)]);
+    let mut b = headless::BufferWR::default();
+    assert_eq!(f.handle(Input::Open, &mut b).unwrap(), Handled::Opened);
+    assert_eq!(f.handle(Input::Right, &mut b).unwrap(), Handled::Moved);
+    assert_eq!(f.handle(Input::Right, &mut b).unwrap(), Handled::Moved);
+    assert_eq!(f.handle(Input::Open, &mut b).unwrap(), Handled::Opened);
+    assert!(f.is_explosion());
+    assert_eq!(b.lines(), vec!["_1*1"]);
+    assert_eq!(f.handle(Input::Open, &mut b).unwrap(), Handled::None);
+    assert_eq!(f.handle(Input::Quit, &mut b).unwrap(), Handled::Quit);
+  }
+  /// test run
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_run() {
+    use input::Input;
+    struct I(Vec<Option<Input>>);
+    impl runner::InputSource for I {
+      fn poll(&mut self, _t: time::Duration) ->
+        Result<Option<Input>, Box<dyn Error>> {
+        Ok(self.0.pop().unwrap_or(Some(Input::Quit)))
+      }
+    }
+    let mut f = MineField::new(3, 1, 1);
+    f.place(&[(0, 2)]);
+    let mut b = headless::BufferWR::default();
+    let mut i = I(vec![Some(Input::Open), None, Some(Input::Right),
+      Some(Input::Right)]);
+    assert_eq!(runner::run(&mut f, &mut b, &mut i).unwrap(), runner::Outcome::Lost);
+    assert_eq!(b.lines(), vec!["_1*"]);
+    let mut f = MineField::new(3, 1, 1);
+    let mut i = I(vec![]);
+    assert_eq!(runner::run(&mut f, &mut b, &mut i).unwrap(), runner::Outcome::Quit);
+  }
+  /// test pause
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_pause() {
+    let mut f = MineField::new(8, 3, 2);
+    f.place(&[(0, 2), (2, 7)]);
+    assert!(!f.resume());
+    f.goto(0, 1);
+    f.click();
+    assert!(f.tm.is_running());
+    assert!(f.pause());
+    assert!(f.is_paused() && !f.tm.is_running());
+    let t = f.elapsed();
+    assert_eq!(f.render_string(), "        \n PAUSED \n        ");
+    assert!(!f.click());
+    assert_eq!(f.elapsed(), t);
+    assert!(f.resume());
+    assert!(f.tm.is_running());
+  }
+  /// test cascade
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_cascade() {
+    let mut f = MineField::new(4, 1, 1);
+    f.place(&[(0, 3)]);
+    f.k = false;
+    f.cfg.animate = true;
+    let mut g = MineField::new(4, 1, 1);
+    g.place(&[(0, 3)]);
+    assert_eq!(g.open_waves(0, 0).unwrap(),
+      vec![vec![(0, 0)], vec![(0, 1)], vec![(0, 2)]]);
+    f.click();
+    assert!(f.is_success());
+    assert_eq!(f.render_string(), "_LLL");
+    assert!(f.step_cascade());
+    assert_eq!(f.render_string(), "__LL");
+    assert!(f.step_cascade());
+    assert!(!f.step_cascade());
+    assert_eq!(f.render_string(), "__1L");
+  }
+  /// test loss reveal
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_loss_reveal() {
+    let mut f = MineField::new(4, 2, 3);
+    f.place(&[(0, 0), (0, 3), (1, 3)]);
+    f.k = false;
+    f.cfg.slow_reveal = true;
+    f.goto(0, 0);
+    f.mark(); // correct
+    f.goto(1, 0);
+    f.mark(); // wrong
+    f.goto(0, 3);
+    f.click();
+    assert!(f.is_explosion());
+    f.ending(&mut headless::NullWR).unwrap();
+    assert_eq!(f.render_string(), "P12*\nX12+");
+    assert_eq!(f.c(0, 3, f.f[0][3]).unwrap().1, f.theme.detonated.0);
+    assert!(f.step_cascade());
+    assert!(!f.step_cascade());
+    assert_eq!(f.render_string(), "P12*\nX12@");
+  }
+  /// test feedback
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_feedback() {
+    use std::{cell::RefCell, rc::Rc};
+    struct Log(Rc<RefCell<Vec<String>>>);
+    impl feedback::Feedback for Log {
+      fn on_open(&mut self, r: u16, c: u16, n: u16) -> () {
+        self.0.borrow_mut().push(format!("open {} {} {}", r, c, n));
+      }
+      fn on_flag(&mut self, r: u16, c: u16, b: bool) -> () {
+        self.0.borrow_mut().push(format!("flag {} {} {}", r, c, b));
+      }
+      fn on_explode(&mut self, r: u16, c: u16) -> () {
+        self.0.borrow_mut().push(format!("explode {} {}", r, c));
+      }
+      fn on_win(&mut self) -> () { self.0.borrow_mut().push("win".into()); }
+    }
+    let l = Rc::new(RefCell::new(vec![]));
+    let mut f = MineField::new(3, 1, 1);
+    f.place(&[(0, 1)]);
+    f.set_feedback(Log(l.clone()));
+    f.goto(0, 1);
+    f.mark();
+    f.goto(0, 0);
+    f.click();
+    assert_eq!(*l.borrow(), ["flag 0 1 true", "open 0 0 1"]);
+    f.goto(0, 2);
+    f.click();
+    assert_eq!(l.borrow()[2..], ["open 0 2 1", "win"]);
+    let mut f = MineField::new(2, 1, 1);
+    f.place(&[(0, 1)]);
+    f.set_feedback(feedback::Bell(vec![]));
+    f.goto(0, 1);
+    f.click();
+    assert!(f.is_explosion());
+  }
+  /// test status
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_status() {
+    let mut f = MineField::new(20, 2, 3);
+    f.place(&[(0, 0), (0, 1), (1, 8)]);
+    f.goto(0, 0);
+    f.mark();
+    assert_eq!(f.status(), "  2   0.0s playing");
+    let mut g = headless::BufferWR::default();
+    f.render_status(&mut g, 2).unwrap();
+    assert_eq!(g.lines()[2], "  2   0.0s playing  ");
+    f.goto(0, 1);
+    f.click();
+    assert!(f.status().ends_with("s lost"));
+  }
+  /// test describe
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_describe() {
+    let mut f = MineField::new(3, 3, 2);
+    f.place(&[(0, 0), (0, 2)]);
+    f.goto(0, 0);
+    f.mark();
+    f.goto(1, 1);
+    f.click();
+    assert_eq!(f.describe(1, 1),
+      "row 2 column 2: open, 2 adjacent mines; 7 unopened neighbors, 1 flagged neighbor");
+    assert_eq!(f.describe(0, 0), "row 1 column 1: flagged");
+    assert_eq!(f.describe(2, 2), "row 3 column 3: unopened");
+    assert_eq!(f.describe(3, 0), "outside the field");
+    assert_eq!(f.describe_state(0, 3), "outside the field");
+    assert_eq!(f.describe_region(0..2, 0..9),
+      "rows 1 to 2, columns 1 to 3: 1 open, 4 unopened, 1 flag");
+  }
+  /// test cell width
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_cell_width() {
+    let mut f = MineField::new(3, 1, 1);
+    f.place(&[(0, 2)]);
+    f.k = false;
+    f.cfg.cell_width = 2;
+    let mut b = headless::BufferWR::default();
+    f.refresh(&mut b).unwrap();
+    assert_eq!(b.lines(), vec!["L L L "]);
+    assert!(f.update_m(5, 0));
+    assert_eq!(f.c, 2);
+    assert!(f.update_m(2, 0));
+    f.click();
+    let mut b = headless::BufferWR::default();
+    f.refresh_dirty(&mut b).unwrap();
+    assert_eq!(b.lines(), vec!["L 1 L "]);
+    assert!(!f.update_m(6, 0));
+  }
+  /// test jump
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_jump() {
+    let mut f = MineField::new(6, 3, 1);
+    f.place(&[(2, 5)]);
+    f.goto(0, 3);
+    f.click(); // opens all but the mine
+    f.home();
+    assert!(!f.home());
+    assert!(!f.jump_to_next_closed(Direction::Right));
+    assert!(f.jump_to_edge(Direction::Down));
+    assert!(!f.jump_to_edge(Direction::Down));
+    assert!(f.jump_to_next_closed(Direction::Right));
+    assert_eq!((f.r, f.c), (2, 5));
+    assert!(f.handle(input::Input::Home, &mut headless::NullWR).unwrap()
+      == input::Handled::Moved);
+    assert_eq!((f.r, f.c), (0, 0));
+  }
+  /// test auto flag
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_auto_flag() {
+    let mut f = MineField::new(5, 3, 3);
+    f.place(&[(0, 3), (1, 3), (2, 3)]);
+    f.goto(0, 0);
+    f.click();
+    assert_eq!(f.auto_flag(), 3);
+    assert!((0..3).all(|r| f.is_flagged(r, 3)));
+    assert_eq!(f.auto_flag(), 0);
+  }
+  /// test finish
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_finish() {
+    let mut f = MineField::new(4, 1, 1);
+    f.place(&[(0, 1)]);
+    f.cfg.auto_finish = true;
+    f.goto(0, 0);
+    f.click();
+    assert!(!f.finish());
+    f.goto(0, 2);
+    f.click();
+    f.goto(0, 3);
+    f.click();
+    assert!(f.is_success() && f.is_flagged(0, 1));
+    let mut f = MineField::new(4, 1, 1);
+    f.place(&[(0, 1)]);
+    f.goto(0, 1);
+    f.mark();
+    assert!(f.finish());
+    assert!(f.is_success());
+    let mut f = MineField::new(4, 1, 1);
+    f.place(&[(0, 1)]);
+    f.goto(0, 3);
+    f.mark();
+    assert!(f.finish());
+    assert!(f.is_explosion() && f.x == Some((0, 1)));
+  }
+  /// test win condition
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_win_condition() {
+    let mut f = MineField::new(3, 1, 1);
+    f.place(&[(0, 2)]);
+    f.cfg.win = WinCondition::Flagged;
+    f.goto(0, 1);
+    f.mark();
+    assert!(!f.is_end());
+    f.mark();
+    f.mark();
+    f.goto(0, 2);
+    f.mark();
+    assert!(f.is_success());
+    let mut f = MineField::new(3, 1, 1);
+    f.place(&[(0, 2)]);
+    f.cfg.win = WinCondition::Both;
+    f.click();
+    f.right();
+    f.click();
+    assert!(!f.is_end());
+    f.right();
+    f.mark();
+    assert!(f.is_success());
+    assert_eq!("win=both".parse::<Config>().unwrap().win, WinCondition::Both);
+  }
+  /// test lives
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_lives() {
+    let mut f = MineField::new(4, 1, 2);
+    f.place(&[(0, 0), (0, 3)]);
+    f.k = false;
+    f.cfg.lives = 2;
+    f.click();
+    assert!(!f.is_end());
+    assert_eq!((f.lives(), f.defused(), f.opened()), (1, 1, 0));
+    assert_eq!(f.render_string(), "%LLL");
+    assert_eq!(f.c(0, 0, f.f[0][0]).unwrap().1, f.theme.defused.0);
+    f.goto(0, 1);
+    f.click();
+    f.goto(0, 2);
+    f.click();
+    assert!(f.is_success());
+    f.ending(&mut headless::NullWR).unwrap();
+    assert_eq!(f.render_string(), "%11@");
+    let mut f = MineField::new(4, 1, 2);
+    f.place(&[(0, 0), (0, 3)]);
+    f.cfg.lives = 2;
+    f.click();
+    f.goto(0, 3);
+    f.click();
+    assert!(f.is_explosion() && f.x == Some((0, 3)));
+  }
+  /// test flag budget
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_flag_budget() {
+    let mut f = MineField::new(3, 1, 1);
+    f.cfg.max_flags = Some(1);
+    assert_eq!(f.toggle_flag(), Ok(true));
+    assert_eq!(f.flags_left(), Some(0));
+    f.right();
+    assert_eq!(f.toggle_flag(), Err(flag::FlagError::Budget(1)));
+    assert!(f.mark() && MineField::is_q(f.f[0][1]));
+    f.left();
+    assert_eq!(f.toggle_flag(), Ok(false));
+    let c: Config = f.cfg.to_string().parse().unwrap();
+    assert_eq!(c.max_flags, Some(1));
+    assert_eq!(Config::default().to_string().parse::<Config>().unwrap(),
+      Config::default());
+  }
+  /// test countdown
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_countdown() {
+    let mut f = MineField::new(3, 1, 1);
+    f.place(&[(0, 2)]);
+    f.cfg.time_limit = Some(10);
+    assert_eq!(f.check_time(), None);
+    f.tm.acc = time::Duration::from_secs(10);
+    assert_eq!(f.check_time(), Some(timer::TimerEvent::TimeExpired));
+    assert!(f.is_end() && !f.is_explosion() && !f.click());
+    assert!(f.status().ends_with("0.0s time up"));
+    assert_eq!(f.check_time(), None);
+    let mut f = MineField::new(3, 1, 1);
+    f.cfg.time_limit = Some(0);
+    let mut b = headless::BufferWR::default();
+    blink::Blinker::default().tick(&mut f, &mut b).unwrap();
+    assert!(f.expired && b.n > 0);
+  }
+  /// test campaign
+  #[test]
+  fn test_campaign() {
+    let d = config::Difficulty{w: 3, h: 1, m: 1};
+    let mut k = campaign::Campaign::new(d, Config::default(), 0);
+    assert!(!k.advance());
+    k.field.place(&[(0, 2)]);
+    k.field.click();
+    assert!(k.field.is_success() && k.advance());
+    assert_eq!((k.level, k.score, k.opened, k.field.m), (1, 1, 2, 2));
+    assert_eq!(k.field.seed, 1);
+    assert_eq!(k.mines(100), 2);
+    k.field.place(&[(0, 0), (0, 1)]);
+    k.field.goto(0, 1);
+    k.field.click();
+    assert!(k.is_over() && !k.advance());
+  }
+  /// test fog
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_fog() {
+    let mut f = MineField::new(6, 1, 1);
+    f.place(&[(0, 5)]);
+    f.k = false;
+    f.cfg.fog = Some(1);
+    assert_eq!(f.render_string(), "LL~~~~");
+    f.goto(0, 3);
+    assert_eq!(f.render_string(), "~~LLL~");
+    f.goto(0, 0);
+    f.click();
+    assert_eq!(f.render_string(), "____1L");
+    f.goto(0, 5);
+    f.click();
+    f.ending(&mut headless::NullWR).unwrap();
+    assert!(!f.render_string().contains('~'));
+  }
+  /// test treasure
+  #[test]
+  fn test_treasure() {
+    let mut f = MineField::from_seed(4, 2, 1, 0);
+    f.cfg.treasures = 2;
+    f.cfg.powerups = 1;
+    f.start();
+    assert_eq!(f.items.len(), 3);
+    assert!(f.items.keys().all(|&(r, c)|
+      !MineField::is_mine(f.f[r as usize][c as usize]) && (r, c) != (0, 0)));
+    let (&p, &item) = f.items.iter().next().unwrap();
+    f.goto(p.0, p.1);
+    f.click();
+    let v = f.take_found();
+    assert!(v.contains(&treasure::Found{item, r: p.0, c: p.1}));
+    assert!(!f.items.contains_key(&p) && f.take_found().is_empty());
+  }
+  /// test mask
+  #[test]
+  fn test_mask() {
+    let mut f = MineField::from_seed(3, 3, 2, 7);
+    f.set_mask(vec![vec![false, true, false], vec![true; 3],
+      vec![false, true, false]]);
+    f.k = false;
+    assert_eq!(f.playable(), 5);
+    f.goto(1, 1);
+    f.click();
+    assert!((0..3).all(|r| (0..3).all(|c|
+      f.is_playable(r, c) || !MineField::is_mine(f.f[r as usize][c as usize]))));
+    assert_eq!(f.render_string().lines().next().unwrap().chars().next(),
+      Some(' '));
+    f.goto(0, 0);
+    assert!(!f.mark());
+    assert_eq!(f.toggle_flag(), Err(flag::FlagError::Masked));
+    for (r, c) in [(0, 1), (1, 0), (1, 2), (2, 1)] {
+      if !MineField::is_mine(f.f[r as usize][c as usize]) {
+        f.goto(r, c);
+        f.click();
+      }
+    }
+    assert!(f.is_success());
+  }
+  /// test stencil
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_stencil() {
+    let mut f = MineField::from_stencil(" # \n###\n #", 1).unwrap();
+    f.k = false;
+    assert_eq!((f.w, f.h, f.playable()), (3, 3, 5));
+    assert_eq!(f.render_string(), " L \nLLL\n L ");
+    assert!(MineField::from_stencil("#", 1).is_err());
+    assert!(MineField::from_stencil("", 0).is_err());
+  }
+  /// test pbm
+  #[cfg(feature = "pbm")]
+  #[test]
+  fn test_pbm() {
+    let v = stencil::pbm_mask(b"P1\n# heart\n3 2\n101\n0 1 0\n").unwrap();
+    let f = MineField::from_mask_seed(v, 1, 0).unwrap();
+    assert_eq!((f.w, f.h, f.playable()), (3, 2, 3));
+    assert!(f.is_playable(0, 2) && !f.is_playable(1, 0));
+    let v = stencil::pbm_mask(b"P4 9 1\n\xff\x80").unwrap();
+    let f = MineField::from_mask_seed(v, 1, 0).unwrap();
+    assert_eq!((f.w, f.playable()), (9, 9));
+    assert!(stencil::pbm_mask(b"P1 2 2 1").is_err());
+  }
+  /// test with mines
+  #[test]
+  fn test_with_mines() {
+    let mut f = MineField::with_mines(3, 2, &[(1, 2), (0, 0), (1, 2)]);
+    f.k = false;
+    assert_eq!((f.m, f.p), (2, true));
+    f.goto(1, 0);
+    f.click();
+    assert_eq!(f.render_string(), "LLL\n1LL");
+    assert_eq!(MineField::get_v(f.f[0][1]), 2);
+    assert!(matches!(MineField::try_with_mines(3, 2, &[(2, 0)]),
+      Err(MineFieldError::OutOfBounds(2, 0))));
+    assert!(matches!(MineField::try_with_mines(2, 1, &[(0, 0), (0, 1)]),
+      Err(MineFieldError::TooManyMines(2))));
+    assert!(MineField::try_with_mines(0, 2, &[]).is_err());
+  }
+  /// test editor
+  #[test]
+  fn test_editor() {
+    let mut e = editor::Editor::new(3, 2).unwrap();
+    assert!(e.add_mine(0, 0) && e.add_mine(1, 2) && !e.add_mine(1, 2));
+    assert_eq!(e.to_string(), "@21\n12@");
+    assert!(e.remove_mine(0, 0) && !e.remove_mine(0, 0));
+    assert_eq!(e.to_string(), "_11\n_1@");
+    assert_eq!(e.mines(), vec![(1, 2)]);
+    let mut f = e.into_minefield();
+    f.click();
+    f.goto(0, 2);
+    f.click();
+    assert!(f.is_success() && f.m == 1);
+    let mut e = editor::Editor::new(2, 1).unwrap();
+    assert!(e.add_mine(0, 0) && !e.add_mine(0, 1)); // a safe cell is left
+    assert!(!e.is_mine(5, 5) && !e.add_mine(5, 5));
+    assert!(e.into_minefield().validate().is_ok());
+    assert!(matches!(editor::Editor::new(200, 100),
+      Err(MineFieldError::InvalidDimensions(200, 100))));
+  }
+  /// test density
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_density() {
+    assert_eq!(MineField::new_density(9, 9, 0.123).unwrap().m, 10);
+    assert_eq!(MineField::new_density(30, 16, 0.20625).unwrap().m, 99);
+    assert_eq!(MineField::new_density(1, 2, 0.99).unwrap().m, 1);
+    assert!(MineField::new_density(9, 9, 1.0).is_err());
+    assert!(MineField::new_density(9, 9, f64::NAN).is_err());
+    assert!(MineField::new_density(0, 9, 0.1).is_err());
+    assert!(matches!(MineField::new_density(200, 100, 0.1),
+      Err(MineFieldError::InvalidDimensions(200, 100))));
+  }
+  /// test placement
+  #[test]
+  fn test_placement() {
+    let n = |f: &MineField| (0..f.h).flat_map(|r| (0..f.w).map(move |c|
+      (r, c))).filter(|&(r, c)| MineField::is_mine(f.f[r as usize][c as usize]))
+      .map(|(r, c)| f.neighbors(r, c).filter(|&(j, i)|
+        MineField::is_mine(f.f[j as usize][i as usize])).count())
+      .sum::<usize>();
+    let mut a = MineField::from_seed(16, 16, 40, 5);
+    a.set_placement(placement::Clustered(16));
+    a.start();
+    let mut b = MineField::from_seed(16, 16, 40, 5);
+    b.set_placement(placement::Spread(16));
+    b.start();
+    assert!(n(&a) > n(&b));
+    let mut f = MineField::from_seed(9, 9, 20, 5);
+    f.goto(4, 4);
+    f.set_placement(placement::Border(100));
+    f.start();
+    assert_eq!((0..81).filter(|&i| MineField::is_mine(f.f[i / 9][i % 9])).count(),
+      20);
+    assert!(!MineField::is_mine(f.f[4][4]));
+    let mut f = MineField::from_seed(2, 1, 2, 5);
+    f.set_placement(placement::Spread::default());
+    f.start();
+    assert!(MineField::is_mine(f.f[0][0]) && MineField::is_mine(f.f[0][1]));
+  }
+  /// test daily
+  #[test]
+  fn test_daily() {
+    let a = MineField::daily(2024, 2, 29).unwrap();
+    let b = MineField::daily(2024, 2, 29).unwrap();
+    let c = MineField::daily(2024, 3, 1).unwrap();
+    assert!(a.p && a.f == b.f && a.fingerprint() == b.fingerprint());
+    assert_ne!(a.fingerprint(), c.fingerprint());
+    assert!(!MineField::is_mine(a.f[8][8]));
+    assert!(MineField::daily(2023, 2, 29).is_err());
+    assert!(MineField::daily(2024, 13, 1).is_err());
+    let f = MineField::with_mines(2, 1, &[(0, 1)]);
+    assert_eq!(f.fingerprint(), daily::fnv1a(&[2, 0, 1, 0, 1, 0, 0, 0]));
+  }
+  /// test board code
+  #[test]
+  fn test_board_code() {
+    let mut f = MineField::from_seed(9, 9, 10, 0xab);
+    assert_eq!(f.board_code(), "9x9-10-00000000000000ab");
+    f.goto(3, 4);
+    f.click();
+    assert_eq!(f.board_code(), "9x9-10-00000000000000ab-3.4");
+    let g = MineField::from_board_code(&f.board_code()).unwrap();
+    assert_eq!(g.fingerprint(), f.fingerprint());
+    assert!(MineField::from_board_code("9x9-81-0").is_err());
+    assert!(MineField::from_board_code("9x9-10-0-9.0").is_err());
+    assert!(!MineField::from_board_code("9x9-10-0").unwrap().p);
+  }
+  /// test metrics
+  #[test]
+  fn test_metrics() {
+    let f = MineField::with_mines(5, 3, &[(0, 2), (2, 2)]);
+    let k = f.metrics();
+    assert_eq!((k.openings, k.bbbv, k.safe), (2, 3, 13));
+    assert!(k.difficulty > 0.0);
+    let f = MineField::with_mines(3, 1, &[(0, 1)]);
+    assert_eq!(f.bbbv(), 2);
+    let d = config::Difficulty::beginner();
+    let s = metrics::find_seed(d, 4, 4, 20..=30, 0..100).unwrap();
+    let mut f = MineField::from_seed(9, 9, 10, s);
+    f.goto(4, 4);
+    f.start();
+    assert!((20..=30).contains(&f.bbbv()));
+  }
+  /// test efficiency
+  #[test]
+  fn test_efficiency() {
+    let mut f = MineField::with_mines(3, 1, &[(0, 1)]);
+    assert_eq!(f.efficiency(), None);
+    f.click();
+    f.click(); // wasted
+    f.right();
+    f.mark();
+    f.chord(); // wasted
+    f.right();
+    f.click();
+    assert!(f.is_success());
+    assert_eq!(f.ck, metrics::Clicks{opens: 2, chords: 0, flags: 1, wasted: 2});
+    assert_eq!(f.efficiency(), Some(0.4));
+  }
+  /// test stats
+  #[test]
+  fn test_stats() {
+    let d = config::Difficulty::beginner();
+    let mut s = stats::Stats::default();
+    assert_eq!(s.record(d, true, time::Duration::from_secs(30)), None);
+    assert_eq!(s.record(d, true, time::Duration::from_secs(20)),
+      Some(time::Duration::from_secs(30)));
+    s.record(d, false, time::Duration::from_secs(5));
+    s.record(d, true, time::Duration::from_secs(25));
+    assert_eq!((s.played, s.won, s.lost, s.streak, s.best_streak),
+      (4, 3, 1, 1, 2));
+    assert_eq!(s.best(d), Some(time::Duration::from_secs(20)));
+    assert_eq!(s.win_rate(), 0.75);
+    let mut f = MineField::with_mines(2, 1, &[(0, 1)]);
+    assert!(!s.record_field(&f));
+    f.click();
+    assert!(s.record_field(&f) && s.best.contains_key("2x1-1"));
+  }
+  /// test stats json
+  #[cfg(feature = "serde")]
+  #[test]
+  fn test_stats_json() {
+    let mut s = stats::Stats::default();
+    s.record(config::Difficulty::expert(), true, time::Duration::from_secs(99));
+    let p = std::env::temp_dir().join("minefield_test_stats.json");
+    s.save(&p).unwrap();
+    assert_eq!(stats::Stats::load(&p).unwrap(), s);
+    std::fs::remove_file(&p).unwrap();
+  }
+  /// test leaderboard
+  #[test]
+  fn test_leaderboard() {
+    let d = config::Difficulty::beginner();
+    let ms = time::Duration::from_millis;
+    let mut b = leaderboard::Leaderboard::new(2);
+    assert_eq!(b.insert(d, "a", ms(3000), (2024, 1, 2)), Some(1));
+    assert_eq!(b.insert(d, "b", ms(1500), (2024, 1, 3)), Some(1));
+    assert_eq!(b.insert(d, "c", ms(4000), (2024, 1, 4)), None);
+    assert_eq!(b.insert(d, "d e", ms(2000), (2024, 1, 5)), Some(2));
+    assert_eq!(b.top(d).iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+      ["b", "d e"]);
+    let s = b.to_string();
+    assert_eq!(s, "9x9-10 1500 2024-01-03 b\n9x9-10 2000 2024-01-05 d e\n");
+    assert_eq!(s.parse::<leaderboard::Leaderboard>().unwrap().top(d), b.top(d));
+  }
+  /// test splits
+  #[test]
+  fn test_splits() {
+    let mut f = MineField::with_mines(5, 1, &[(0, 2)]);
+    f.set_splits(true);
+    assert_eq!(f.bbbv(), 2);
+    f.goto(0, 0);
+    f.click();
+    let sp = f.sp.unwrap();
+    assert!(sp.first.is_some() && sp.q[1].is_some() && sp.q[2].is_none());
+    assert_eq!((sp.bbbv, f.bbbv_done(), sp.end), (2, 1, None));
+    f.goto(0, 4);
+    f.click();
+    let sp = f.sp.unwrap();
+    assert!(f.is_success() && sp.q[2].is_some() && sp.end.is_some());
+  }
+  /// test replay ghost
+  #[test]
+  fn test_replay() {
+    let mut f = MineField::from_seed(8, 8, 10, 7);
+    f.goto(4, 4);
+    f.click();
+    f.goto(0, 0);
+    f.mark();
+    let rp = f.replay();
+    assert_eq!(rp.mv.iter().map(|&(_, m)| m).collect::<Vec<_>>(),
+      [replay::Move::Open(4, 4), replay::Move::Mark(0, 0)]);
+    let rp: replay::Replay = rp.to_string().parse().unwrap();
+    let mut g = replay::Ghost::new(rp);
+    let live = MineField::from_seed(8, 8, 10, 7);
+    assert_eq!(g.pace(&live), replay::Pace::Behind(f.opened()));
+    assert!(g.is_done() && g.f.f == f.f);
+    assert_eq!(g.cells().count(), f.opened() as usize);
+  }
+  /// test history notation
+  #[test]
+  fn test_history() {
+    use replay::{Move, col_name, parse_cell};
+    assert_eq!((col_name(0), col_name(25), col_name(26)),
+      ("a".to_string(), "z".to_string(), "aa".to_string()));
+    assert_eq!(parse_cell("ab12").unwrap(), (11, 27));
+    assert!(parse_cell("a0").is_err() && parse_cell("5").is_err());
+    for m in [Move::Open(4, 2), Move::Chord(0, 0), Move::Flag(1, 30),
+      Move::Mark(2, 3), Move::Finish] {
+      assert_eq!(m.to_string().parse::<Move>().unwrap(), m);
+    }
+    assert_eq!(Move::Chord(4, 2).to_string(), "#:c5");
+    let mut f = MineField::from_seed(8, 8, 10, 7);
+    f.goto(4, 2);
+    f.click();
+    f.goto(0, 0);
+    let _ = f.toggle_flag();
+    assert_eq!(f.history().len(), 2);
+    let s = f.history_text();
+    let l: Vec<&str> = s.lines().map(|l| l.split_once(' ').unwrap().1)
+      .collect();
+    assert_eq!(l, ["o:c5", "f:a1"]);
+  }
+  /// test apply_move
+  #[test]
+  fn test_apply_move() {
+    use apply::MoveError;
+    use replay::Move;
+    let mut f = MineField::with_mines(4, 1, &[(0, 3)]);
+    assert_eq!(f.apply_move(Move::Open(1, 0)), Err(MoveError::OutOfBounds(1, 0)));
+    assert_eq!(f.apply_move(Move::Chord(0, 0)), Err(MoveError::NotOpened));
+    let o = f.apply_move(Move::Flag(0, 0)).unwrap();
+    assert_eq!(o.marked, [(0, 0, 0x40)]);
+    assert_eq!(f.apply_move(Move::Open(0, 0)), Err(MoveError::Flagged));
+    f.apply_move(Move::Flag(0, 0)).unwrap();
+    let o = f.apply_move(Move::Open(0, 0)).unwrap();
+    assert_eq!(o.opened, [(0, 0, 0), (0, 1, 0), (0, 2, 1)]);
+    assert!(o.won && !o.exploded);
+    assert_eq!(f.apply_move(Move::Flag(0, 1)), Err(MoveError::Ended));
+  }
+  /// test sync
+  #[test]
+  fn test_sync() {
+    use replay::Move;
+    use sync::{Delta, Sync, Update};
+    let (mut a, mut b) = (MineField::from_seed(8, 8, 10, 3),
+      MineField::from_seed(8, 8, 10, 3));
+    let (mut sa, mut sb) = (Sync::new(), Sync::new());
+    let v = sa.play(&mut a, Move::Open(4, 4)).unwrap();
+    assert_eq!(v[0], Update{n: 0, d: Delta::Start(4, 4)});
+    for u in v {
+      let u: Update = u.to_string().parse().unwrap();
+      assert!(sb.apply(&mut b, u).unwrap());
+    }
+    assert_eq!((a.f.clone(), a.opened()), (b.f.clone(), b.opened()));
+    let (r, c) = (0..64).map(|i| (i / 8, i % 8))
+      .find(|&(r, c)| !b.is_opened(r, c)).unwrap();
+    let v = sb.play(&mut b, Move::Flag(r, c)).unwrap(); // flag vs open race
+    assert_eq!(v, [Update{n: 0, d: Delta::Mark(r, c, 0x40)}]);
+    let x = b.f[r as usize][c as usize] & 0x0f;
+    let o = Update{n: sb.rx, d: Delta::Open(r, c, x)};
+    assert!(sb.apply(&mut b, o).unwrap() && !b.is_flagged(r, c));
+    assert!(sb.apply(&mut b, o).is_err()); // out of order
+  }
+  /// test versus
+  #[test]
+  fn test_versus() {
+    use versus::{Versus, VersusEvent};
+    let mut v = Versus::new(config::Difficulty::beginner(), 5);
+    assert_eq!(v.f[0].f, v.f[1].f);
+    assert_eq!((v.leader(), v.winner()), (None, None));
+    let e = v.play(1, replay::Move::Open(4, 4)).unwrap();
+    assert!(matches!(e[0], VersusEvent::Opened(1, n) if n > 0));
+    assert_eq!(v.leader(), Some(1));
+    assert!(v.progress(1).done > 0 && v.progress(0).done == 0);
+    let (r, c) = (0..81).map(|i| (i / 9, i % 9))
+      .find(|&(r, c)| MineField::is_mine(v.f[0].f[r as usize][c as usize]))
+      .unwrap();
+    let e = v.play(0, replay::Move::Open(r, c)).unwrap();
+    assert!(e.contains(&VersusEvent::Exploded(0)));
+    assert_eq!(v.winner(), Some(1));
+    assert!(v.is_over());
+  }
+  /// test wire format
+  #[test]
+  fn test_wire() {
+    use wire::{Snapshot, decode_updates, encode_updates};
+    let mut f = MineField::from_seed(16, 16, 40, 9);
+    f.goto(8, 8);
+    f.click();
+    f.goto(0, 0);
+    f.mark();
+    let s = Snapshot::of(&f, false);
+    let b = s.encode();
+    assert!(b.len() < 256);
+    assert_eq!(Snapshot::decode(&b).unwrap(), s);
+    let h = Snapshot::of(&f, true);
+    let d = Snapshot::decode(&h.encode()).unwrap();
+    assert_eq!(d, h);
+    assert!(d.f.iter().flatten().all(|&u| MineField::is_o(u) || u & 0x0f == 0));
+    let mut e = b.clone();
+    e[2] = 99;
+    assert!(Snapshot::decode(&e).is_err());
+    let bad = |t: &Snapshot| Snapshot::decode(&t.encode()).is_err();
+    assert!(bad(&Snapshot{w: 0, h: 0, f: vec![], ..s.clone()}));
+    assert!(bad(&Snapshot{m: 256, ..s.clone()}));
+    assert!(bad(&Snapshot{s: s.s + 1, ..s.clone()}));
+    let mut sy = sync::Sync::new();
+    let mut g = MineField::from_seed(8, 8, 10, 3);
+    let v = sy.play(&mut g, replay::Move::Open(4, 4)).unwrap();
+    assert_eq!(decode_updates(&encode_updates(&v)).unwrap(), v);
+  }
+  /// test spectator
+  #[test]
+  fn test_spectator() {
+    let mut a = MineField::from_seed(8, 8, 10, 3);
+    let s = wire::Snapshot::of(&a, true);
+    assert!(spectator::SpectatorView::new(&wire::Snapshot::of(&a, false), 0)
+      .is_err());
+    let mut v = spectator::SpectatorView::new(&s, 0).unwrap();
+    let mut sy = sync::Sync::new();
+    let u = sy.play(&mut a, replay::Move::Open(4, 4)).unwrap();
+    for u in wire::decode_updates(&wire::encode_updates(&u)).unwrap() {
+      v.apply(u).unwrap();
+    }
+    let f = v.field();
+    assert_eq!(f.opened(), a.opened());
+    for r in 0..8 {
+      for c in 0..8 {
+        let u = f.f[r as usize][c as usize];
+        if a.is_opened(r, c) { assert_eq!(u, a.f[r as usize][c as usize]); }
+        else { assert_eq!(u & 0x0f, 0); }
+      }
+    }
+  }
+  /// test replay verify
+  #[test]
+  fn test_verify() {
+    use replay::VerifyError;
+    let mut f = MineField::from_seed(9, 9, 10, 11);
+    f.goto(4, 4);
+    f.click();
+    let (r, c) = (0..81).map(|i| (i / 9, i % 9))
+      .find(|&(r, c)| MineField::is_mine(f.f[r as usize][c as usize]))
+      .unwrap();
+    f.goto(r, c);
+    f.click();
+    let rp: replay::Replay = f.replay().to_string().parse().unwrap();
+    assert_eq!(rp.verify(11), Ok(sim::Outcome::Lost));
+    assert_eq!(rp.verify(12), Err(VerifyError::Seed(11, 12)));
+    let mut x = rp.clone();
+    x.claim = Some((sim::Outcome::Won, rp.duration()));
+    assert_eq!(x.verify(11),
+      Err(VerifyError::Result(sim::Outcome::Won, sim::Outcome::Lost)));
+    let mut x = rp.clone();
+    x.mv.push((rp.duration(), replay::Move::Open(0, 0)));
+    assert_eq!(x.verify(11), Err(VerifyError::AfterEnd(2)));
+  }
+  /// test typed errors
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_error() {
+    use error::MineFieldError;
+    assert!(matches!(MineField::try_new(0, 3, 1),
+      Err(MineFieldError::InvalidDimensions(0, 3))));
+    assert!(matches!(MineField::try_new(3, 3, 9),
+      Err(MineFieldError::TooManyMines(9))));
+    assert!(matches!(MineField::new_density(3, 3, 1.5),
+      Err(MineFieldError::InvalidDensity(_))));
+    struct V;
+    impl TView<u16> for V {
+      fn wr(&mut self, _p: impl TPacket) -> Result<(), Box<dyn Error>> {
+        Err("broken pipe".into())
+      }
+      fn reg(&mut self, _c: Vec<u16>) -> () {}
+      fn col(&self, n: u16) -> u16 { n }
+    }
+    let f = MineField::from_seed(3, 3, 1, 0);
+    let e = f.refresh(&mut V).unwrap_err();
+    assert!(matches!(e, MineFieldError::RenderError(_)));
+    assert_eq!(e.source().unwrap().to_string(), "broken pipe");
+  }
+  /// test bounds checked accessors
+  #[test]
+  fn test_get() {
+    let mut f = MineField::with_mines(3, 2, &[(1, 2)]);
+    assert!(f.get(2, 0).is_none() && f.get(0, 3).is_none());
+    assert!(f.get(1, 2).unwrap().is_mine());
+    assert!(!f.is_opened(9, 9) && !f.is_flagged(9, 9) && !f.is_playable(9, 9));
+    assert!(matches!(f.try_open(5, 0),
+      Err(error::MineFieldError::OutOfBounds(5, 0))));
+    assert!(matches!(f.c(0, 7, 0),
+      Err(error::MineFieldError::OutOfBounds(0, 7))));
+    assert!(f.open(7, 7) && f.opened() == 0);
+    assert!(f.try_open(0, 0).unwrap() && f.get(0, 0).unwrap().is_open());
+    assert!(!f.try_open(1, 2).unwrap());
+  }
+  /// test cell iterators
+  #[test]
+  fn test_cells() {
+    let f = MineField::with_mines(3, 2, &[(1, 2)]);
+    assert_eq!(f.cells().count(), 6);
+    assert_eq!(f.cells().filter(|(_, _, u)| u.is_mine()).map(|(r, c, _)| (r, c))
+      .collect::<Vec<_>>(), [(1, 2)]);
+    let v: Vec<Vec<u8>> = f.rows().map(|r| r.map(|u| u.value()).collect())
+      .collect();
+    assert_eq!(v, [[0, 1, 1], [0, 1, 15]]);
+    assert_eq!(f.neighbor_cells(0, 0).filter(|(_, _, u)| u.count() == Some(1))
+      .count(), 2);
+  }
+  /// test open_report
+  #[test]
+  fn test_open_report() {
+    let mut f = MineField::with_mines(4, 2, &[(1, 3)]);
+    f.cfg.lives = 2;
+    let o = f.open_report(1, 3);
+    assert_eq!(o, OpenResult{opened: vec![(1, 3)], exploded: false,
+      finished: false});
+    let o = f.open_report(0, 0);
+    assert_eq!(o.opened.len(), 6);
+    assert_eq!(o.opened[0], (0, 0));
+    assert!(!o.finished && !o.exploded);
+    assert_eq!(f.open_report(0, 1), OpenResult::default());
+    assert!(f.open_report(0, 3).finished && f.is_success());
+    let mut f = MineField::with_mines(2, 1, &[(0, 1)]);
+    assert!(f.open_report(0, 1).exploded);
+  }
+  /// test flat grid storage
+  #[test]
+  fn test_grid() {
+    let mut f = MineField::with_mines(3, 2, &[(1, 2)]);
+    assert_eq!(f.f.as_slice(), [0, 1, 1, 0, 1, 15]);
+    assert_eq!((f.f.len(), f.f.width(), f.f.at(1, 2), f.f.at(0, 3)),
+      (2, 3, Some(15), None));
+    f.f[0][1] |= 0x40;
+    assert_eq!(f.f.at(0, 1), Some(0x41));
+    assert_eq!(f.f.to_rows(), vec![vec![0, 0x41, 1], vec![0, 1, 15]]);
+    assert_eq!(grid::Grid::from(f.f.to_rows()), f.f);
+    assert!(f.f == vec![vec![0, 0x41, 1], vec![0, 1, 15]]);
+  }
+  /// test storage backends
+  #[test]
+  fn test_storage() {
+    use storage::{Packed, Storage, place};
+    let mut f = MineField::from_seed(30, 16, 99, 4);
+    f.goto(8, 15);
+    f.click();
+    f.goto(0, 0);
+    f.mark();
+    let p: Packed = f.to_storage();
+    assert_eq!(p.bytes(), 30 * 16 / 2 + 4 * 8 * 8);
+    assert_eq!(p.count_bits(0x10), f.opened() as usize);
+    assert_eq!(p.count_bits(0x40), f.flags() as usize);
+    let g: grid::Grid = f.to_storage();
+    assert_eq!(g, f.f);
+    let mut e = MineField::from_seed(30, 16, 99, 4);
+    assert!(e.load_storage(&p) && e.f == f.f);
+    let q: Vec<(u16, u16)> = f.cells().filter(|(_, _, u)| u.is_mine())
+      .map(|(r, c, _)| (r, c)).collect();
+    let mut k = Packed::with_size(30, 16);
+    place(&mut k, &q);
+    assert!(f.cells().all(|(r, c, u)| k.cell(r, c) == u.value()));
+  }
+  /// test place counts
+  #[test]
+  fn test_place_counts() {
+    let mut f = MineField::from_seed(30, 16, 99, 2);
+    f.goto(8, 15);
+    f.start();
+    for r in 0..16 {
+      for c in 0..30 {
+        let u = f.f[r as usize][c as usize];
+        if MineField::is_mine(u) { continue; }
+        assert_eq!(u, MineField::get_k(30, 16, &f.f, r, c));
+      }
+    }
+    let q: Vec<(u16, u16)> = f.cells().filter(|(_, _, u)| u.is_mine())
+      .map(|(r, c, _)| (r, c)).collect();
+    let g = f.f.clone();
+    f.place(&q); // again
+    assert_eq!(f.f, g);
+  }
+  /// test incremental mines
+  #[test]
+  fn test_add_remove_mine() {
+    let mut f = MineField::from_seed(16, 16, 40, 8);
+    f.goto(0, 0);
+    f.start();
+    let (r, c) = (0..256).map(|i| (i / 16, i % 16))
+      .find(|&(r, c)| !f.is_opened(r, c) && !MineField::is_mine(f.f[r as usize][c as usize]))
+      .unwrap();
+    assert!(f.add_mine(r, c) && !f.add_mine(r, c) && f.m == 41);
+    let mut g = MineField::from_seed(16, 16, 0, 0);
+    let q: Vec<(u16, u16)> = f.cells().filter(|(_, _, u)| u.is_mine())
+      .map(|(r, c, _)| (r, c)).collect();
+    g.place(&q);
+    assert_eq!(g.f, f.f);
+    assert!(f.remove_mine(r, c) && !f.remove_mine(r, c) && f.m == 40);
+    assert!(!f.add_mine(99, 0));
+  }
+  /// test frame
+  #[test]
+  fn test_frame() {
+    let mut f = MineField::with_mines(4, 3, &[(2, 3)]);
+    f.set_viewport(3, 2);
+    f.goto(1, 2);
+    f.follow();
+    let fr = f.frame().unwrap();
+    assert_eq!((fr.w, fr.h, fr.cw, fr.cursor), (3, 2, 1, Some((2, 1))));
+    assert_eq!(fr.status, f.status());
+    let o = f.c(1, 2, f.f[1][2]).unwrap();
+    assert_eq!(fr.cells[1][2], frame::FrameCell{glyph: o.0, bgc: o.1, fgc: o.2,
+      st: f.style(1, 2)});
+    f.goto(2, 3);
+    f.scroll(-3, -2);
+    assert_eq!(f.frame().unwrap().cursor, None);
+  }
+  /// test colors
+  #[test]
+  fn test_color() {
+    use color::{Color, NamedColor, Palette};
+    let p = Palette::classic();
+    assert_eq!(p.get(1), Color::Rgb(255, 255, 255));
+    assert_eq!(p.get(99), Color::Indexed(99));
+    assert_eq!(Palette::named().v.len(), p.v.len());
+    assert_eq!(Color::Named(NamedColor::Red).rgb(), Some((255, 0, 0)));
+    assert_eq!(Color::Rgb(255, 0, 0).ansi256(), 196);
+    assert_eq!(Color::Named(NamedColor::White).ansi256(), 15);
+  }
+  /// test sink with an associated error
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_sink() {
+    struct S(Vec<(u16, u16, String)>);
+    impl sink::Sink for S {
+      type Error = u8;
+      fn put(&mut self, x: u16, y: u16, _st: u16, _bgc: u16, _fgc: u16,
+        s: &str) -> Result<(), u8> {
+        if y > 0 { return Err(7); }
+        self.0.push((x, y, s.to_string()));
+        Ok(())
+      }
+    }
+    let mut f = MineField::new(3, 1, 0);
+    f.cfg.cell_width = 2;
+    let mut s = S(vec![]);
+    f.draw(&mut s).unwrap();
+    assert_eq!(s.0.len(), 6);
+    assert_eq!(s.0[1], (1, 0, " ".to_string()));
+    f.draw_dirty(&mut s).unwrap();
+    assert_eq!(s.0.len(), 12);
+    f.draw_dirty(&mut s).unwrap();
+    assert_eq!(s.0.len(), 12);
+    let mut f = MineField::new(1, 2, 0);
+    assert!(matches!(f.draw_dirty(&mut S(vec![])), Err(sink::DrawError::Sink(7))));
+  }
+  /// test packet str
+  #[test]
+  fn test_packet_str() {
+    let mut g = headless::BufferWR::default();
+    let s = String::from("ab");
+    g.wr(Packet{x: 1, y: 0, st: 3, bgc: 2, fgc: 5, msg: &s}).unwrap();
+    g.wr(Packet{x: 0, y: 1, st: 3, bgc: 0, fgc: 0, msg: "c"}).unwrap();
+    assert_eq!(g.n, 2);
+    assert_eq!(g.lines(), vec![" ab".to_string(), "c".to_string()]);
+    assert_eq!(g.get(2, 0), Some(&("b".to_string(), 2, 5)));
+  }
+  /// test layers
+  #[test]
+  fn test_layers() {
+    use layer::{Layer, Paint};
+    let mut f = MineField::with_mines(4, 3, &[(2, 3)]);
+    f.k = false;
+    let mut g = headless::BufferWR::default();
+    assert!(f.ly.set(Layer::Overlay, 0, 1, Paint::bg(9)));
+    assert!(f.ly.set(Layer::Ui, 0, 0, Paint::new("M", 1, 2)));
+    assert!(!f.ly.set(Layer::Board, 0, 0, Paint::glyph("x")));
+    f.refresh(&mut g).unwrap();
+    assert_eq!(g.get(0, 0), Some(&("M".to_string(), 1, 2)));
+    assert_eq!(g.get(1, 0).unwrap().1, 9);
+    f.refresh_dirty(&mut g).unwrap();
+    let n = g.n;
+    f.refresh_dirty(&mut g).unwrap();
+    assert_eq!(g.n, n);
+    assert!(f.ly.unset(Layer::Ui, 0, 0));
+    f.refresh_dirty(&mut g).unwrap();
+    assert_eq!(g.n, n + 1);
+    assert_ne!(g.glyph(0, 0), "M");
+    f.ly.clear(Layer::Overlay);
+    assert!(f.ly.is_empty());
+    assert_eq!(f.frame().unwrap().cells[0][1].bgc, f.c(0, 1, f.f[0][1]).unwrap().1);
+  }
+  /// test tile
+  #[test]
+  fn test_tile() {
+    use tile::Tile;
+    let mut f = MineField::with_mines(4, 3, &[(0, 3), (2, 3)]);
+    f.cfg.question = true;
+    assert_eq!(f.tile(0, 0).unwrap(), Tile::Closed);
+    assert!(f.goto(0, 2) && f.mark());
+    assert_eq!(f.tile(0, 2).unwrap(), Tile::Flag);
+    assert!(f.mark());
+    assert_eq!(f.tile(0, 2).unwrap(), Tile::Question);
+    assert!(f.mark());
+    assert!(f.goto(2, 2) && f.mark());
+    assert!(f.goto(0, 0) && f.click());
+    assert_eq!(f.tile(0, 0).unwrap(), Tile::Number(0));
+    assert_eq!(f.tile(0, 2).unwrap(), Tile::Number(1));
+    assert!(f.goto(0, 3) && f.click());
+    assert_eq!(f.tile(0, 3).unwrap(), Tile::Exploded);
+    assert_eq!(f.tile(2, 3).unwrap(), Tile::Closed);
+    f.cfg.slow_reveal = false;
+    f.ending(&mut headless::NullWR).unwrap();
+    assert_eq!(f.tile(2, 3).unwrap(), Tile::Mine);
+    assert_eq!(f.tile(2, 2).unwrap(), Tile::WrongFlag);
+    assert!(f.tile(3, 0).is_err());
+    let fr = f.frame_tiles().unwrap();
+    assert_eq!(fr.tiles.unwrap()[0][0], Tile::Number(0));
+    assert!(f.frame().unwrap().tiles.is_none());
+  }
+  /// test probabilities
+  #[test]
+  fn test_probabilities() {
+    use layer::{Heat, Layer};
+    use solver::Known;
+    let mut f = MineField::with_mines(4, 3, &[(0, 3), (1, 3)]);
+    let p = f.probabilities();
+    assert_eq!(p[1][1], Some(2.0 / 12.0));
+    f.set_heatmap(Some(Heat::Digit));
+    assert!(f.goto(0, 0) && f.click());
+    let k = f.deduce();
+    assert_eq!(k[0][3], Known::Mine);
+    assert_eq!(k[1][3], Known::Mine);
+    assert_eq!(k[2][3], Known::Safe);
+    let p = f.probabilities();
+    assert_eq!(p[0][0], None);
+    assert_eq!((p[0][3], p[2][3]), (Some(1.0), Some(0.0)));
+    assert_eq!(f.ly.heat.get(&(0, 3)).unwrap().glyph.as_deref(), Some("9"));
+    assert_eq!(f.compose(2, 3, 3, 2).unwrap().0, "0");
+    f.set_heatmap(Some(Heat::tint()));
+    assert_eq!(f.compose(0, 3, 3, 0).unwrap().1, 8);
+    f.set_heatmap(None);
+    assert!(f.ly.heat.is_empty());
+    assert!(f.ly.set(Layer::Heat, 0, 3, Heat::Digit.paint(0.5)));
+  }
+  /// test explain
+  #[test]
+  fn test_explain() {
+    use solver::{Known, Rule};
+    let mut f = MineField::with_mines(4, 3, &[(0, 3), (1, 3)]);
+    assert!(f.explain().is_empty());
+    assert!(f.goto(0, 0) && f.click());
+    let st = f.explain();
+    assert_eq!(st.len(), 3);
+    assert_eq!((st[0].r, st[0].c, st[0].known), (0, 3, Known::Mine));
+    assert_eq!((st[0].by, st[0].rule), ((0, 2), Rule::Exhausted));
+    assert_eq!(st[0].to_string(),
+      "cell (0,3) must be a mine because the '2' at (0,2) has exactly 2 closed neighbors");
+    let s = st.iter().find(|s| s.known == Known::Safe).unwrap();
+    assert_eq!((s.r, s.c, s.rule), (2, 3, Rule::Satisfied));
+    assert_eq!(s.to_string(),
+      "cell (2,3) must be safe because the '2' at (1,2) already has 2 mines around");
+  }
+  /// test enumerate
+  #[test]
+  fn test_enumerate() {
+    assert!(MineField::from_seed(4, 3, 2, 1).enumerate(30).is_none());
+    let mut f = MineField::with_mines(4, 3, &[(0, 3), (2, 3)]);
+    let e = f.enumerate(exact::MAX_BORDER).unwrap();
+    assert_eq!((e.border, e.p[1][1]), (0, Some(2.0 / 12.0)));
+    assert!(f.goto(0, 0) && f.click());
+    let e = f.enumerate(exact::MAX_BORDER).unwrap();
+    assert_eq!((e.border, e.arrangements), (3, 1));
+    assert_eq!((e.p[0][3], e.p[1][3], e.p[2][3]),
+      (Some(1.0), Some(0.0), Some(1.0)));
+    assert_eq!((e.best, e.risk), (Some((1, 3)), 0.0));
+    assert!(f.enumerate(2).is_none());
+    assert_eq!(f.probabilities()[1][3], Some(0.0));
+    let mut f = MineField::with_mines(5, 3, &[(0, 4), (2, 2)]);
+    assert!(f.goto(0, 0) && f.click());
+    let e = f.enumerate(exact::MAX_BORDER).unwrap();
+    let s: f64 = e.p.iter().flatten().flatten().sum();
+    assert!((s - 2.0).abs() < 1e-9);
+    assert!(e.p[1][4].unwrap() > 0.0 && e.risk <= e.p[1][4].unwrap());
+  }
+  /// test best_move
+  #[test]
+  fn test_best_move() {
+    use replay::Move;
+    let f = MineField::from_seed(5, 4, 3, 1);
+    assert_eq!(f.best_move().unwrap().mv, Move::Open(2, 2));
+    let mut f = MineField::with_mines(4, 3, &[(0, 3), (1, 3)]);
+    assert!(f.goto(0, 0) && f.click());
+    let s = f.best_move().unwrap();
+    assert_eq!((s.mv, s.risk), (Move::Open(2, 3), 0.0));
+    let mut f = MineField::with_mines(3, 2, &[(0, 2)]);
+    assert!(f.goto(1, 0) && f.click());
+    let s = f.best_move().unwrap();
+    assert!(matches!(s.mv, Move::Open(_, 2)) && s.risk == 0.5);
+    assert!(s.mv.play(&mut f));
+    assert!(f.is_end());
+    assert!(f.best_move().is_none());
+  }
+  /// test analyze
+  #[test]
+  fn test_analyze() {
+    let f = MineField::with_mines(4, 3, &[(0, 3), (1, 3)]);
+    let a = f.analyze(0, 0);
+    assert!(a.guess_free);
+    assert_eq!((a.guesses, a.basic, a.advanced), (0, 1, 0));
+    let a = MineField::with_mines(4, 3, &[(0, 3), (2, 3)]).analyze(0, 0);
+    assert!(a.guess_free);
+    assert_eq!((a.basic, a.advanced), (0, 1));
+    let a = MineField::with_mines(3, 2, &[(0, 2)]).analyze(1, 0);
+    assert!(!a.guess_free);
+    assert_eq!((a.guesses, a.max_risk), (1, 0.5));
+    assert_eq!(a.start, (1, 0));
+    let f = MineField::from_seed(8, 8, 10, 3);
+    assert_eq!(f.analyze(4, 4), f.analyze(4, 4));
+    assert!(!f.p);
+  }
+  /// test simulate
+  #[test]
+  fn test_simulate() {
+    let d = config::Difficulty{w: 8, h: 8, m: 10};
+    let cfg = Config::default();
+    let s = sim::simulate(d, &cfg, 20, &sim::Best);
+    assert_eq!(s.games, 20);
+    assert_eq!(s.won + s.lost + s.stuck, 20);
+    assert_eq!(s.causes.iter().sum::<u32>(), 20 - s.won);
+    assert_eq!(s.causes[sim::Cause::Blunder as usize], 0);
+    assert!(s.win_rate() > 0.5 && s.avg_bbbv > 0.0);
+    let b = sim::simulate(d, &cfg, 20, &sim::Basic);
+    assert_eq!(b.lost, 0);
+    assert_eq!(b.stuck, b.causes[sim::Cause::GaveUp as usize]);
+    assert!(s.won >= b.won);
+    assert_eq!(sim::SimReport::of(&[]).win_rate(), 0.0);
+  }
+  /// test win_probability
+  #[test]
+  fn test_win_probability() {
+    let mut f = MineField::with_mines(4, 3, &[(0, 3), (1, 3)]);
+    assert!(f.goto(0, 0) && f.click());
+    let e = f.win_probability(10, 1);
+    assert_eq!((e.samples, e.won, e.rejected), (10, 10, 0));
+    let mut f = MineField::with_mines(3, 2, &[(0, 2)]);
+    assert!(f.goto(1, 0) && f.click());
+    let mut g = rng::Rng::new(7);
+    let q = f.sample_layout(&mut g).unwrap();
+    assert_eq!(q.len(), 1);
+    let x = f.with_layout(&q);
+    assert_eq!((x.opened(), x.m), (f.opened(), f.m));
+    assert_eq!(x.f[1][1], f.f[1][1]);
+    let e = f.win_probability(200, 1);
+    assert_eq!(e.samples, 200);
+    assert!(e.p() > 0.3 && e.p() < 0.7);
+    assert!(MineField::from_seed(3, 2, 1, 0).sample_layout(&mut g).is_none());
+  }
+  /// test validate
+  #[test]
+  fn test_validate() {
+    use validate::InvariantError;
+    for seed in 0..20 {
+      let mut f = MineField::from_seed(6, 5, 6, seed);
+      let mut g = rng::Rng::new(seed);
+      assert_eq!(f.validate(), Ok(()));
+      for _ in 0..40 {
+        let (r, c) = (g.below(5) as u16, g.below(6) as u16);
+        f.goto(r, c);
+        match g.below(4) {
+        0 | 1 => { f.click(); },
+        2 => { f.mark(); },
+        _ => { f.chord(); }
+        }
+        assert_eq!(f.validate(), Ok(()), "seed {}", seed);
+      }
+      f.ending(&mut headless::NullWR).unwrap();
+      assert_eq!(f.validate(), Ok(()));
+    }
+    let mut f = MineField::with_mines(3, 3, &[(0, 0)]);
+    f.f[2][2] = 5;
+    assert_eq!(f.validate(), Err(InvariantError::Number(2, 2, 5, 0)));
+    f.f[2][2] = 0x10;
+    assert_eq!(f.validate(), Err(InvariantError::Opened(0, 1)));
+    f.s = 1;
+    f.f[2][2] = 0x50;
+    assert_eq!(f.validate(), Err(InvariantError::OpenFlag(2, 2)));
+    f.f[2][2] = 0;
+    f.s = 0;
+    f.m = 2;
+    assert_eq!(f.validate(), Err(InvariantError::Mines(1)));
+  }
+  /// test testing
+  #[cfg(feature = "testing")]
+  #[test]
+  fn test_testing() {
+    let f = testing::assert_script("
+      ...*
+      ...*
+      ....
+    ", "o:a1 f:d1", "
+      ..2F
+      ..2#
+      ..1#
+    ");
+    assert!(!f.is_end());
+    testing::assert_script("
+      -..*
+      ....
+    ", "o:d1", "
+      -##X
+      ####
+    ");
+    let mut f = testing::board("*..");
+    assert!(testing::play(&mut f, "o:z9 x").is_err());
+  }
+  /// test try_from_seed
+  #[test]
+  fn test_try_from_seed() {
+    let f = MineField::try_from_seed(9, 9, 10, 5).unwrap();
+    assert_eq!((f.w, f.h, f.m, f.seed), (9, 9, 10, 5));
+    assert!(matches!(MineField::try_from_seed(0, 9, 1, 5),
+      Err(MineFieldError::InvalidDimensions(0, 9))));
+    assert!(matches!(MineField::try_from_seed(2, 2, 4, 5),
+      Err(MineFieldError::TooManyMines(4))));
+    for (w, h) in [(200, 100), (255, 255), (128, 128)] {
+      assert!(matches!(MineField::try_from_seed(w, h, 1, 5),
+        Err(MineFieldError::InvalidDimensions(..))));
+    }
+    let mut f = MineField::with_mines(127, 129, &[(0, 0)]); // MAX_CELLS
+    f.goto(128, 126);
+    f.click();
+    assert_eq!(f.opened(), MAX_CELLS as u16 - 1);
+    assert!(f.is_success() && !f.is_explosion());
+  }
+  /// test MineFieldConst
+  #[test]
+  fn test_const() {
+    use fixed::{ConstGrid, Logic, MineFieldConst};
+    use storage::Storage;
+    let q = [(0, 3), (1, 3)];
+    let mut b = MineFieldConst::<4, 3>::with_mines(&q);
+    let mut f = MineField::with_mines(4, 3, &q);
+    assert_eq!(f.to_storage::<ConstGrid<4, 3>>(), b.f);
+    assert!(b.open_at(0, 0));
+    assert!(Logic::open_at(&mut f, 0, 0));
+    assert_eq!(f.to_storage::<ConstGrid<4, 3>>(), b.f);
+    assert_eq!((b.opened_count(), f.opened()), (9, 9));
+    assert!(b.flag_at(0, 3) && b.flag_at(1, 3) && !b.flag_at(0, 0));
+    assert!(b.chord_at(1, 2));
+    assert!(b.won() && b.ended() && !b.exploded());
+    let mut b = MineFieldConst::<3, 2>::with_mines(&[(0, 2), (0, 2)]);
+    assert_eq!(b.m, 1);
+    assert!(!b.open_at(0, 2));
+    assert!(b.exploded() && b.ended());
+    let b = MineFieldConst::<9, 9>::from_seed(10, 3);
+    assert_eq!(b.f.count_bits(0xf0), 0);
+    assert_eq!(b.f.a.iter().flatten().filter(|&&u| u == 0x0f).count(), 10);
+    assert_eq!(b, MineFieldConst::<9, 9>::from_seed(10, 3));
+  }
+  /// test SharedMineField
+  #[test]
+  fn test_shared() {
+    use replay::Move;
+    let s = shared::SharedMineField::spawn(||
+      MineField::with_mines(4, 3, &[(0, 3), (1, 3)]));
+    let t = s.clone();
+    let h = std::thread::spawn(move || t.play(Move::Open(0, 0)).unwrap());
+    assert!(h.join().unwrap());
+    assert_eq!(s.with(|f| f.opened()).unwrap(), 9);
+    s.post(|f| { f.goto(2, 3); }).unwrap();
+    assert!(s.play(Move::Flag(0, 3)).unwrap());
+    assert_eq!(s.with(|f| (f.r, f.c, f.flags())).unwrap(), (0, 3, 1));
+    assert_eq!(s.snapshot(true).unwrap().f[0][0], 0x10);
+    assert!(!s.is_end().unwrap());
+    assert!(s.play(Move::Open(2, 3)).unwrap());
+    assert!(s.is_end().unwrap() && s.status().unwrap().contains("won"));
+    assert_eq!(s.with(|_| -> u8 { panic!("job") }),
+      Err(shared::Disconnected));
+    assert!(s.play(Move::Finish).is_err());
+  }
+  /// test run_async
+  #[cfg(feature = "async")]
+  #[test]
+  fn test_run_async() {
+    use std::{future::Future, task::{Context, Poll, Waker}};
+    use aio::{AsyncInput, AsyncTimer};
+    use input::Input;
+    struct I(Vec<Option<Input>>);
+    impl AsyncInput for I {
+      async fn next(&mut self) ->
+        Result<Option<Input>, Box<dyn std::error::Error>> {
+        match self.0.pop() {
+        Some(Some(i)) => Ok(Some(i)),
+        Some(None) => std::future::pending().await,
+        None => Ok(None)
+        }
+      }
+    }
+    struct T(u32);
+    impl AsyncTimer for T {
+      async fn sleep(&mut self, _d: std::time::Duration) { self.0 += 1; }
+    }
+    fn block_on<F: Future>(f: F) -> F::Output {
+      let mut f = std::pin::pin!(f);
+      let mut cx = Context::from_waker(Waker::noop());
+      loop {
+        if let Poll::Ready(x) = f.as_mut().poll(&mut cx) { return x; }
+      }
+    }
+    let mut f = MineField::with_mines(3, 1, &[(0, 2)]);
+    let mut g = headless::NullWR;
+    let mut i = I(vec![Some(Input::Open), None, Some(Input::Left)]);
+    let mut t = T(0);
+    let o = block_on(aio::run_async(&mut f, &mut g, &mut i, &mut t));
+    assert_eq!(o.unwrap(), runner::Outcome::Won);
+    assert_eq!(t.0, 1);
+    let mut f = MineField::with_mines(3, 1, &[(0, 2)]);
+    let o = block_on(aio::run_async(&mut f, &mut g, &mut I(vec![]), &mut t));
+    assert_eq!(o.unwrap(), runner::Outcome::Quit);
+  }
+  /// test on_resize
+  #[test]
+  fn test_on_resize() {
+    use input::{Handled, Input};
+    let mut f = MineField::with_mines(10, 8, &[(0, 0)]);
+    f.cfg.cell_width = 2;
+    let mut b = headless::BufferWR::default();
+    f.goto(7, 9);
+    assert_eq!(f.handle(Input::Resize(8, 3), &mut b).unwrap(),
+      Handled::Resized);
+    assert_eq!((f.vp.w, f.vp.h), (4, 3));
+    assert!(f.vp.contains(7, 9));
+    assert_eq!(f.d.len(), 3);
+    assert!(b.n >= 4 * 3 * 2);
+    let n = b.n;
+    f.on_resize(100, 100);
+    assert_eq!((f.vp.x, f.vp.y, f.vp.w, f.vp.h), (0, 0, 10, 8));
+    f.refresh_dirty(&mut b).unwrap();
+    assert_eq!(b.n - n, 10 * 8 * 2);
+    f.on_resize(0, 0);
+    assert_eq!((f.vp.w, f.vp.h), (1, 1));
+  }
+  /// test widget
+  #[test]
+  fn test_widget() {
+    use widget::{Menu, MenuEvent, MessageBox};
+    let b = MessageBox::new(vec!["You win! time 42s".into(), "R Q".into()]);
+    assert_eq!(b.size(), (21, 4));
+    let mut g = headless::BufferWR::default();
+    b.render(&mut g, 30, 10).unwrap();
+    assert_eq!(g.lines()[3].trim_end(), "    +-------------------+");
+    assert_eq!(g.lines()[4].trim_end(), "    | You win! time 42s |");
+    let mut m = Menu::difficulty();
+    assert_eq!(m.handle(input::Input::Up), MenuEvent::Moved);
+    assert_eq!(m.sel, 2);
+    assert_eq!(m.handle(input::Input::Down), MenuEvent::Moved);
+    assert_eq!(m.handle(input::Input::Down), MenuEvent::Moved);
+    assert_eq!(m.handle(input::Input::Open), MenuEvent::Chosen(1));
+    assert_eq!(Menu::difficulty_of(1), Some(config::Difficulty::intermediate()));
+    assert_eq!(m.handle(input::Input::Quit), MenuEvent::Cancel);
+    let mut g = headless::BufferWR::default();
+    m.render(&mut g, 40, 10).unwrap();
+    assert!(g.lines()[5].contains("intermediate 16x16 40"));
+    assert_eq!(g.get(10, 5).map(|p| (p.1, p.2)), Some(m.hi));
+  }
+  /// test restart
+  #[test]
+  fn test_restart() {
+    use replay::Move;
+    let mut f = MineField::from_seed(9, 9, 10, 7);
+    f.cfg.treasures = 2;
+    f.set_splits(true);
+    Move::Open(4, 4).play(&mut f);
+    let (g, items) = (f.f.clone(), f.items.clone());
+    Move::Flag(0, 0).play(&mut f);
+    while !f.is_end() { f.best_move().unwrap().mv.play(&mut f); }
+    f.restart_same_board();
+    assert!(!f.is_end() && f.opened() == 0 && f.hist.is_empty());
+    assert_eq!((f.ck.opens, f.tm.elapsed()), (0, std::time::Duration::ZERO));
+    assert!(f.f.as_slice().iter().zip(g.as_slice()).all(|(a, b)|
+      *a == *b & 0x0f));
+    assert_eq!(f.items, items);
+    Move::Open(4, 4).play(&mut f);
+    assert_eq!(f.f, g);
+    f.new_game_from_seed(8);
+    assert!(!f.p && f.first.is_none() && f.items.is_empty());
+    assert!(f.f.as_slice().iter().all(|&u| u == 0));
+    Move::Open(4, 4).play(&mut f);
+    assert!(f.p && f.f != g && f.sp.is_some());
+  }
+  /// test reveal_mines
+  #[test]
+  fn test_reveal_mines() {
+    let mut f = MineField::with_mines(4, 3, &[(0, 3), (2, 0)]);
+    f.r = 1;
+    replay::Move::Flag(2, 0).play(&mut f);
+    let (g, s) = (f.f.clone(), f.s);
+    assert_eq!(f.compose(0, 3, 3, 0).unwrap().1, f.theme.closed.0);
+    f.reveal_mines(true);
+    let o = f.compose(0, 3, 3, 0).unwrap();
+    assert_eq!(o, (f.glyphs.lower[15].clone(), f.theme.peek.0, f.theme.peek.1));
+    assert_ne!(f.compose(2, 0, 0, 2).unwrap().0, o.0);
+    assert_ne!(f.compose(0, 0, 0, 0).unwrap().0, o.0);
+    assert_eq!((f.f.clone(), f.s), (g, s));
+    f.reveal_mines(false);
+    assert_ne!(f.compose(0, 3, 3, 0).unwrap().0, o.0);
+  }
+  /// test tutorial
+  #[test]
+  fn test_tutorial() {
+    use replay::Move;
+    use tutorial::{Tutorial, Verdict};
+    let mut t = Tutorial::basics();
+    assert_eq!(t.lessons.len(), 3);
+    assert_eq!(t.act(Move::Open(2, 2)), Verdict::Wrong);
+    assert!(!t.field.is_end() && t.wrong == 1 && !t.next_lesson());
+    assert_eq!(t.act(Move::Open(0, 0)), Verdict::LessonDone);
+    assert!(t.field.is_success() && t.prompt().is_none());
+    assert!(t.next_lesson());
+    assert_eq!(t.prompt(), Some("flag the cell the 1 at b1 points to"));
+    assert_eq!(t.act(Move::Flag(0, 2)), Verdict::Correct);
+    assert_eq!(t.act(Move::Open(0, 4)), Verdict::LessonDone);
+    assert!(t.field.is_success() && t.next_lesson());
+    for m in ["f:c1", "#:b2", "#:c2"] {
+      assert_eq!(t.act(m.parse().unwrap()), Verdict::Correct);
+    }
+    assert_eq!(t.act(Move::Chord(1, 3)), Verdict::Finished);
+    assert!(t.field.is_success() && t.is_finished() && !t.next_lesson());
+    assert!(tutorial::parse("step x").is_err());
+    assert!(tutorial::parse("lesson a\nboard *.\nstep x").is_err());
+  }
+  /// test messages
+  #[test]
+  fn test_messages() {
+    use messages::{English, Messages, Msg};
+    use tutorial::Tutorial;
+    struct Ja;
+    impl Messages for Ja {
+      fn text(&self, k: Msg) -> String {
+        match k {
+        Msg::Playing => "プレイ中".into(),
+        _ => English.text(k)
+        }
+      }
+      fn tutorial(&self, l: &str, i: usize, _p: &str) -> String {
+        format!("{}-{}", l, i)
+      }
+    }
+    let f = MineField::with_mines(4, 3, &[(0, 3), (1, 3)]);
+    assert!(f.status().ends_with(" playing"));
+    assert!(f.status_with(&Ja).ends_with(" プレイ中"));
+    let p = panel::Finale::new(true, std::time::Duration::from_secs(42));
+    assert_eq!(p.lines_with(&Ja), vec!["YOU WIN", "time 42.0s"]);
+    let t = Tutorial::basics();
+    assert_eq!(t.prompt_with(&English).as_deref(), t.prompt());
+    assert_eq!(t.prompt_with(&Ja).as_deref(), Some("open-0"));
+    let mut f = f;
+    replay::Move::Open(0, 0).play(&mut f);
+    assert_eq!(f.hint_with(&English), Some(f.explain()[0].to_string()));
+    let b = widget::MessageBox::game_over(&f);
+    assert_eq!(b.lines[1], "R to restart, Q to quit");
+  }
+  /// test json
+  #[cfg(feature = "serde")]
+  #[test]
+  fn test_json() {
+    use replay::Move;
+    let mut f = MineField::from_seed(9, 9, 10, u64::MAX - 1);
+    f.cfg.treasures = 2;
+    f.cfg.time_limit = Some(60);
+    Move::Open(4, 4).play(&mut f);
+    Move::Flag(0, 0).play(&mut f);
+    let s = f.to_json();
+    assert!(s.contains("\"seed\":\"18446744073709551614\""));
+    let g = MineField::from_json(&s).unwrap();
+    assert_eq!((g.f.clone(), g.s, g.seed, g.p, g.first, g.r, g.c),
+      (f.f.clone(), f.s, f.seed, f.p, f.first, f.r, f.c));
+    let mv = |f: &MineField| f.hist.iter().map(|p| p.1).collect::<Vec<_>>();
+    assert_eq!((g.items.clone(), g.ck, mv(&g), g.cfg.clone()),
+      (f.items.clone(), f.ck, mv(&f), f.cfg.clone()));
+    assert_eq!(g.to_json_value()["cells"], f.to_json_value()["cells"]);
+    let mut v = f.to_json_value();
+    v["future"] = serde_json::json!({"x": 1});
+    v.as_object_mut().unwrap().remove("history");
+    assert!(MineField::from_json_value(&v).unwrap().hist.is_empty());
+    v["version"] = serde_json::json!(json::VERSION + 1);
+    assert!(MineField::from_json_value(&v).is_err());
+    let v = serde_json::json!({"version": 1, "w": 2, "h": 1, "m": 1,
+      "cells": [[0x0f, 0x01]]});
+    let g = MineField::from_json_value(&v).unwrap();
+    assert!(g.p && g.opened() == 0 && g.hist.is_empty());
+    let mut v = v;
+    v["cells"] = serde_json::json!([[0x0f, 0x11]]);
+    assert!(MineField::from_json_value(&v).is_err());
+    assert!(MineField::from_json(r#"{"version":1,"w":2,"h":1,"m":1,
+      "cells":[[15]]}"#).is_err());
+    v["cells"] = serde_json::json!([[0x0f, 0x01]]);
+    v["detonated"] = serde_json::json!([0, 2]);
+    assert!(MineField::from_json_value(&v).is_err());
+    v["detonated"] = serde_json::Value::Null;
+    v["m"] = serde_json::json!(2);
+    assert!(MineField::from_json_value(&v).is_err());
+  }
+  /// test save
+  #[test]
+  fn test_save() {
+    use replay::Move;
+    use save::SaveError;
+    let mut f = MineField::from_seed(9, 9, 10, 11);
+    f.cfg.treasures = 2;
+    f.cfg.fog = Some(2);
+    Move::Open(4, 4).play(&mut f);
+    Move::Flag(0, 0).play(&mut f);
+    let b = f.to_save(true);
+    let g = MineField::from_save(&b).unwrap();
+    let mv = |f: &MineField| f.hist.iter().map(|p| p.1).collect::<Vec<_>>();
+    assert_eq!((g.f.clone(), g.s, g.seed, g.first, g.items.clone()),
+      (f.f.clone(), f.s, f.seed, f.first, f.items.clone()));
+    assert_eq!((g.ck, mv(&g), g.cfg.clone()), (f.ck, mv(&f), f.cfg.clone()));
+    let g = MineField::from_save(&f.to_save(false)).unwrap();
+    assert!(g.hist.is_empty() && f.to_save(false).len() < b.len());
+    assert_eq!(MineField::from_seed(9, 9, 10, 1).to_save(false).len(), 87);
+    let mut f = MineField::with_mines(3, 2, &[(0, 0)]);
+    f.set_mask(vec![vec![true, true, false], vec![true, true, true]]);
+    let g = MineField::from_save(&f.to_save(false)).unwrap();
+    assert_eq!(g.mask, f.mask);
+    for i in 0..b.len() {
+      assert!(matches!(MineField::from_save(&b[..i]), Err(
+        SaveError::Truncated(_) | SaveError::BadMagic | SaveError::Corrupt(_)
+        | SaveError::Checksum(..))), "{}", i);
+    }
+    let mut c = b.clone();
+    c[40] ^= 1;
+    assert!(MineField::from_save(&c).is_err());
+    c = b.clone();
+    c[4] = save::VERSION + 1;
+    assert_eq!(MineField::from_save(&c).err(), Some(SaveError::Version(2)));
+    c = b.clone();
+    c.push(0);
+    assert_eq!(MineField::from_save(&c).err(), Some(SaveError::Trailing(1)));
+    for (i, v, e) in [(8, 2000u16, "dimensions"), (12, 81, "mines"),
+      (24, 9, "cursor"), (28, 9, "first"), (32, 9, "detonated")] {
+      c = b.clone();
+      c[i..i + 2].copy_from_slice(&v.to_le_bytes());
+      assert_eq!(MineField::from_save(&c).err(), Some(SaveError::Corrupt(e)));
+    }
+  }
+  /// test blink mark
+  #[test]
+  fn test_blink_mark() {
+    let mut f = MineField::with_mines(3, 1, &[(0, 2)]);
+    f.theme.cursor = Some((9, 9));
+    let t = f.theme.clone();
+    let g = |f: &MineField| f.c(0, 0, f.f[0][0]).unwrap();
+    assert_eq!(g(&f), (f.glyphs.upper[0].clone(), 9, 9));
+    f.mark();
+    assert_eq!(g(&f), (f.glyphs.upper[4].clone(), 9, 9));
+    f.k = false;
+    assert_eq!(g(&f), (f.glyphs.upper[4].clone(), t.flag.0, t.flag.1));
+    f.mark();
+    assert_eq!(g(&f), (f.glyphs.upper[2].clone(), t.question.0, t.question.1));
+    f.cfg.question = false;
+    assert_eq!(g(&f), (f.glyphs.upper[0].clone(), t.closed.0, t.closed.1));
+    let mut f = MineField::with_mines(3, 1, &[(0, 0), (0, 2)]);
+    f.goto(0, 2);
+    f.mark();
+    f.goto(0, 0);
+    f.click();
+    f.goto(0, 2);
+    f.k = false;
+    assert!(f.is_explosion());
+    assert_eq!(f.c(0, 2, f.f[0][2]).unwrap().0, f.glyphs.upper[4]);
+  }
+  /// test cursor leak
+  #[test]
+  fn test_cursor_leak() {
+    let mut f = MineField::with_mines(3, 1, &[(0, 0), (0, 2)]);
+    f.click();
+    assert!(f.is_explosion());
+    f.goto(0, 2);
+    f.k = true;
+    let t = f.theme.closed;
+    assert_eq!(f.c(0, 2, f.f[0][2]).unwrap(),
+      (f.glyphs.upper[0].clone(), t.1, t.0));
+    f.goto(0, 1);
+    assert_eq!(f.c(0, 1, f.f[0][1]).unwrap().0, f.glyphs.upper[0]);
+    assert_eq!(f.c(0, 0, f.f[0][0]).unwrap().0, f.glyphs.upper[1]);
+  }
+  /// test style
+  #[test]
+  fn test_style() {
+    struct V(Vec<(u16, u16, String)>);
+    impl TView<u16> for V {
+      fn wr(&mut self, _p: impl TPacket) -> Result<(), Box<dyn Error>> {
+        Ok(())
+      }
+      fn reg(&mut self, _c: Vec<u16>) -> () {}
+      fn col(&self, n: u16) -> u16 { n }
+    }
+    impl TViewRun<u16> for V {
+      fn wr_run(&mut self, x: u16, _y: u16, st: u16, _bgc: u16, _fgc: u16,
+        s: &str) -> Result<(), Box<dyn Error>> {
+        self.0.push((x, st, s.to_string()));
+        Ok(())
+      }
+    }
+    let mut f = MineField::with_mines(4, 1, &[(0, 3)]);
+    f.k = true;
+    assert_eq!(f.style(0, 0), style::BASE | style::CURSOR);
+    assert_eq!(style::attrs(f.style(0, 1)), 0);
+    let mut v = V(vec![]);
+    f.refresh_run(&mut v).unwrap();
+    assert_eq!(v.0, vec![(0, style::BASE | style::CURSOR, String::from("L")),
+      (1, style::BASE, String::from("LLL"))]);
+    f.k = false;
+    assert_eq!(f.style(0, 0), style::BASE);
+    f.goto(0, 2);
+    f.mark();
+    f.goto(0, 3);
+    f.click();
+    assert!(f.is_explosion());
+    assert_eq!(f.style(0, 3), style::BASE | style::BOLD | style::BLINK);
+    assert_eq!(f.style(0, 2), style::BASE | style::UNDERLINE);
+  }
+  /// test confirm
+  #[test]
+  fn test_confirm() {
+    use confirm::Outcome;
+    use input::{Handled, Input};
+    let mut f = MineField::with_mines(5, 1, &[(0, 2)]);
+    f.cfg.confirm = true;
+    f.click();
+    f.goto(0, 3);
+    f.click();
+    f.goto(0, 2);
+    f.mark();
+    f.goto(0, 3);
+    assert_eq!(f.would_open(Move::Chord(0, 3)), vec![(0, 4)]);
+    assert!(f.risky(Move::Chord(0, 3)).is_empty()); // deduced safe
+    assert_eq!(f.try_chord(), Outcome::Done(true));
+    assert!(f.is_success());
+    let mut f = MineField::with_mines(4, 2, &[(0, 2)]);
+    f.click();
+    f.goto(1, 2);
+    f.mark(); // wrong flag
+    f.goto(0, 1);
+    assert_eq!(f.try_finish(), Outcome::Done(true)); // confirm off
+    assert!(f.is_explosion());
+    f.restart_same_board();
+    f.cfg.confirm = true;
+    f.goto(0, 0);
+    f.click();
+    f.goto(1, 2);
+    f.mark();
+    f.goto(0, 1);
+    assert_eq!(f.try_chord(), Outcome::NeedsConfirmation(vec![(0, 2)]));
+    assert_eq!(f.pending, Some(Move::Chord(0, 1)));
+    assert!(f.cancel() && !f.cancel());
+    assert_eq!(f.try_finish(),
+      Outcome::NeedsConfirmation(vec![(0, 2), (0, 3), (1, 3)]));
+    assert!(!f.is_end());
+    let mut b = headless::BufferWR::default();
+    assert_eq!(f.handle(Input::Chord, &mut b).unwrap(), Handled::Confirm);
+    assert_eq!(f.handle(Input::Chord, &mut b).unwrap(), Handled::Chorded);
+    assert!(f.is_explosion() && f.pending.is_none());
+    let cfg: config::Config = "confirm=true".parse().unwrap();
+    assert!(cfg.confirm);
+    assert!(MineField::from_save(&f.to_save(false)).unwrap().cfg.confirm);
+  }
+  /// test open twice
+  #[test]
+  fn test_open_twice() {
+    let mut f = MineField::with_mines(3, 2, &[(1, 2)]);
+    for _ in 0..3 { assert!(f.try_open(1, 0).unwrap()); }
+    assert_eq!(f.opened(), 4);
+    assert!(f.validate().is_ok());
+    assert_eq!(f.open_cells(0, 0), Some(vec![]));
+    f.goto(0, 2);
+    f.mark();
+    assert_eq!(f.open_cells(0, 2), Some(vec![]));
+    assert!(f.try_open(2, 0).is_err());
+    assert_eq!(f.opened(), 4);
+  }
+  /// test flush
+  #[test]
+  fn test_flush() {
+    use input::Input;
+    struct V(usize, usize);
+    impl TView<u16> for V {
+      fn wr(&mut self, _p: impl TPacket) -> Result<(), Box<dyn Error>> {
+        self.0 += 1;
+        Ok(())
+      }
+      fn reg(&mut self, _c: Vec<u16>) -> () {}
+      fn col(&self, n: u16) -> u16 { n }
+    }
+    impl TViewRun<u16> for V {
+      fn flush(&mut self) -> Result<(), Box<dyn Error>> {
+        self.1 += 1;
+        Ok(())
+      }
+    }
+    struct I(Vec<Input>);
+    impl runner::InputSource for I {
+      fn poll(&mut self, _t: time::Duration) ->
+        Result<Option<Input>, Box<dyn Error>> {
+        Ok(Some(self.0.pop().unwrap_or(Input::Quit)))
+      }
+    }
+    let mut f = MineField::with_mines(3, 1, &[(0, 2)]);
+    let mut v = V(0, 0);
+    f.refresh_run(&mut v).unwrap();
+    assert_eq!((v.0, v.1), (3, 1));
+    let mut i = I(vec![Input::Open]);
+    assert_eq!(runner::run(&mut f, &mut v, &mut i).unwrap(),
+      runner::Outcome::Won);
+    assert_eq!(v.1, 3); // before the poll and after the ending
+  }
+  /// test parse errors
+  #[test]
+  fn test_parse_errors() {
+    use MineFieldError::*;
+    assert!(matches!("x5".parse::<Move>(), Err(ParseError(_))));
+    assert!(matches!("o:a0".parse::<Move>(), Err(ParseError(_))));
+    assert!(matches!("question=maybe".parse::<config::Config>(),
+      Err(ParseError(_))));
+    assert!(matches!("9x9-10 x 2024-01-01 a"
+      .parse::<leaderboard::Leaderboard>(), Err(ParseError(_))));
+    assert!(matches!("3 3".parse::<replay::Replay>(), Err(ParseError(_))));
+    assert!(matches!(replay::Replay::load("/nonexistent/replay"),
+      Err(IoError(_))));
+  }
+  /// test stencil sizes
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_stencil_size() {
+    let wide = vec![vec![true; 0x4000]];
+    assert!(matches!(MineField::from_mask(wide, 1),
+      Err(MineFieldError::InvalidDimensions(..))));
+    assert!(matches!(MineField::from_stencil("##", 2),
+      Err(MineFieldError::TooManyMines(2))));
+  }
+  /// test mask seed
+  #[test]
+  fn test_mask_seed() {
+    let v = vec![vec![true, true, false], vec![true, true, true]];
+    let play = |mut f: MineField| { f.goto(1, 2); f.click(); f.f.clone() };
+    let f = MineField::from_mask_seed(v.clone(), 2, 7).unwrap();
+    let g = MineField::from_mask_seed(v.clone(), 2, 7).unwrap();
+    assert_eq!(play(f), play(g));
+    assert!(MineField::from_mask_seed(v, 5, 7).is_err());
+  }
+  /// test spectator snapshot
+  #[test]
+  fn test_spectator_snapshot() {
+    use spectator::SpectatorView;
+    let mut a = MineField::from_seed(8, 8, 10, 3);
+    a.goto(4, 4);
+    a.click();
+    let s = wire::Snapshot::of(&a, true);
+    assert!(SpectatorView::new(&s, 0).is_ok());
+    let bad = |t: wire::Snapshot| SpectatorView::new(&t, 0).is_err();
+    assert!(bad(wire::Snapshot{s: s.s + 1, ..s.clone()}));
+    assert!(bad(wire::Snapshot{s: s.s - 1, ..s.clone()}));
+    assert!(bad(wire::Snapshot{w: 9, ..s.clone()}));
+    assert!(bad(wire::Snapshot{m: 64, ..s.clone()}));
+    assert!(bad(wire::Snapshot{w: 0, h: 0, f: vec![], ..s}));
+  }
+  /// test finale theme
+  #[test]
+  fn test_finale_theme() {
+    let mut f = MineField::with_mines(3, 2, &[(1, 2)]);
+    assert_eq!(panel::Finale::of(&f).frame, (4, 5));
+    f.set_theme(Theme::high_contrast());
+    let p = panel::Finale::of(&f);
+    assert_eq!((p.frame, p.text), ((1, 0), (0, 1)));
+  }
 }
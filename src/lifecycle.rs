@@ -0,0 +1,70 @@
+//! restart and new game keeping the configuration
+//!
+//! theme, glyphs, viewport, mask, placement, hooks, layers over the board
+//! and tracking modes are kept, the play state (cells, status, timer,
+//! clicks, history, items) is reset
+//!
+
+use crate::MineField;
+use crate::rng::Rng;
+
+/// MineField
+impl MineField {
+  /// reset the play state (cells closed, layout kept when keep)
+  fn reset_play(&mut self, keep: bool) -> () {
+    for u in self.f.as_mut_slice() { *u = if keep { *u & 0x0f } else { 0 }; }
+    self.s = 0;
+    self.x = None;
+    self.hits = 0;
+    self.expired = false;
+    self.paused = false;
+    self.mb = 0;
+    self.pending = None;
+    self.tm.reset();
+    self.ck = Default::default();
+    self.hist.clear();
+    self.cascade.clear();
+    self.hidden.clear();
+    self.items.clear();
+    self.found.clear();
+    if self.sp.is_some() { self.set_splits(true); }
+  }
+
+  /// restart_same_board (same mine layout and items, everything closed,
+  /// a board not placed yet keeps its seed)
+  pub fn restart_same_board(&mut self) -> () {
+    self.reset_play(self.p);
+    if let Some((r, c)) = self.first {
+      let cur = (self.r, self.c);
+      (self.r, self.c) = (r, c);
+      let mut g = Rng::new(self.seed);
+      let _ = self.placement.mines(self, &mut g);
+      let mut p: Vec<u16> = (0..self.w*self.h).collect();
+      g.shuffle(&mut p);
+      self.place_items(&p);
+      (self.r, self.c) = cur;
+    }
+    self.overlay_probabilities();
+    self.invalidate();
+    if let Some(fb) = self.fb.as_mut() { fb.on_restart(true); }
+  }
+
+  /// new_game with the same config and a new layout by the seed (mines
+  /// placed at the first click)
+  pub fn new_game_from_seed(&mut self, seed: u64) -> () {
+    self.reset_play(false);
+    self.seed = seed;
+    self.p = false;
+    self.first = None;
+    self.overlay_probabilities();
+    self.invalidate();
+    if let Some(fb) = self.fb.as_mut() { fb.on_restart(false); }
+  }
+
+  /// new_game with the same config and a new layout (random seed, feature
+  /// rand)
+  #[cfg(feature = "rand")]
+  pub fn new_game(&mut self) -> () {
+    self.new_game_from_seed(rand::random())
+  }
+}
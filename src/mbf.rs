@@ -0,0 +1,50 @@
+//! Minesweeper Board Format (MBF) interop
+//!
+//! - 0 width (1 byte)
+//! - 1 height (1 byte)
+//! - 2-3 mines (u16 big endian)
+//! - 4- x, y (1 byte each) for every mine
+//!
+
+use std::collections::HashSet;
+
+use crate::MineField;
+use crate::error::MineFieldError::{self, ParseError};
+
+/// MineField
+impl MineField {
+  /// to_mbf (mines must be placed, at most 255 x 255)
+  pub fn to_mbf(&self) -> Result<Vec<u8>, MineFieldError> {
+    if !self.p { Err(ParseError("mbf: mines not placed".into()))? }
+    if self.w > 255 || self.h > 255 {
+      return Err(MineFieldError::InvalidDimensions(self.w, self.h));
+    }
+    let mut q = vec![];
+    for (r, v) in self.f.iter().enumerate() {
+      for (c, u) in v.iter().enumerate() {
+        if Self::is_mine(Self::get_v(*u)) { q.push((c as u8, r as u8)); }
+      }
+    }
+    let mut b = vec![self.w as u8, self.h as u8];
+    b.extend_from_slice(&(q.len() as u16).to_be_bytes());
+    for (x, y) in q { b.push(x); b.push(y); }
+    Ok(b)
+  }
+
+  /// from_mbf (at most MAX_CELLS and w x h - 1 mines)
+  pub fn from_mbf(b: &[u8]) -> Result<Self, MineFieldError> {
+    if b.len() < 4 { Err(ParseError("mbf: truncated header".into()))? }
+    let (w, h) = (b[0] as u16, b[1] as u16);
+    let m = u16::from_be_bytes([b[2], b[3]]);
+    Self::check_size(w, h)?;
+    if b.len() != 4 + 2 * m as usize {
+      Err(ParseError("mbf: length mismatch".into()))?
+    }
+    let mut q = HashSet::with_capacity(m as usize);
+    for p in b[4..].chunks(2) {
+      let (c, r) = (p[0] as u16, p[1] as u16);
+      if !q.insert((r, c)) { Err(ParseError("mbf: duplicated mine".into()))? }
+    }
+    MineField::try_with_mines(w, h, &q.into_iter().collect::<Vec<_>>())
+  }
+}
@@ -0,0 +1,76 @@
+//! status and message strings (localization hooks, English by default)
+//!
+//! every method has the English text as default, a localized provider
+//! overrides text (keys without parameters) and any formatted message
+//!
+
+use std::time::Duration;
+
+use crate::solver::Step;
+
+/// Msg (keys of the fixed texts)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Msg {
+  /// status playing
+  Playing,
+  /// status paused
+  Paused,
+  /// status won
+  Won,
+  /// status lost
+  Lost,
+  /// status time budget expired
+  TimeUp,
+  /// title of a won game
+  YouWin,
+  /// title of a lost game
+  GameOver,
+  /// keys at the end of a game
+  Keys
+}
+
+/// trait Messages
+pub trait Messages {
+  /// text of the key
+  fn text(&self, k: Msg) -> String {
+    String::from(match k {
+    Msg::Playing => "playing",
+    Msg::Paused => "paused",
+    Msg::Won => "won",
+    Msg::Lost => "lost",
+    Msg::TimeUp => "time up",
+    Msg::YouWin => "YOU WIN",
+    Msg::GameOver => "GAME OVER",
+    Msg::Keys => "R to restart, Q to quit"
+    })
+  }
+
+  /// elapsed time
+  fn time(&self, t: Duration) -> String {
+    format!("time {:.1}s", t.as_secs_f64())
+  }
+
+  /// difference to the best time in seconds
+  fn best(&self, d: f64) -> String { format!("best {:+.1}s", d) }
+
+  /// efficiency 0.0 - 1.0
+  fn eff(&self, e: f64) -> String { format!("eff {:.0}%", e * 100.0) }
+
+  /// share code
+  fn code(&self, c: &str) -> String { format!("code {}", c) }
+
+  /// hint of a deduction step
+  fn hint(&self, s: &Step) -> String { s.to_string() }
+
+  /// prompt of the step i of the tutorial lesson (the lesson text)
+  fn tutorial(&self, _lesson: &str, _i: usize, prompt: &str) -> String {
+    prompt.to_string()
+  }
+}
+
+/// English (the defaults)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct English;
+
+/// trait Messages for English
+impl Messages for English {}
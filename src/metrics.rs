@@ -0,0 +1,141 @@
+//! board metrics (3BV, openings, difficulty estimate)
+//!
+
+use std::ops::{Range, RangeInclusive};
+
+use crate::MineField;
+use crate::config::Difficulty;
+
+/// Metrics
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metrics {
+  /// 3BV (minimum clicks to open every safe cell without chords)
+  pub bbbv: u32,
+  /// openings (connected regions of cells without mines around)
+  pub openings: u32,
+  /// safe cells
+  pub safe: u32,
+  /// estimated difficulty (3BV per safe cell x mine density x 100)
+  pub difficulty: f64
+}
+
+/// Clicks (counted during play)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Clicks {
+  /// clicks opening cells
+  pub opens: u32,
+  /// chords opening cells
+  pub chords: u32,
+  /// marks (flag cycle)
+  pub flags: u32,
+  /// clicks without effect
+  pub wasted: u32
+}
+
+/// Clicks
+impl Clicks {
+  /// total clicks
+  pub fn total(&self) -> u32 {
+    self.opens + self.chords + self.flags + self.wasted
+  }
+}
+
+/// MineField
+impl MineField {
+  /// efficiency 3BV / clicks (None: no clicks yet)
+  pub fn efficiency(&self) -> Option<f64> {
+    let n = self.ck.total();
+    if !self.p || n == 0 { return None; }
+    Some(self.bbbv() as f64 / n as f64)
+  }
+
+  /// metrics of the board (mines must be placed)
+  pub fn metrics(&self) -> Metrics {
+    let (w, h) = (self.w as usize, self.h as usize);
+    let mut k = vec![vec![false; w]; h]; // counted
+    let zero = |r: u16, c: u16| self.f[r as usize][c as usize] & 0x0f == 0;
+    let (mut openings, mut bbbv, mut safe) = (0, 0, 0);
+    for r in 0..self.h {
+      for c in 0..self.w {
+        if !self.is_playable(r, c) || !zero(r, c) || k[r as usize][c as usize] {
+          continue;
+        }
+        openings += 1;
+        k[r as usize][c as usize] = true;
+        let mut q = vec![(r, c)];
+        while let Some((j, i)) = q.pop() { // flood fill the opening
+          if !zero(j, i) { continue; }
+          for (y, x) in self.neighbors(j, i) {
+            if k[y as usize][x as usize] { continue; }
+            k[y as usize][x as usize] = true;
+            q.push((y, x));
+          }
+        }
+      }
+    }
+    for r in 0..self.h {
+      for c in 0..self.w {
+        if !self.is_playable(r, c) { continue; }
+        let u = self.f[r as usize][c as usize];
+        if Self::is_mine(u) { continue; }
+        safe += 1;
+        if !k[r as usize][c as usize] { bbbv += 1; } // isolated number
+      }
+    }
+    bbbv += openings;
+    let n = self.playable() as f64;
+    let difficulty = if safe == 0 { 0.0 }
+      else { bbbv as f64 / safe as f64 * (self.m as f64 / n) * 100.0 };
+    Metrics{bbbv, openings, safe, difficulty}
+  }
+
+  /// bbbv (3BV)
+  pub fn bbbv(&self) -> u32 { self.metrics().bbbv }
+
+  /// 3BV completed (openings with an opened cell and opened isolated numbers)
+  pub fn bbbv_done(&self) -> u32 {
+    let (w, h) = (self.w as usize, self.h as usize);
+    let mut k = vec![vec![false; w]; h]; // counted
+    let zero = |r: u16, c: u16| self.f[r as usize][c as usize] & 0x0f == 0;
+    let mut n = 0;
+    for r in 0..self.h {
+      for c in 0..self.w {
+        if !self.is_playable(r, c) || !zero(r, c) || k[r as usize][c as usize] {
+          continue;
+        }
+        k[r as usize][c as usize] = true;
+        let mut done = false;
+        let mut q = vec![(r, c)];
+        while let Some((j, i)) = q.pop() { // flood fill the opening
+          if !zero(j, i) { continue; }
+          done |= self.is_opened(j, i);
+          for (y, x) in self.neighbors(j, i) {
+            if k[y as usize][x as usize] { continue; }
+            k[y as usize][x as usize] = true;
+            q.push((y, x));
+          }
+        }
+        if done { n += 1; }
+      }
+    }
+    for r in 0..self.h {
+      for c in 0..self.w {
+        if k[r as usize][c as usize] || !self.is_playable(r, c) { continue; }
+        let u = self.f[r as usize][c as usize];
+        if !Self::is_mine(u) && self.is_opened(r, c) { n += 1; }
+      }
+    }
+    n
+  }
+}
+
+/// find_seed of a board with 3BV in the range (first click at (r, c))
+pub fn find_seed(d: Difficulty, r: u16, c: u16, bbbv: RangeInclusive<u32>,
+  seeds: Range<u64>) -> Option<u64> {
+  seeds.into_iter().find(|&seed| {
+    let mut f = MineField::from_seed(d.w, d.h, d.m, seed);
+    f.goto(r, c);
+    f.start();
+    bbbv.contains(&f.bbbv())
+  })
+}
@@ -0,0 +1,120 @@
+//! Monte Carlo win probability of a position
+//!
+//! mine layouts consistent with the opened numbers are sampled (border by
+//! randomized backtracking, approximately uniform, the rest uniform) and
+//! played out from the position by sim::Best
+//!
+
+use crate::MineField;
+use crate::exact::Border;
+use crate::rng::Rng;
+use crate::sim::{Best, Policy};
+use crate::solver::Known;
+
+/// Estimate
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Estimate {
+  /// layouts played out
+  pub samples: u32,
+  /// layouts won
+  pub won: u32,
+  /// samples failed (no consistent layout found)
+  pub rejected: u32
+}
+
+/// Estimate
+impl Estimate {
+  /// win probability (0 when nothing played out)
+  pub fn p(&self) -> f64 {
+    if self.samples == 0 { 0.0 } else { self.won as f64 / self.samples as f64 }
+  }
+}
+
+/// assign border cell i and deeper at random (false: inconsistent)
+fn fill(b: &Border, g: &mut Rng, a: &mut [bool], mines: &mut [usize],
+  open: &mut [usize], i: usize, k: usize) -> bool {
+  if i == a.len() { return true; }
+  let first = g.below(2) == 1;
+  for v in [first, !first] {
+    if v && k >= b.left { continue; }
+    if !b.of[i].iter().all(|&j| {
+      let m = mines[j] + v as usize;
+      m <= b.cs[j].1 && m + open[j] > b.cs[j].1
+    }) { continue; }
+    a[i] = v;
+    for &j in &b.of[i] { mines[j] += v as usize; open[j] -= 1; }
+    if fill(b, g, a, mines, open, i + 1, k + v as usize) { return true; }
+    for &j in &b.of[i] { mines[j] -= v as usize; open[j] += 1; }
+  }
+  a[i] = false;
+  false
+}
+
+/// MineField
+impl MineField {
+  /// sample a layout of every mine consistent with the position (None:
+  /// not started or no consistent layout within a few tries)
+  pub fn sample_layout(&self, g: &mut Rng) -> Option<Vec<(u16, u16)>> {
+    if !self.p { return None; }
+    let b = self.border();
+    for _ in 0..8 {
+      let mut a = vec![false; b.cells.len()];
+      let mut mines = vec![0; b.cs.len()];
+      let mut open = b.cs.iter().map(|(q, _)| q.len()).collect::<Vec<_>>();
+      if !fill(&b, g, &mut a, &mut mines, &mut open, 0, 0) { return None; }
+      let n = a.iter().filter(|&&v| v).count();
+      let rest = b.left - n;
+      if rest > b.interior.len() { continue; }
+      let mut q = self.cells().filter(|&(r, c, u)| u.is_mine() && u.is_open()
+        || !u.is_open() && b.k[r as usize][c as usize] == Known::Mine)
+        .map(|(r, c, _)| (r, c)).collect::<Vec<_>>();
+      q.extend(b.cells.iter().zip(&a).filter(|(_, &v)| v).map(|(&p, _)| p));
+      let mut i = b.interior.clone();
+      g.shuffle(&mut i);
+      q.extend(&i[..rest]);
+      return Some(q);
+    }
+    None
+  }
+
+  /// position with the mines at q (opened cells and marks kept)
+  pub fn with_layout(&self, q: &[(u16, u16)]) -> MineField {
+    let mut f = MineField::with_mines(self.w, self.h, q);
+    for (r, c, u) in self.cells() {
+      let v = &mut f.f[r as usize][c as usize];
+      *v = (*v & 0x0f) | (u8::from(u) & 0xf0);
+    }
+    f.mask.clone_from(&self.mask);
+    f.cfg = self.cfg.clone();
+    (f.s, f.hits, f.r, f.c) = (self.s, self.hits, self.r, self.c);
+    f
+  }
+
+  /// win_probability of the position by n layouts played out by
+  /// sim::Best
+  pub fn win_probability(&self, n: u32, seed: u64) -> Estimate {
+    let mut e = Estimate::default();
+    if self.is_end() {
+      e.samples = n;
+      e.won = if self.is_success() { n } else { 0 };
+      return e;
+    }
+    let mut g = Rng::new(seed);
+    for _ in 0..n {
+      let Some(q) = self.sample_layout(&mut g) else {
+        e.rejected += 1;
+        continue;
+      };
+      let mut f = self.with_layout(&q);
+      let mut p = Best;
+      while !f.is_end() {
+        let Some((r, c)) = p.next(&f) else { break; };
+        f.goto(r, c);
+        f.click();
+      }
+      e.samples += 1;
+      if f.is_success() { e.won += 1; }
+    }
+    e
+  }
+}
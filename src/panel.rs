@@ -0,0 +1,128 @@
+//! end of game panel and status line
+//!
+
+use std::time::Duration;
+
+use mvc_rs::TView;
+
+use crate::{MineField, Packet};
+use crate::error::MineFieldError;
+use crate::messages::{English, Messages, Msg};
+use crate::style;
+use crate::theme::Theme;
+use crate::widget::MessageBox;
+
+/// wr_str one glyph per cell from (x, y)
+pub fn wr_str<T>(g: &mut impl TView<T>, x: u16, y: u16, bgc: u16, fgc: u16,
+  s: &str) -> Result<(), MineFieldError> {
+  let mut b = [0u8; 4];
+  for (i, ch) in s.chars().enumerate() {
+    let msg = ch.encode_utf8(&mut b);
+    g.wr(Packet{x: x + i as u16, y, st: style::BASE, bgc, fgc, msg})?;
+  }
+  Ok(())
+}
+
+/// MineField
+impl MineField {
+  /// status line text (mines remaining, elapsed time, game state)
+  pub fn status(&self) -> String { self.status_with(&English) }
+
+  /// status line text by the messages
+  pub fn status_with(&self, ms: &impl Messages) -> String {
+    let st = if self.is_success() { Msg::Won }
+      else if self.is_explosion() { Msg::Lost }
+      else if self.expired { Msg::TimeUp }
+      else if self.paused { Msg::Paused }
+      else { Msg::Playing };
+    let n = self.flags() + self.defused();
+    let t = self.remaining().unwrap_or_else(|| self.elapsed()); // countdown
+    format!("{:>3} {:>5.1}s {}", self.m as i32 - n as i32, t.as_secs_f64(),
+      ms.text(st))
+  }
+
+  /// render_status on row y (padded to the viewport width)
+  pub fn render_status<T>(&self, g: &mut impl TView<T>, y: u16) ->
+    Result<(), MineFieldError> {
+    let (bgc, fgc) = self.theme.open;
+    let s = format!("{:<1$}", self.status(), (self.vp.w * self.cw()) as usize);
+    wr_str(g, 0, y, bgc, fgc, &s)
+  }
+}
+
+/// Finale
+#[derive(Debug, Clone)]
+pub struct Finale {
+  /// won or lost
+  pub won: bool,
+  /// elapsed time
+  pub time: Duration,
+  /// best time before this game
+  pub best: Option<Duration>,
+  /// efficiency (3BV / clicks)
+  pub eff: Option<f64>,
+  /// share code
+  pub code: Option<String>,
+  /// frame colors (bgc, fgc)
+  pub frame: (u16, u16),
+  /// text colors (bgc, fgc)
+  pub text: (u16, u16)
+}
+
+/// Finale
+impl Finale {
+  /// constructor (colors of the default theme, see of)
+  pub fn new(won: bool, time: Duration) -> Self {
+    let t = Theme::default();
+    Finale{won, time, best: None, eff: None, code: None,
+      frame: t.mine, text: t.open}
+  }
+
+  /// of a field at the end (won, time, efficiency, frame colors of the
+  /// mine role and text colors of the open role of the theme)
+  pub fn of(m: &MineField) -> Self {
+    let mut p = Finale::new(m.is_success(), m.elapsed());
+    p.eff = m.efficiency();
+    (p.frame, p.text) = (m.theme.mine, m.theme.open);
+    p
+  }
+
+  /// lines inside the frame
+  pub fn lines(&self) -> Vec<String> { self.lines_with(&English) }
+
+  /// lines inside the frame by the messages
+  pub fn lines_with(&self, ms: &impl Messages) -> Vec<String> {
+    let mut v = vec![
+      ms.text(if self.won { Msg::YouWin } else { Msg::GameOver }),
+      ms.time(self.time)];
+    if let Some(b) = self.best {
+      v.push(ms.best(self.time.as_secs_f64() - b.as_secs_f64()));
+    }
+    if let Some(e) = self.eff { v.push(ms.eff(e)); }
+    if let Some(c) = &self.code { v.push(ms.code(c)); }
+    v
+  }
+
+  /// message box of the lines
+  pub fn message_box(&self) -> MessageBox {
+    MessageBox{lines: self.lines(), frame: self.frame, text: self.text}
+  }
+
+  /// size (w, h) with frame
+  pub fn size(&self) -> (u16, u16) { self.message_box().size() }
+
+  /// render at (x, y)
+  pub fn render_at<T>(&self, g: &mut impl TView<T>, x: u16, y: u16) ->
+    Result<(), MineFieldError> {
+    self.message_box().render_at(g, x, y)
+  }
+
+  /// render centered on the viewport
+  pub fn render<T>(&self, m: &MineField, g: &mut impl TView<T>) ->
+    Result<(), MineFieldError> {
+    let (w, h) = self.size();
+    let sw = m.vp.w * m.cw();
+    let (x, y) = (sw.saturating_sub(w) / 2, m.vp.h.saturating_sub(h) / 2);
+    self.render_at(g, x, y)
+  }
+}
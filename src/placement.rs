@@ -0,0 +1,115 @@
+//! mine distribution strategies used by MineField::start
+//!
+
+use crate::MineField;
+use crate::rng::Rng;
+
+/// trait Placement
+pub trait Placement {
+  /// mines (r, c) for f.m mines on playable cells avoiding the cursor
+  /// (all playable cells when the mines fill the field)
+  fn mines(&self, f: &MineField, g: &mut Rng) -> Vec<(u16, u16)>;
+}
+
+/// Uniform (shuffle, the board code format, see rng)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Uniform;
+
+/// trait Placement for Uniform
+impl Placement for Uniform {
+  /// mines
+  fn mines(&self, f: &MineField, g: &mut Rng) -> Vec<(u16, u16)> {
+    let e = f.m >= f.playable(); // fill all when mine full
+    let mut p: Vec<u16> = (0..f.w*f.h).collect();
+    g.shuffle(&mut p);
+    let mut q = vec![];
+    for &i in &p {
+      if q.len() >= f.m as usize { break; }
+      let (r, c) = (i / f.w, i % f.w);
+      if !f.is_playable(r, c) { continue; } // masked out
+      if e || r != f.r || c != f.c { q.push((r, c)); } // fill all
+    }
+    q
+  }
+}
+
+/// weighted draw of mines one by one (weight by mines already around)
+pub fn weighted(f: &MineField, g: &mut Rng,
+  wt: impl Fn(u16, u16, u32) -> u32) -> Vec<(u16, u16)> {
+  let e = f.m >= f.playable();
+  let mut k = vec![vec![false; f.w as usize]; f.h as usize];
+  let mut p = (0..f.h).flat_map(|r| (0..f.w).map(move |c| (r, c)))
+    .filter(|&(r, c)| f.is_playable(r, c) && (e || (r, c) != (f.r, f.c)))
+    .collect::<Vec<_>>();
+  let mut q = vec![];
+  while q.len() < f.m as usize && !p.is_empty() {
+    let w = p.iter().map(|&(r, c)| {
+      let n = f.neighbors(r, c).filter(|&(j, i)| k[j as usize][i as usize])
+        .count() as u32;
+      wt(r, c, n).max(1)
+    }).collect::<Vec<_>>();
+    let mut x = g.below(w.iter().sum());
+    let i = w.iter().position(|&v| if x < v { true } else { x -= v; false })
+      .unwrap_or(0);
+    let (r, c) = p.swap_remove(i);
+    k[r as usize][c as usize] = true;
+    q.push((r, c));
+  }
+  q
+}
+
+/// Clustered (mines attract each other, weight 1 + k x mines around)
+#[derive(Debug, Clone, Copy)]
+pub struct Clustered(pub u32);
+
+/// Clustered
+impl Default for Clustered {
+  /// default k = 4
+  fn default() -> Self { Clustered(4) }
+}
+
+/// trait Placement for Clustered
+impl Placement for Clustered {
+  /// mines
+  fn mines(&self, f: &MineField, g: &mut Rng) -> Vec<(u16, u16)> {
+    weighted(f, g, |_, _, n| 1 + self.0 * n)
+  }
+}
+
+/// Spread (mines repel each other, weight 64 / (1 + k x mines around))
+#[derive(Debug, Clone, Copy)]
+pub struct Spread(pub u32);
+
+/// Spread
+impl Default for Spread {
+  /// default k = 4
+  fn default() -> Self { Spread(4) }
+}
+
+/// trait Placement for Spread
+impl Placement for Spread {
+  /// mines
+  fn mines(&self, f: &MineField, g: &mut Rng) -> Vec<(u16, u16)> {
+    weighted(f, g, |_, _, n| 64 / (1 + self.0 * n))
+  }
+}
+
+/// Border heavy (weight 1 + k on the edges of the field)
+#[derive(Debug, Clone, Copy)]
+pub struct Border(pub u32);
+
+/// Border
+impl Default for Border {
+  /// default k = 3
+  fn default() -> Self { Border(3) }
+}
+
+/// trait Placement for Border
+impl Placement for Border {
+  /// mines
+  fn mines(&self, f: &MineField, g: &mut Rng) -> Vec<(u16, u16)> {
+    weighted(f, g, |r, c, _|
+      if r == 0 || c == 0 || r == f.h - 1 || c == f.w - 1 { 1 + self.0 }
+      else { 1 })
+  }
+}
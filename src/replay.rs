@@ -0,0 +1,351 @@
+//! move history, replay and ghost race against a replay
+//!
+//! move notation `o:c5` (open), `#:c5` (chord), `m:c5` (mark cycle),
+//! `f:c5` (flag), `F` (finish) with column letters `a` .. `z`, `aa` ..
+//! and row numbers from 1
+//!
+//! replay persisted as the first line `W H M SEED` followed by
+//! `millis notation` lines and the claim `= won|lost|stuck millis` of an
+//! ended game (`#` comment), replayed with the default placement
+//!
+
+use std::error::Error;
+use std::{fmt, fs, path};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::MineField;
+use crate::error::MineFieldError::{self, ParseError};
+use crate::sim::Outcome;
+
+/// Move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+  /// click (r, c)
+  Open(u16, u16),
+  /// chord (r, c)
+  Chord(u16, u16),
+  /// mark cycle (r, c)
+  Mark(u16, u16),
+  /// toggle_flag (r, c)
+  Flag(u16, u16),
+  /// finish
+  Finish
+}
+
+/// column letters of c (0: a, 25: z, 26: aa)
+pub fn col_name(c: u16) -> String {
+  let mut s = vec![];
+  let mut n = c as u32 + 1;
+  while n > 0 {
+    n -= 1;
+    s.push(b'a' + (n % 26) as u8);
+    n /= 26;
+  }
+  s.iter().rev().map(|&b| b as char).collect()
+}
+
+/// cell (r, c) of the notation `c5` (column letters and row number from 1)
+pub fn parse_cell(s: &str) -> Result<(u16, u16), MineFieldError> {
+  let i = s.find(|ch: char| !ch.is_ascii_lowercase()).unwrap_or(s.len());
+  let (a, n) = s.split_at(i);
+  if a.is_empty() || a.len() > 4 { Err(ParseError(format!("cell: {}", s)))? }
+  let c = a.bytes().fold(0u32, |c, b| c * 26 + (b - b'a') as u32 + 1) - 1;
+  let r: u16 = n.parse()?;
+  if r == 0 || c > u16::MAX as u32 { Err(ParseError(format!("cell: {}", s)))? }
+  Ok((r - 1, c as u16))
+}
+
+/// Move
+impl Move {
+  /// play the move on the field (false: no effect)
+  pub fn play(&self, f: &mut MineField) -> bool {
+    match *self {
+    Move::Open(r, c) => f.goto(r, c) && f.click(),
+    Move::Chord(r, c) => f.goto(r, c) && f.chord(),
+    Move::Mark(r, c) => f.goto(r, c) && f.mark(),
+    Move::Flag(r, c) => f.goto(r, c) && f.toggle_flag().is_ok(),
+    Move::Finish => f.finish()
+    }
+  }
+}
+
+/// Move
+impl fmt::Display for Move {
+  /// fmt (notation)
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let (k, r, c) = match *self {
+    Move::Open(r, c) => ('o', r, c),
+    Move::Chord(r, c) => ('#', r, c),
+    Move::Mark(r, c) => ('m', r, c),
+    Move::Flag(r, c) => ('f', r, c),
+    Move::Finish => return write!(f, "F")
+    };
+    write!(f, "{}:{}{}", k, col_name(c), r as u32 + 1)
+  }
+}
+
+/// Move
+impl FromStr for Move {
+  type Err = MineFieldError;
+
+  /// from_str (notation)
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if s == "F" { return Ok(Move::Finish); }
+    let (k, a) = s.split_once(':')
+      .ok_or_else(|| ParseError(format!("move: {}", s)))?;
+    let (r, c) = parse_cell(a)?;
+    Ok(match k {
+    "o" => Move::Open(r, c),
+    "#" => Move::Chord(r, c),
+    "m" => Move::Mark(r, c),
+    "f" => Move::Flag(r, c),
+    _ => Err(ParseError(format!("move: {}", s)))?
+    })
+  }
+}
+
+/// Replay
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+  /// area width
+  pub w: u16,
+  /// area height
+  pub h: u16,
+  /// mines
+  pub m: u16,
+  /// generation seed
+  pub seed: u64,
+  /// moves with the elapsed time
+  pub mv: Vec<(Duration, Move)>,
+  /// claimed result and time (time of the last move)
+  pub claim: Option<(Outcome, Duration)>
+}
+
+/// Replay
+impl Replay {
+  /// constructor (empty) for the field
+  pub fn new(f: &MineField) -> Self {
+    Replay{w: f.w, h: f.h, m: f.m, seed: f.seed, mv: vec![], claim: None}
+  }
+
+  /// field before the first move
+  pub fn field(&self) -> MineField {
+    MineField::from_seed(self.w, self.h, self.m, self.seed)
+  }
+
+  /// duration (time of the last move)
+  pub fn duration(&self) -> Duration {
+    self.mv.last().map_or(Duration::ZERO, |&(t, _)| t)
+  }
+
+  /// verify by re-simulation with the seed (returns the outcome)
+  pub fn verify(&self, seed: u64) -> Result<Outcome, VerifyError> {
+    if seed != self.seed { return Err(VerifyError::Seed(self.seed, seed)); }
+    let mut f = MineField::from_seed(self.w, self.h, self.m, seed);
+    let mut p = Duration::ZERO;
+    for (i, &(t, m)) in self.mv.iter().enumerate() {
+      if t < p { return Err(VerifyError::Time(i)); }
+      p = t;
+      if f.is_end() { return Err(VerifyError::AfterEnd(i)); }
+      if let Move::Open(r, c) | Move::Chord(r, c) | Move::Mark(r, c) |
+        Move::Flag(r, c) = m {
+        if r >= f.h || c >= f.w { return Err(VerifyError::OutOfBounds(i)); }
+      }
+      m.play(&mut f);
+    }
+    let o = if f.is_success() { Outcome::Won }
+      else if f.is_end() { Outcome::Lost }
+      else { Outcome::Stuck };
+    if let Some((k, t)) = self.claim {
+      if k != o { return Err(VerifyError::Result(k, o)); }
+      if t != p { return Err(VerifyError::Duration(t, p)); }
+    }
+    Ok(o)
+  }
+
+  /// load
+  pub fn load(p: impl AsRef<path::Path>) -> Result<Self, MineFieldError> {
+    fs::read_to_string(p)?.parse()
+  }
+
+  /// save
+  pub fn save(&self, p: impl AsRef<path::Path>) ->
+    Result<(), MineFieldError> {
+    fs::write(p, self.to_string())?;
+    Ok(())
+  }
+}
+
+/// VerifyError
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VerifyError {
+  /// seed of the replay differs (replay, expected)
+  Seed(u64, u64),
+  /// time goes back at the move
+  Time(usize),
+  /// move after the end of the game
+  AfterEnd(usize),
+  /// move out of the field
+  OutOfBounds(usize),
+  /// claimed result differs (claimed, simulated)
+  Result(Outcome, Outcome),
+  /// claimed time differs (claimed, simulated)
+  Duration(Duration, Duration)
+}
+
+/// VerifyError
+impl fmt::Display for VerifyError {
+  /// fmt
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+    VerifyError::Seed(a, b) => write!(f, "seed {} is not {}", a, b),
+    VerifyError::Time(i) => write!(f, "time goes back at move {}", i),
+    VerifyError::AfterEnd(i) => write!(f, "move {} after the end", i),
+    VerifyError::OutOfBounds(i) => write!(f, "move {} is out of bounds", i),
+    VerifyError::Result(a, b) => write!(f, "claimed {:?} but {:?}", a, b),
+    VerifyError::Duration(a, b) => write!(f, "claimed {:?} but {:?}", a, b)
+    }
+  }
+}
+
+/// VerifyError
+impl Error for VerifyError {}
+
+/// Replay
+impl fmt::Display for Replay {
+  /// fmt
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "{} {} {} {}", self.w, self.h, self.m, self.seed)?;
+    for &(t, m) in &self.mv { writeln!(f, "{} {}", t.as_millis(), m)?; }
+    if let Some((o, t)) = self.claim {
+      let o = match o {
+      Outcome::Won => "won",
+      Outcome::Lost => "lost",
+      Outcome::Stuck => "stuck"
+      };
+      writeln!(f, "= {} {}", o, t.as_millis())?;
+    }
+    Ok(())
+  }
+}
+
+/// Replay
+impl FromStr for Replay {
+  type Err = MineFieldError;
+
+  /// from_str
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut ls = s.lines().map(|l| l.trim())
+      .filter(|l| !l.is_empty() && !l.starts_with('#'));
+    let l = ls.next().ok_or(ParseError("replay: empty".into()))?;
+    let q: Vec<&str> = l.split_whitespace().collect();
+    if q.len() != 4 { Err(ParseError(format!("replay: {}", l)))? }
+    let mut rp = Replay{w: q[0].parse()?, h: q[1].parse()?, m: q[2].parse()?,
+      seed: q[3].parse()?, mv: vec![], claim: None};
+    for l in ls {
+      if let Some(c) = l.strip_prefix('=') {
+        let q: Vec<&str> = c.split_whitespace().collect();
+        if q.len() != 2 { Err(ParseError(format!("replay: {}", l)))? }
+        let o = match q[0] {
+        "won" => Outcome::Won,
+        "lost" => Outcome::Lost,
+        "stuck" => Outcome::Stuck,
+        _ => Err(ParseError(format!("replay: {}", l)))?
+        };
+        rp.claim = Some((o, Duration::from_millis(q[1].parse()?)));
+        continue;
+      }
+      let (t, m) = l.split_once(' ')
+        .ok_or_else(|| ParseError(format!("replay: {}", l)))?;
+      rp.mv.push((Duration::from_millis(t.parse()?), m.trim().parse()?));
+    }
+    Ok(rp)
+  }
+}
+
+/// Pace (live run compared with the ghost by opened cells)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pace {
+  /// live run ahead by cells
+  Ahead(u16),
+  /// live run behind by cells
+  Behind(u16),
+  /// even
+  Even
+}
+
+/// Ghost (replay played in parallel with a live game on the same seed)
+pub struct Ghost {
+  /// replay
+  pub rp: Replay,
+  /// ghost field
+  pub f: MineField,
+  /// next move index
+  pub i: usize
+}
+
+/// Ghost
+impl Ghost {
+  /// constructor
+  pub fn new(rp: Replay) -> Self {
+    let f = rp.field();
+    Ghost{rp, f, i: 0}
+  }
+
+  /// advance the ghost until the time (returns moves played)
+  pub fn advance(&mut self, t: Duration) -> usize {
+    let i = self.i;
+    while let Some(&(u, m)) = self.rp.mv.get(self.i) {
+      if u > t { break; }
+      m.play(&mut self.f);
+      self.i += 1;
+    }
+    self.i - i
+  }
+
+  /// is_done (every move played)
+  pub fn is_done(&self) -> bool { self.i >= self.rp.mv.len() }
+
+  /// pace of the live field at its elapsed time
+  pub fn pace(&mut self, live: &MineField) -> Pace {
+    self.advance(live.elapsed());
+    let (a, b) = (live.opened(), self.f.opened());
+    if a > b { Pace::Ahead(a - b) }
+    else if a < b { Pace::Behind(b - a) }
+    else { Pace::Even }
+  }
+
+  /// cells the ghost has opened
+  pub fn cells(&self) -> impl Iterator<Item=(u16, u16)> + '_ {
+    (0..self.f.h).flat_map(move |r| (0..self.f.w).map(move |c| (r, c)))
+      .filter(|&(r, c)| self.f.is_opened(r, c))
+  }
+}
+
+/// MineField
+impl MineField {
+  /// record a move at the elapsed time into the history
+  pub fn record(&mut self, m: Move) -> () {
+    let t = self.elapsed();
+    self.hist.push((t, m));
+  }
+
+  /// history of moves with the elapsed time
+  pub fn history(&self) -> &[(Duration, Move)] { &self.hist }
+
+  /// history as text (`seconds notation` lines)
+  pub fn history_text(&self) -> String {
+    self.hist.iter().map(|(t, m)|
+      format!("{:.3} {}\n", t.as_secs_f64(), m)).collect()
+  }
+
+  /// replay of the history so far (claimed result when ended)
+  pub fn replay(&self) -> Replay {
+    let t = self.hist.last().map_or(Duration::ZERO, |&(t, _)| t);
+    let claim = if self.is_success() { Some((Outcome::Won, t)) }
+      else if self.is_end() { Some((Outcome::Lost, t)) }
+      else { None };
+    Replay{mv: self.hist.clone(), claim, ..Replay::new(self)}
+  }
+}
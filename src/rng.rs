@@ -0,0 +1,46 @@
+//! deterministic rng for board generation
+//!
+//! part of the board code format, any implementation must follow it
+//!
+//! - ChaCha20 (20 rounds, stream 0, counter from 0)
+//! - key: seed u64 little endian followed by 24 zero bytes
+//! - draw: next 32bit little endian word of the keystream
+//! - below(n): draw x until x < n * (0xffffffff / n), then x % n
+//! - shuffle: for i from len - 1 down to 1, swap i and below(i + 1)
+//!
+
+use rand_chacha::ChaCha20Rng;
+use rand_chacha::rand_core::{RngCore, SeedableRng};
+
+/// Rng
+pub struct Rng(ChaCha20Rng);
+
+/// Rng
+impl Rng {
+  /// constructor
+  pub fn new(seed: u64) -> Self {
+    let mut k = [0u8; 32];
+    k[..8].copy_from_slice(&seed.to_le_bytes());
+    Rng(ChaCha20Rng::from_seed(k))
+  }
+
+  /// draw
+  pub fn draw(&mut self) -> u32 { self.0.next_u32() }
+
+  /// below n (n > 0)
+  pub fn below(&mut self, n: u32) -> u32 {
+    let lim = n * (u32::MAX / n);
+    loop {
+      let x = self.draw();
+      if x < lim { return x % n; }
+    }
+  }
+
+  /// shuffle
+  pub fn shuffle<T>(&mut self, p: &mut [T]) -> () {
+    for i in (1..p.len()).rev() {
+      let j = self.below(i as u32 + 1) as usize;
+      p.swap(i, j);
+    }
+  }
+}
@@ -0,0 +1,60 @@
+//! game loop runner
+//!
+
+use std::error::Error;
+use std::time::Duration;
+
+use crate::{MineField, TViewRun};
+use crate::blink::Blinker;
+use crate::input::{Handled, Input};
+
+/// trait InputSource
+pub trait InputSource {
+  /// poll an input until timeout (None when idle)
+  fn poll(&mut self, timeout: Duration) ->
+    Result<Option<Input>, Box<dyn Error>>;
+}
+
+/// Outcome
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+  /// won
+  Won,
+  /// lost
+  Lost,
+  /// quit before the end
+  Quit
+}
+
+/// run a game until the end (ending is already drawn) or quit (g is
+/// flushed before each poll)
+pub fn run<T>(m: &mut MineField, g: &mut impl TViewRun<T>,
+  i: &mut impl InputSource) -> Result<Outcome, Box<dyn Error>> {
+  run_blink(m, g, i, &mut Blinker::default())
+}
+
+/// run with a blink timing
+pub fn run_blink<T>(m: &mut MineField, g: &mut impl TViewRun<T>,
+  i: &mut impl InputSource, bl: &mut Blinker) ->
+  Result<Outcome, Box<dyn Error>> {
+  m.invalidate();
+  bl.reset(m, g)?;
+  while !m.is_end() {
+    if m.check_time().is_some() { m.ending(g)?; break; } // under input
+    g.flush()?;
+    match i.poll(bl.ms)? {
+    Some(p) => match m.handle(p, g)? {
+      Handled::Quit => return Ok(Outcome::Quit),
+      Handled::None => (),
+      _ => bl.t = 0 // handle shows the cursor
+    },
+    None => bl.tick(m, g)?
+    }
+  }
+  while !m.cascade.is_empty() { // reveal sequence (input ignored)
+    g.flush()?;
+    if i.poll(bl.ms)?.is_none() { bl.tick(m, g)?; }
+  }
+  g.flush()?;
+  Ok(if m.is_success() { Outcome::Won } else { Outcome::Lost })
+}
@@ -0,0 +1,364 @@
+//! versioned compact binary save format (save files and replays)
+//!
+//! `MFSV` version (u8) flags (u8: 1 history, 2 mask), integers little
+//! endian, counts and times as varint, then
+//! - header length (u16) and the header, fields appended by later
+//!   versions are skipped by older readers
+//!   - w h m (u16) seed (u64) s r c (u16) first x (r c u16, 0xffff none)
+//!     hits (u8) state (u8: 1 placed, 2 expired, 4 paused) elapsed ms
+//!     (u64) clicks opens chords flags wasted (u32)
+//!   - config bits (u16: 1 question, 2 wrap_x, 4 wrap_y, 8 animate,
+//!     16 slow_reveal, 32 auto_finish, 64 confirm) lives (u8) max_flags
+//!     (u16) fog (u16) time_limit (u32) (max value none) treasures
+//!     powerups (u16) win (u8) cell_width (u16)
+//! - cells run length encoded as (run varint, cell byte) up to w x h
+//! - mask (flag 2) packed bits of the rows, lsb first
+//! - items count then (r c u16, kind u8: 0 treasure, 1 power up)
+//! - history (flag 1) count then (ms varint, move u8: 0 open, 1 chord,
+//!   2 mark, 3 flag, 4 finish, r c u16 but for finish)
+//! - checksum FNV-1a 32 of all the bytes before
+//!
+
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use crate::MineField;
+use crate::config::{Config, WinCondition};
+use crate::metrics::Clicks;
+use crate::replay::Move;
+use crate::treasure::Item;
+use crate::validate::InvariantError;
+
+/// magic bytes
+pub const MAGIC: &[u8; 4] = b"MFSV";
+
+/// save format version
+pub const VERSION: u8 = 1;
+
+/// flag history section
+const HISTORY: u8 = 1;
+
+/// flag mask section
+const MASK: u8 = 2;
+
+/// SaveError
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SaveError {
+  /// not starting with MAGIC
+  BadMagic,
+  /// version newer than VERSION
+  Version(u8),
+  /// input ends at the offset
+  Truncated(usize),
+  /// checksum mismatch (stored, computed)
+  Checksum(u32, u32),
+  /// corrupt field (what)
+  Corrupt(&'static str),
+  /// bytes after the checksum
+  Trailing(usize),
+  /// state decoded breaks an invariant
+  Invalid(InvariantError)
+}
+
+/// SaveError
+impl fmt::Display for SaveError {
+  /// fmt
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+    SaveError::BadMagic => write!(f, "save: bad magic"),
+    SaveError::Version(v) => write!(f, "save: unsupported version {}", v),
+    SaveError::Truncated(i) => write!(f, "save: truncated at {}", i),
+    SaveError::Checksum(a, b) =>
+      write!(f, "save: checksum {:08x} != {:08x}", a, b),
+    SaveError::Corrupt(s) => write!(f, "save: corrupt {}", s),
+    SaveError::Trailing(n) => write!(f, "save: {} trailing bytes", n),
+    SaveError::Invalid(e) => write!(f, "save: {}", e)
+    }
+  }
+}
+
+/// SaveError
+impl Error for SaveError {
+  /// source
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    match self {
+    SaveError::Invalid(e) => Some(e),
+    _ => None
+    }
+  }
+}
+
+/// checksum FNV-1a 32
+fn fnv(b: &[u8]) -> u32 {
+  b.iter().fold(0x811c9dc5u32, |h, &u|
+    (h ^ u as u32).wrapping_mul(0x01000193))
+}
+
+/// put varint
+fn put_varint(b: &mut Vec<u8>, mut n: u64) -> () {
+  while n >= 0x80 {
+    b.push(n as u8 | 0x80);
+    n >>= 7;
+  }
+  b.push(n as u8);
+}
+
+/// (r, c) or 0xffff 0xffff
+fn put_pos(b: &mut Vec<u8>, p: Option<(u16, u16)>) -> () {
+  let (r, c) = p.unwrap_or((u16::MAX, u16::MAX));
+  b.extend_from_slice(&r.to_le_bytes());
+  b.extend_from_slice(&c.to_le_bytes());
+}
+
+/// Reader
+struct Reader<'a> {
+  /// bytes
+  b: &'a [u8],
+  /// position
+  i: usize
+}
+
+/// Reader
+impl Reader<'_> {
+  /// bytes n
+  fn take(&mut self, n: usize) -> Result<&[u8], SaveError> {
+    let s = self.b.get(self.i..self.i + n)
+      .ok_or(SaveError::Truncated(self.i))?;
+    self.i += n;
+    Ok(s)
+  }
+
+  /// u8
+  fn u8(&mut self) -> Result<u8, SaveError> { Ok(self.take(1)?[0]) }
+
+  /// u16
+  fn u16(&mut self) -> Result<u16, SaveError> {
+    Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap_or_default()))
+  }
+
+  /// u32
+  fn u32(&mut self) -> Result<u32, SaveError> {
+    Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap_or_default()))
+  }
+
+  /// u64
+  fn u64(&mut self) -> Result<u64, SaveError> {
+    Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap_or_default()))
+  }
+
+  /// varint
+  fn varint(&mut self) -> Result<u64, SaveError> {
+    let mut n = 0u64;
+    for k in 0..10 {
+      let u = self.u8()?;
+      n |= ((u & 0x7f) as u64) << (7 * k);
+      if u & 0x80 == 0 { return Ok(n); }
+    }
+    Err(SaveError::Corrupt("varint"))
+  }
+
+  /// (r, c) or None by 0xffff 0xffff
+  fn pos(&mut self) -> Result<Option<(u16, u16)>, SaveError> {
+    let (r, c) = (self.u16()?, self.u16()?);
+    Ok(if (r, c) == (u16::MAX, u16::MAX) { None } else { Some((r, c)) })
+  }
+}
+
+/// header bytes of the field
+fn header(f: &MineField) -> Vec<u8> {
+  let mut b = vec![];
+  for n in [f.w, f.h, f.m] { b.extend_from_slice(&n.to_le_bytes()); }
+  b.extend_from_slice(&f.seed.to_le_bytes());
+  for n in [f.s, f.r, f.c] { b.extend_from_slice(&n.to_le_bytes()); }
+  put_pos(&mut b, f.first);
+  put_pos(&mut b, f.x);
+  b.push(f.hits);
+  b.push(f.p as u8 | (f.expired as u8) << 1 | (f.paused as u8) << 2);
+  b.extend_from_slice(&(f.elapsed().as_millis() as u64).to_le_bytes());
+  for n in [f.ck.opens, f.ck.chords, f.ck.flags, f.ck.wasted] {
+    b.extend_from_slice(&n.to_le_bytes());
+  }
+  let g = &f.cfg;
+  let bits = [g.question, g.wrap_x, g.wrap_y, g.animate, g.slow_reveal,
+    g.auto_finish, g.confirm].iter().enumerate()
+    .fold(0u16, |a, (i, &x)| a | (x as u16) << i);
+  b.extend_from_slice(&bits.to_le_bytes());
+  b.push(g.lives);
+  for n in [g.max_flags.unwrap_or(u16::MAX), g.fog.unwrap_or(u16::MAX)] {
+    b.extend_from_slice(&n.to_le_bytes());
+  }
+  b.extend_from_slice(&g.time_limit.unwrap_or(u32::MAX).to_le_bytes());
+  for n in [g.treasures, g.powerups] {
+    b.extend_from_slice(&n.to_le_bytes());
+  }
+  b.push(match g.win {
+  WinCondition::Opened => 0,
+  WinCondition::Flagged => 1,
+  WinCondition::Both => 2
+  });
+  b.extend_from_slice(&g.cell_width.to_le_bytes());
+  b
+}
+
+/// MineField
+impl MineField {
+  /// to_save bytes (history: with the moves for a replay)
+  pub fn to_save(&self, history: bool) -> Vec<u8> {
+    let mut b = MAGIC.to_vec();
+    b.push(VERSION);
+    b.push(if history { HISTORY } else { 0 } |
+      if self.mask.is_some() { MASK } else { 0 });
+    let h = header(self);
+    b.extend_from_slice(&(h.len() as u16).to_le_bytes());
+    b.extend(h);
+    let v = self.f.as_slice();
+    let mut i = 0;
+    while i < v.len() {
+      let n = v[i..].iter().take_while(|&&u| u == v[i]).count();
+      put_varint(&mut b, n as u64);
+      b.push(v[i]);
+      i += n;
+    }
+    if let Some(m) = &self.mask {
+      let bits = m.iter().flatten().collect::<Vec<_>>();
+      b.extend(bits.chunks(8).map(|q| q.iter().enumerate()
+        .fold(0u8, |a, (i, &&x)| a | (x as u8) << i)));
+    }
+    let mut items = self.items.iter().collect::<Vec<_>>();
+    items.sort_by_key(|p| *p.0);
+    put_varint(&mut b, items.len() as u64);
+    for (&(r, c), &item) in items {
+      put_pos(&mut b, Some((r, c)));
+      b.push(if item == Item::Treasure { 0 } else { 1 });
+    }
+    if history {
+      put_varint(&mut b, self.hist.len() as u64);
+      for (t, mv) in &self.hist {
+        put_varint(&mut b, t.as_millis() as u64);
+        let (k, p) = match *mv {
+        Move::Open(r, c) => (0, Some((r, c))),
+        Move::Chord(r, c) => (1, Some((r, c))),
+        Move::Mark(r, c) => (2, Some((r, c))),
+        Move::Flag(r, c) => (3, Some((r, c))),
+        Move::Finish => (4, None)
+        };
+        b.push(k);
+        if p.is_some() { put_pos(&mut b, p); }
+      }
+    }
+    let k = fnv(&b);
+    b.extend_from_slice(&k.to_le_bytes());
+    b
+  }
+
+  /// from_save bytes (timer stopped at the elapsed time, invariants
+  /// validated)
+  pub fn from_save(b: &[u8]) -> Result<Self, SaveError> {
+    if b.len() < 4 { return Err(SaveError::Truncated(b.len())); }
+    if &b[..4] != MAGIC { return Err(SaveError::BadMagic); }
+    let mut r = Reader{b, i: 4};
+    let ver = r.u8()?;
+    if ver > VERSION { return Err(SaveError::Version(ver)); }
+    let flags = r.u8()?;
+    let hl = r.u16()? as usize;
+    let mut h = Reader{b: r.take(hl)?, i: 0};
+    let mut f = Self::read_header(&mut h)?;
+    let n = f.w as usize * f.h as usize;
+    let mut i = 0;
+    while i < n {
+      let k = r.varint()? as usize;
+      let u = r.u8()?;
+      if k == 0 || i + k > n { return Err(SaveError::Corrupt("cell runs")); }
+      f.f.as_mut_slice()[i..i + k].fill(u);
+      i += k;
+    }
+    if flags & MASK != 0 {
+      let v = r.take(n.div_ceil(8))?;
+      let w = f.w as usize;
+      f.mask = Some((0..f.h as usize).map(|j| (0..w).map(|c| {
+        let i = j * w + c;
+        v[i / 8] >> (i % 8) & 1 != 0
+      }).collect()).collect());
+    }
+    for _ in 0..r.varint()? {
+      let p = f.inside(r.pos()?, "item")?.ok_or(SaveError::Corrupt("item"))?;
+      let item = match r.u8()? {
+      0 => Item::Treasure,
+      1 => Item::PowerUp,
+      _ => return Err(SaveError::Corrupt("item"))
+      };
+      f.items.insert(p, item);
+    }
+    if flags & HISTORY != 0 {
+      for _ in 0..r.varint()? {
+        let t = Duration::from_millis(r.varint()?);
+        let k = r.u8()?;
+        let p = if k == 4 { None } else { r.pos()? };
+        let mv = match (k, p) {
+        (0, Some((j, i))) => Move::Open(j, i),
+        (1, Some((j, i))) => Move::Chord(j, i),
+        (2, Some((j, i))) => Move::Mark(j, i),
+        (3, Some((j, i))) => Move::Flag(j, i),
+        (4, _) => Move::Finish,
+        _ => return Err(SaveError::Corrupt("move"))
+        };
+        f.hist.push((t, mv));
+      }
+    }
+    let k = fnv(&b[..r.i]);
+    let s = r.u32()?;
+    if s != k { return Err(SaveError::Checksum(s, k)); }
+    if r.i != b.len() { return Err(SaveError::Trailing(b.len() - r.i)); }
+    f.validate().map_err(SaveError::Invalid)?;
+    Ok(f)
+  }
+
+  /// position p inside the field (Corrupt(what) when out of it)
+  fn inside(&self, p: Option<(u16, u16)>, what: &'static str) ->
+    Result<Option<(u16, u16)>, SaveError> {
+    match p {
+    Some((r, c)) if self.check(r, c).is_err() => Err(SaveError::Corrupt(what)),
+    _ => Ok(p)
+    }
+  }
+
+  /// field of the header (fields of later versions skipped)
+  fn read_header(h: &mut Reader) -> Result<Self, SaveError> {
+    let (w, hh, m) = (h.u16()?, h.u16()?, h.u16()?);
+    let n = MineField::check_size(w, hh)
+      .map_err(|_| SaveError::Corrupt("dimensions"))?;
+    if m as u32 >= n { return Err(SaveError::Corrupt("mines")); }
+    let mut f = MineField::from_seed(w, hh, m, h.u64()?);
+    (f.s, f.r, f.c) = (h.u16()?, h.u16()?, h.u16()?);
+    f.check(f.r, f.c).map_err(|_| SaveError::Corrupt("cursor"))?;
+    f.first = f.inside(h.pos()?, "first")?;
+    f.x = f.inside(h.pos()?, "detonated")?;
+    f.hits = h.u8()?;
+    let st = h.u8()?;
+    (f.p, f.expired, f.paused) = (st & 1 != 0, st & 2 != 0, st & 4 != 0);
+    f.tm.acc = Duration::from_millis(h.u64()?);
+    f.ck = Clicks{opens: h.u32()?, chords: h.u32()?, flags: h.u32()?,
+      wasted: h.u32()?};
+    let bits = h.u16()?;
+    let bit = |i: u16| bits >> i & 1 != 0;
+    let opt16 = |n: u16| if n == u16::MAX { None } else { Some(n) };
+    let (lives, max_flags, fog) = (h.u8()?, h.u16()?, h.u16()?);
+    let tl = h.u32()?;
+    let (treasures, powerups) = (h.u16()?, h.u16()?);
+    let win = match h.u8()? {
+    0 => WinCondition::Opened,
+    1 => WinCondition::Flagged,
+    2 => WinCondition::Both,
+    _ => return Err(SaveError::Corrupt("win condition"))
+    };
+    f.cfg = Config{question: bit(0), wrap_x: bit(1), wrap_y: bit(2),
+      animate: bit(3), slow_reveal: bit(4), auto_finish: bit(5),
+      confirm: bit(6), lives,
+      max_flags: opt16(max_flags), fog: opt16(fog),
+      time_limit: if tl == u32::MAX { None } else { Some(tl) },
+      treasures, powerups, win, cell_width: h.u16()?};
+    f.set_viewport(w, hh);
+    Ok(f)
+  }
+}
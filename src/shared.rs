@@ -0,0 +1,90 @@
+//! thread safe shared board
+//!
+//! an actor thread owns the MineField (hooks and placements need not be
+//! Send) and runs closures sent by clone-able handles in order, so a
+//! network thread and a render thread share one game without locks of
+//! their own, the thread ends when every handle is dropped
+//!
+
+use std::error::Error;
+use std::fmt;
+use std::sync::mpsc;
+use std::thread;
+
+use crate::MineField;
+use crate::replay::Move;
+use crate::wire::Snapshot;
+
+/// job run on the actor thread
+type Job = Box<dyn FnOnce(&mut MineField) + Send>;
+
+/// Disconnected (the actor thread is gone, a job panicked)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Disconnected;
+
+/// Disconnected
+impl fmt::Display for Disconnected {
+  /// fmt
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "shared minefield disconnected")
+  }
+}
+
+/// Disconnected
+impl Error for Disconnected {}
+
+/// SharedMineField (handle to the actor thread)
+#[derive(Debug, Clone)]
+pub struct SharedMineField {
+  /// jobs
+  tx: mpsc::Sender<Job>
+}
+
+/// SharedMineField
+impl SharedMineField {
+  /// spawn the actor thread owning the field built by make on it
+  pub fn spawn(make: impl FnOnce() -> MineField + Send + 'static) -> Self {
+    let (tx, rx) = mpsc::channel::<Job>();
+    thread::spawn(move || {
+      let mut f = make();
+      for j in rx { j(&mut f); }
+    });
+    SharedMineField{tx}
+  }
+
+  /// with the field on the actor thread (waits for the result)
+  pub fn with<R: Send + 'static>(&self,
+    j: impl FnOnce(&mut MineField) -> R + Send + 'static) ->
+    Result<R, Disconnected> {
+    let (tx, rx) = mpsc::sync_channel(1);
+    self.tx.send(Box::new(move |f| { let _ = tx.send(j(f)); }))
+      .map_err(|_| Disconnected)?;
+    rx.recv().map_err(|_| Disconnected)
+  }
+
+  /// post a job without waiting
+  pub fn post(&self, j: impl FnOnce(&mut MineField) + Send + 'static) ->
+    Result<(), Disconnected> {
+    self.tx.send(Box::new(j)).map_err(|_| Disconnected)
+  }
+
+  /// play a move (false: no effect)
+  pub fn play(&self, m: Move) -> Result<bool, Disconnected> {
+    self.with(move |f| m.play(f))
+  }
+
+  /// status line text
+  pub fn status(&self) -> Result<String, Disconnected> {
+    self.with(|f| f.status())
+  }
+
+  /// is_end
+  pub fn is_end(&self) -> Result<bool, Disconnected> {
+    self.with(|f| f.is_end())
+  }
+
+  /// snapshot of the field (see wire::Snapshot::of)
+  pub fn snapshot(&self, hide: bool) -> Result<Snapshot, Disconnected> {
+    self.with(move |f| Snapshot::of(f, hide))
+  }
+}
@@ -0,0 +1,232 @@
+//! headless simulation
+//!
+
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+use crate::MineField;
+use crate::config::{Config, Difficulty};
+use crate::replay::Move;
+use crate::solver::Known;
+
+/// trait Policy (bot playing headless)
+pub trait Policy {
+  /// reset before a new game
+  fn reset(&mut self) -> () {}
+  /// next cell (r, c) to open, None to give up
+  fn next(&mut self, m: &MineField) -> Option<(u16, u16)>;
+}
+
+/// Basic policy (single cell deduction, gives up when a guess is needed)
+#[derive(Debug, Clone, Default)]
+pub struct Basic;
+
+/// Basic
+impl Basic {
+  /// closed neighbors of (r, c)
+  pub fn closed(m: &MineField, r: u16, c: u16) -> Vec<(u16, u16)> {
+    m.neighbors(r, c).filter(|&(j, i)| !m.is_opened(j, i)).collect()
+  }
+}
+
+/// trait Policy for Basic
+impl Policy for Basic {
+  /// next
+  fn next(&mut self, m: &MineField) -> Option<(u16, u16)> {
+    if m.opened() == 0 { return Some((m.h / 2, m.w / 2)); } // first click
+    let mut k = vec![vec![false; m.w as usize]; m.h as usize]; // known mines
+    loop {
+      let mut changed = false;
+      for r in 0..m.h {
+        for c in 0..m.w {
+          if !m.is_opened(r, c) { continue; }
+          let v = MineField::get_v(m.f[r as usize][c as usize]) as usize;
+          if v == 0 { continue; }
+          let q = Self::closed(m, r, c);
+          let n = q.iter().filter(|&&(j, i)| k[j as usize][i as usize]).count();
+          if q.len() == v {
+            for &(j, i) in &q {
+              if !k[j as usize][i as usize] {
+                k[j as usize][i as usize] = true;
+                changed = true;
+              }
+            }
+          } else if n == v {
+            let s = q.iter().find(|&&(j, i)| !k[j as usize][i as usize]);
+            if s.is_some() { return s.copied(); }
+          }
+        }
+      }
+      if !changed { return None; }
+    }
+  }
+}
+
+/// Best policy (best_move: deduction, then the guess of the least risk)
+#[derive(Debug, Clone, Default)]
+pub struct Best;
+
+/// trait Policy for Best
+impl Policy for Best {
+  /// next
+  fn next(&mut self, m: &MineField) -> Option<(u16, u16)> {
+    match m.best_move()?.mv {
+    Move::Open(r, c) => Some((r, c)),
+    _ => None
+    }
+  }
+}
+
+/// Cause of a game not won
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cause {
+  /// mine hit on a guess
+  Guess,
+  /// mine hit that was deducible
+  Blunder,
+  /// policy gave up
+  GaveUp,
+  /// clicks limit (w x h) reached
+  Limit
+}
+
+/// Outcome
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+  /// won
+  Won,
+  /// lost
+  Lost,
+  /// policy gave up (guess required)
+  Stuck
+}
+
+/// Report for a seed
+#[derive(Debug, Clone)]
+pub struct Report {
+  /// seed
+  pub seed: u64,
+  /// outcome
+  pub outcome: Outcome,
+  /// clicks
+  pub clicks: u32,
+  /// opened cells
+  pub opened: u16,
+  /// degenerate board (no mines, mine full, or won by the first click)
+  pub degenerate: bool,
+  /// unsolvable by the policy
+  pub unsolvable: bool,
+  /// cause when not won
+  pub cause: Option<Cause>,
+  /// 3BV of the board
+  pub bbbv: u32,
+  /// time the policy took
+  pub t: Duration
+}
+
+/// play a game by the policy
+pub fn play(d: Difficulty, cfg: &Config, seed: u64, p: &mut impl Policy) ->
+  Report {
+  let mut m = MineField::from_seed(d.w, d.h, d.m, seed);
+  m.cfg = cfg.clone();
+  p.reset();
+  let (mut clicks, mut cause) = (0, None);
+  let t = Instant::now();
+  while !m.is_end() {
+    if clicks > d.w as u32 * d.h as u32 { cause = Some(Cause::Limit); break; }
+    let Some((r, c)) = p.next(&m) else { cause = Some(Cause::GaveUp); break; };
+    if m.p && m.get(r, c).is_some_and(|u| u.is_mine()) {
+      let k = m.deduce()[r as usize][c as usize] == Known::Mine;
+      cause = Some(if k { Cause::Blunder } else { Cause::Guess });
+    }
+    m.goto(r, c);
+    m.click();
+    clicks += 1;
+  }
+  let t = t.elapsed();
+  if m.is_success() { cause = None; }
+  let outcome = if m.is_success() { Outcome::Won }
+    else if m.is_explosion() { Outcome::Lost }
+    else { Outcome::Stuck };
+  let degenerate = d.m == 0 || d.m as u32 + 1 >= d.w as u32 * d.h as u32
+    || (outcome == Outcome::Won && clicks == 1);
+  Report{seed, outcome, clicks, opened: m.opened(), degenerate,
+    unsolvable: outcome != Outcome::Won, cause, bbbv: m.bbbv(), t}
+}
+
+/// sweep every seed in the range
+pub fn sweep(seeds: Range<u64>, d: Difficulty, cfg: &Config,
+  p: &mut impl Policy) -> Vec<Report> {
+  seeds.map(|seed| play(d, cfg, seed, p)).collect()
+}
+
+/// SimReport
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimReport {
+  /// games played
+  pub games: u32,
+  /// won
+  pub won: u32,
+  /// lost
+  pub lost: u32,
+  /// stuck (gave up or limit)
+  pub stuck: u32,
+  /// not won by cause (Guess, Blunder, GaveUp, Limit)
+  pub causes: [u32; 4],
+  /// average time the policy took per game
+  pub avg_time: Duration,
+  /// average 3BV
+  pub avg_bbbv: f64,
+  /// average 3BV of games won
+  pub avg_bbbv_won: f64
+}
+
+/// SimReport
+impl SimReport {
+  /// from reports
+  pub fn of(v: &[Report]) -> Self {
+    let mut s = SimReport{games: v.len() as u32, ..SimReport::default()};
+    if v.is_empty() { return s; }
+    let (mut t, mut b, mut bw) = (Duration::ZERO, 0u64, 0u64);
+    for p in v {
+      match p.outcome {
+      Outcome::Won => { s.won += 1; bw += p.bbbv as u64; },
+      Outcome::Lost => s.lost += 1,
+      Outcome::Stuck => s.stuck += 1
+      }
+      if let Some(c) = p.cause { s.causes[c as usize] += 1; }
+      t += p.t;
+      b += p.bbbv as u64;
+    }
+    s.avg_time = t / s.games;
+    s.avg_bbbv = b as f64 / s.games as f64;
+    s.avg_bbbv_won = if s.won == 0 { 0.0 } else { bw as f64 / s.won as f64 };
+    s
+  }
+
+  /// win rate (0 when no games)
+  pub fn win_rate(&self) -> f64 {
+    if self.games == 0 { 0.0 } else { self.won as f64 / self.games as f64 }
+  }
+}
+
+/// simulate n games of seeds 0..n by a clone of the policy per game (in
+/// parallel with the rayon feature)
+#[cfg(not(feature = "rayon"))]
+pub fn simulate<P: Policy + Clone>(d: Difficulty, cfg: &Config, n: u64,
+  p: &P) -> SimReport {
+  let v = (0..n).map(|seed| play(d, cfg, seed, &mut p.clone()))
+    .collect::<Vec<_>>();
+  SimReport::of(&v)
+}
+
+/// simulate n games of seeds 0..n by a clone of the policy per game (in
+/// parallel with the rayon feature)
+#[cfg(feature = "rayon")]
+pub fn simulate<P: Policy + Clone + Sync>(d: Difficulty, cfg: &Config,
+  n: u64, p: &P) -> SimReport {
+  use rayon::prelude::*;
+  let v = (0..n).into_par_iter()
+    .map(|seed| play(d, cfg, seed, &mut p.clone())).collect::<Vec<_>>();
+  SimReport::of(&v)
+}
@@ -0,0 +1,126 @@
+//! render sink with an associated error type
+//!
+//! the core draws through Sink so a backend chooses its own error (no boxed
+//! error per write), ViewSink adapts a TView (Box<dyn Error>)
+//!
+
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+
+use mvc_rs::TView;
+
+use crate::{MineField, Packet};
+use crate::error::MineFieldError;
+
+/// trait Sink
+pub trait Sink {
+  /// error of the sink
+  type Error;
+  /// put glyph s at screen (x, y) with the style and colors
+  fn put(&mut self, x: u16, y: u16, st: u16, bgc: u16, fgc: u16, s: &str) ->
+    Result<(), Self::Error>;
+}
+
+/// ViewSink (Sink of a TView)
+pub struct ViewSink<'a, T, V: TView<T>> {
+  /// view
+  pub v: &'a mut V,
+  /// color type of the view
+  p: PhantomData<T>
+}
+
+/// ViewSink
+impl<'a, T, V: TView<T>> ViewSink<'a, T, V> {
+  /// constructor
+  pub fn new(v: &'a mut V) -> Self { ViewSink{v, p: PhantomData} }
+}
+
+/// trait Sink for ViewSink
+impl<T, V: TView<T>> Sink for ViewSink<'_, T, V> {
+  type Error = Box<dyn Error>;
+
+  /// put
+  fn put(&mut self, x: u16, y: u16, st: u16, bgc: u16, fgc: u16, s: &str) ->
+    Result<(), Self::Error> {
+    self.v.wr(Packet{x, y, st, bgc, fgc, msg: s})
+  }
+}
+
+/// DrawError
+#[derive(Debug)]
+pub enum DrawError<E> {
+  /// error of the field
+  Field(MineFieldError),
+  /// error of the sink
+  Sink(E)
+}
+
+/// DrawError
+impl<E: fmt::Display> fmt::Display for DrawError<E> {
+  /// fmt
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+    DrawError::Field(e) => write!(f, "{}", e),
+    DrawError::Sink(e) => write!(f, "sink: {}", e)
+    }
+  }
+}
+
+/// DrawError
+impl<E: fmt::Debug + fmt::Display> Error for DrawError<E> {}
+
+/// MineFieldError
+impl From<DrawError<Box<dyn Error>>> for MineFieldError {
+  /// from
+  fn from(e: DrawError<Box<dyn Error>>) -> Self {
+    match e {
+    DrawError::Field(e) => e,
+    DrawError::Sink(e) => MineFieldError::RenderError(e)
+    }
+  }
+}
+
+/// MineField
+impl MineField {
+  /// put_cell glyph and colors o of the cell at viewport (x, y) with the
+  /// style st (screen x * cw, padded with spaces)
+  pub fn put_cell<S: Sink>(&self, s: &mut S, x: u16, y: u16, st: u16,
+    o: &(String, u16, u16)) -> Result<(), S::Error> {
+    let cw = self.cw();
+    s.put(x * cw, y, st, o.1, o.2, &o.0)?;
+    for i in 1..cw { s.put(x * cw + i, y, st, o.1, o.2, " ")?; }
+    Ok(())
+  }
+
+  /// draw cells in the viewport (composited with the layers)
+  pub fn draw<S: Sink>(&self, s: &mut S) -> Result<(), DrawError<S::Error>> {
+    for y in 0..self.vp.h {
+      for x in 0..self.vp.w {
+        let (r, c) = (self.vp.y + y, self.vp.x + x);
+        let o = self.compose(r, c, x, y).map_err(DrawError::Field)?;
+        let st = self.style(r, c);
+        self.put_cell(s, x, y, st, &o).map_err(DrawError::Sink)?;
+      }
+    }
+    Ok(())
+  }
+
+  /// draw only cells changed since the last draw_dirty
+  pub fn draw_dirty<S: Sink>(&mut self, s: &mut S) ->
+    Result<(), DrawError<S::Error>> {
+    if self.d.len() != self.vp.h as usize { self.invalidate(); }
+    for y in 0..self.vp.h {
+      for x in 0..self.vp.w {
+        let (r, c) = (self.vp.y + y, self.vp.x + x);
+        let o = self.compose(r, c, x, y).map_err(DrawError::Field)?;
+        let st = self.style(r, c);
+        let d = &mut self.d[y as usize][x as usize];
+        if d.as_ref() == Some(&(o.clone(), st)) { continue; }
+        *d = Some((o.clone(), st));
+        self.put_cell(s, x, y, st, &o).map_err(DrawError::Sink)?;
+      }
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,201 @@
+//! solver on what a player sees (opened numbers only, flags not trusted)
+//!
+
+use std::fmt;
+
+use crate::MineField;
+use crate::exact::MAX_BORDER;
+use crate::messages::Messages;
+use crate::replay::Move;
+
+/// Knowledge of closed cells deduced from the opened numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Known {
+  /// not deduced
+  Unknown,
+  /// must be safe
+  Safe,
+  /// must be a mine (or a defused mine)
+  Mine
+}
+
+/// Rule of a deduction by a number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+  /// the number already has its mines around (the rest is safe)
+  Satisfied,
+  /// the number has exactly as many closed cells left as mines (all mines)
+  Exhausted
+}
+
+/// Step of the reasoning chain (cell (r, c) is known by the number v at by)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+  /// row
+  pub r: u16,
+  /// column
+  pub c: u16,
+  /// conclusion
+  pub known: Known,
+  /// number cell (r, c) used
+  pub by: (u16, u16),
+  /// number
+  pub v: u8,
+  /// rule
+  pub rule: Rule
+}
+
+/// Step
+impl fmt::Display for Step {
+  /// fmt (a sentence)
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let k = if self.known == Known::Mine { "a mine" } else { "safe" };
+    let (n, s) = (self.v, if self.v == 1 { "" } else { "s" });
+    write!(f, "cell ({},{}) must be {} because the '{}' at ({},{}) ",
+      self.r, self.c, k, self.v, self.by.0, self.by.1)?;
+    match self.rule {
+    Rule::Satisfied => write!(f, "already has {} mine{} around", n, s),
+    Rule::Exhausted => write!(f, "has exactly {} closed neighbor{}", n, s)
+    }
+  }
+}
+
+/// Suggestion of best_move
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Suggestion {
+  /// move
+  pub mv: Move,
+  /// mine probability of the move (0: guaranteed safe)
+  pub risk: f64
+}
+
+/// MineField
+impl MineField {
+  /// best_move (a deduced safe open, otherwise the guess of the least
+  /// risk by exact or estimated probabilities, flagged cells skipped,
+  /// None: ended, paused or nothing to open)
+  pub fn best_move(&self) -> Option<Suggestion> {
+    if self.is_end() || self.paused { return None; }
+    if !self.p {
+      let mv = Move::Open(self.h / 2, self.w / 2);
+      return Some(Suggestion{mv, risk: 0.0});
+    }
+    let k = self.deduce();
+    let ok = |r: u16, c: u16| !self.is_opened(r, c) &&
+      !self.is_flagged(r, c) && self.is_playable(r, c);
+    if let Some((r, c, _)) = self.cells().find(|&(r, c, _)|
+      k[r as usize][c as usize] == Known::Safe && ok(r, c)) {
+      return Some(Suggestion{mv: Move::Open(r, c), risk: 0.0});
+    }
+    let p = self.probabilities();
+    self.cells().filter(|&(r, c, _)| ok(r, c))
+      .filter_map(|(r, c, _)| p[r as usize][c as usize].map(|x| (x, r, c)))
+      .min_by(|a, b| a.0.total_cmp(&b.0))
+      .map(|(risk, r, c)| Suggestion{mv: Move::Open(r, c), risk})
+  }
+
+  /// closed playable cells around (r, c) for the solver (defused mines
+  /// count as mines)
+  pub fn unresolved(&self, r: u16, c: u16) -> Vec<(u16, u16)> {
+    self.neighbors(r, c).filter(|&(j, i)| !self.is_opened(j, i)).collect()
+  }
+
+  /// knowledge [r][c] by single number deduction until nothing changes
+  pub fn deduce(&self) -> Vec<Vec<Known>> { self.reason().0 }
+
+  /// explain the deduction as the reasoning chain in order
+  pub fn explain(&self) -> Vec<Step> { self.reason().1 }
+
+  /// hint of the first deduction step by the messages (None: no step)
+  pub fn hint_with(&self, ms: &impl Messages) -> Option<String> {
+    self.explain().first().map(|s| ms.hint(s))
+  }
+
+  /// reason (knowledge and the steps)
+  fn reason(&self) -> (Vec<Vec<Known>>, Vec<Step>) {
+    let mut st = vec![];
+    let mut k = vec![vec![Known::Unknown; self.w as usize]; self.h as usize];
+    for (r, c, u) in self.cells() {
+      if !u.is_open() || !u.is_mine() { continue; }
+      k[r as usize][c as usize] = Known::Mine;
+    }
+    loop {
+      let mut changed = false;
+      for (r, c, u) in self.cells() {
+        if !u.is_open() || u.is_mine() { continue; }
+        let q = self.unresolved(r, c);
+        let q = q.iter().copied().chain(self.neighbors(r, c)
+          .filter(|&(j, i)| k[j as usize][i as usize] == Known::Mine &&
+            self.is_opened(j, i))).collect::<Vec<_>>();
+        let n = |e: Known| q.iter()
+          .filter(|&&(j, i)| k[j as usize][i as usize] == e).count();
+        let v = u.value() as usize;
+        let (mines, safes) = (n(Known::Mine), n(Known::Safe));
+        let (e, rule) = if mines == v { (Known::Safe, Rule::Satisfied) }
+          else if q.len() - safes == v { (Known::Mine, Rule::Exhausted) }
+          else { continue; };
+        for &(j, i) in &q {
+          let p = &mut k[j as usize][i as usize];
+          if *p != Known::Unknown { continue; }
+          *p = e;
+          changed = true;
+          st.push(Step{r: j, c: i, known: e, by: (r, c), v: v as u8, rule});
+        }
+      }
+      if !changed { return (k, st); }
+    }
+  }
+
+  /// mine probabilities [r][c] of closed playable cells (None: opened or
+  /// masked), exact within exact::MAX_BORDER border cells, otherwise
+  /// deduced cells are 0 or 1, border cells by the most constrained number
+  /// around, others by the density of the rest
+  pub fn probabilities(&self) -> Vec<Vec<Option<f64>>> {
+    if let Some(e) = self.enumerate(MAX_BORDER) { return e.p; }
+    let mut p = vec![vec![None; self.w as usize]; self.h as usize];
+    if !self.p {
+      let d = self.m as f64 / self.playable().max(1) as f64;
+      for (r, c, _) in self.cells().filter(|&(r, c, _)|
+        self.is_playable(r, c)) { p[r as usize][c as usize] = Some(d); }
+      return p;
+    }
+    let k = self.deduce();
+    let (mut mines, mut rest) = (0u32, vec![]);
+    for (r, c, u) in self.cells() {
+      if u.is_open() || !self.is_playable(r, c) { continue; }
+      let (ru, cu) = (r as usize, c as usize);
+      match k[ru][cu] {
+      Known::Safe => p[ru][cu] = Some(0.0),
+      Known::Mine => { p[ru][cu] = Some(1.0); mines += 1; },
+      Known::Unknown => rest.push((r, c))
+      }
+    }
+    let defused = self.defused() as u32;
+    let left = (self.m as u32).saturating_sub(mines + defused) as f64;
+    let mut interior = vec![];
+    for &(r, c) in &rest {
+      let b = self.neighbors(r, c).filter(|&(j, i)| {
+        let u = self.f[j as usize][i as usize];
+        Self::is_o(u) && !Self::is_mine(Self::get_v(u))
+      }).map(|(j, i)| {
+        let q = self.unresolved(j, i);
+        let m = self.neighbors(j, i).filter(|&(y, x)|
+          k[y as usize][x as usize] == Known::Mine).count();
+        let u = q.iter().filter(|&&(y, x)|
+          k[y as usize][x as usize] == Known::Unknown).count();
+        let v = Self::get_v(self.f[j as usize][i as usize]) as usize;
+        v.saturating_sub(m) as f64 / u.max(1) as f64
+      }).fold(None, |a: Option<f64>, x| Some(a.map_or(x, |a| a.max(x))));
+      match b {
+      Some(x) => p[r as usize][c as usize] = Some(x.min(1.0)),
+      None => interior.push((r, c))
+      }
+    }
+    let border: f64 = rest.iter().filter_map(|&(r, c)|
+      p[r as usize][c as usize]).sum();
+    let d = if interior.is_empty() { 0.0 }
+      else { ((left - border) / interior.len() as f64).clamp(0.0, 1.0) };
+    for (r, c) in interior { p[r as usize][c as usize] = Some(d); }
+    p
+  }
+}
@@ -0,0 +1,103 @@
+//! read only spectator view consuming the delta stream
+//!
+//! built from a hidden snapshot (closed cells stripped by the server) so
+//! mine locations of closed cells are never known to the view
+//!
+
+use mvc_rs::TView;
+
+use crate::{MineField, MAX_CELLS};
+use crate::error::MineFieldError::{self, ParseError};
+use crate::sync::{Delta, Update};
+use crate::wire::Snapshot;
+
+/// SpectatorView
+pub struct SpectatorView {
+  /// field reconstructed from the stream (no mines of closed cells)
+  f: MineField,
+  /// next sequence number expected
+  n: u64
+}
+
+/// SpectatorView
+impl SpectatorView {
+  /// constructor from a hidden snapshot and the next sequence number
+  /// (the opened count must match the opened cells without mines, cells
+  /// force opened at the ending are not counted)
+  pub fn new(s: &Snapshot, n: u64) -> Result<Self, MineFieldError> {
+    if !s.hidden {
+      Err(ParseError("spectator: snapshot is not hidden".into()))?
+    }
+    let k = MineField::check_size(s.w, s.h)?;
+    let hw = s.f.len() == s.h as usize &&
+      s.f.iter().all(|v| v.len() == s.w as usize);
+    if !hw { Err(ParseError("spectator: cells are not h x w".into()))? }
+    if s.m as u32 >= k { return Err(MineFieldError::TooManyMines(s.m)); }
+    let o = s.f.iter().flatten()
+      .filter(|&&u| MineField::is_o(u) && !MineField::is_mine(u)).count();
+    let q = (s.s & 0x3fff) as usize;
+    if q > o || (s.s < 0x4000 && q != o) {
+      Err(ParseError(format!("spectator: opened {} but {} cells open", q, o)))?
+    }
+    let mut f = MineField::from_seed(s.w, s.h, s.m, 0);
+    f.f = s.f.clone().into();
+    f.s = s.s;
+    f.p = true;
+    f.k = false; // no cursor
+    Ok(SpectatorView{f, n})
+  }
+
+  /// field (read only)
+  pub fn field(&self) -> &MineField { &self.f }
+
+  /// apply an update of the stream (false: ignored)
+  pub fn apply(&mut self, u: Update) -> Result<bool, MineFieldError> {
+    if u.n != self.n {
+      Err(ParseError(format!("spectator: expected {} got {}", self.n, u.n)))?
+    }
+    let f = &mut self.f;
+    match u.d {
+    Delta::Open(r, c, _) | Delta::Mark(r, c, _) | Delta::Explode(r, c)
+      if r >= f.h || c >= f.w => {
+      return Err(MineFieldError::OutOfBounds(r, c));
+    },
+    _ => ()
+    }
+    self.n += 1;
+    Ok(match u.d {
+    Delta::Start(..) => false,
+    Delta::Open(r, c, v) => {
+      if f.is_opened(r, c) { return Ok(false); }
+      let u = &mut f.f[r as usize][c as usize];
+      *u = 0x10 | (v & 0x0f);
+      if v & 0x0f != 15 {
+        if f.opened() as u32 >= MAX_CELLS {
+          Err(ParseError("spectator: opened count overflow".into()))?
+        }
+        f.s += 1;
+      }
+      true
+    },
+    Delta::Mark(r, c, b) => {
+      if f.is_opened(r, c) { return Ok(false); }
+      let u = &mut f.f[r as usize][c as usize];
+      *u = (*u & !0x60) | (b & 0x60);
+      true
+    },
+    Delta::Explode(r, c) => { f.x = Some((r, c)); f.s |= 0x8000; true },
+    Delta::Win => { f.s |= 0x4000; true },
+    Delta::Expire => { f.expired = true; true }
+    })
+  }
+
+  /// set_viewport size
+  pub fn set_viewport(&mut self, w: u16, h: u16) -> () {
+    self.f.set_viewport(w, h);
+  }
+
+  /// refresh
+  pub fn refresh<T>(&self, g: &mut impl TView<T>) ->
+    Result<(), MineFieldError> {
+    self.f.refresh(g)
+  }
+}
@@ -0,0 +1,89 @@
+//! session statistics (win rate, best times per difficulty, streaks)
+//!
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use std::{fs, path};
+
+use crate::MineField;
+use crate::config::Difficulty;
+#[cfg(feature = "serde")]
+use crate::error::MineFieldError::{self, ParseError};
+
+/// Stats
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stats {
+  /// games played
+  pub played: u32,
+  /// wins
+  pub won: u32,
+  /// losses
+  pub lost: u32,
+  /// best times by difficulty key (see key)
+  pub best: BTreeMap<String, Duration>,
+  /// current win streak
+  pub streak: u32,
+  /// best win streak
+  pub best_streak: u32
+}
+
+/// Stats
+impl Stats {
+  /// key of a difficulty `WxH-M`
+  pub fn key(d: Difficulty) -> String { format!("{}x{}-{}", d.w, d.h, d.m) }
+
+  /// record a game (returns the best time before this game when won)
+  pub fn record(&mut self, d: Difficulty, won: bool, t: Duration) ->
+    Option<Duration> {
+    self.played += 1;
+    if !won {
+      self.lost += 1;
+      self.streak = 0;
+      return None;
+    }
+    self.won += 1;
+    self.streak += 1;
+    self.best_streak = self.best_streak.max(self.streak);
+    let b = self.best.get(&Self::key(d)).copied();
+    if b.is_none_or(|b| t < b) { self.best.insert(Self::key(d), t); }
+    b
+  }
+
+  /// record a field at the end (false: not ended)
+  pub fn record_field(&mut self, m: &MineField) -> bool {
+    if !m.is_end() { return false; }
+    let d = Difficulty{w: m.w, h: m.h, m: m.m};
+    self.record(d, m.is_success(), m.elapsed());
+    true
+  }
+
+  /// best time of a difficulty
+  pub fn best(&self, d: Difficulty) -> Option<Duration> {
+    self.best.get(&Self::key(d)).copied()
+  }
+
+  /// win rate 0.0 - 1.0
+  pub fn win_rate(&self) -> f64 {
+    if self.played == 0 { 0.0 } else { self.won as f64 / self.played as f64 }
+  }
+
+  /// load json
+  #[cfg(feature = "serde")]
+  pub fn load(p: impl AsRef<path::Path>) -> Result<Self, MineFieldError> {
+    serde_json::from_str(&fs::read_to_string(p)?)
+      .map_err(|e| ParseError(format!("stats: {}", e)))
+  }
+
+  /// save json
+  #[cfg(feature = "serde")]
+  pub fn save(&self, p: impl AsRef<path::Path>) ->
+    Result<(), MineFieldError> {
+    let s = serde_json::to_string_pretty(self)
+      .map_err(|e| ParseError(format!("stats: {}", e)))?;
+    fs::write(p, s)?;
+    Ok(())
+  }
+}
@@ -0,0 +1,111 @@
+//! board shapes from an ascii stencil (or a PBM bitmap with feature pbm)
+//! with seeded mines (random seed with feature rand)
+//!
+
+use crate::MineField;
+use crate::error::MineFieldError;
+#[cfg(feature = "pbm")]
+use crate::error::MineFieldError::ParseError;
+
+/// MineField
+impl MineField {
+  /// from_stencil ('#' playable, others not, short lines are padded)
+  #[cfg(feature = "rand")]
+  pub fn from_stencil(s: &str, m: u16) -> Result<Self, MineFieldError> {
+    let v = s.lines().map(|l| l.chars().map(|c| c == '#').collect())
+      .collect::<Vec<Vec<bool>>>();
+    Self::from_mask(v, m)
+  }
+
+  /// from_mask of playable cells [r][c] (random seed, see from_mask_seed)
+  #[cfg(feature = "rand")]
+  pub fn from_mask(v: Vec<Vec<bool>>, m: u16) ->
+    Result<Self, MineFieldError> {
+    Self::from_mask_seed(v, m, rand::random())
+  }
+
+  /// from_mask_seed of playable cells [r][c] (rows are padded to the
+  /// longest)
+  pub fn from_mask_seed(mut v: Vec<Vec<bool>>, m: u16, seed: u64) ->
+    Result<Self, MineFieldError> {
+    let w = v.iter().map(|r| r.len()).max().unwrap_or(0);
+    let (w, h) = (clamp(w), clamp(v.len()));
+    MineField::check_size(w, h)?;
+    for r in v.iter_mut() { r.resize(w as usize, false); }
+    let n = v.iter().flatten().filter(|&&b| b).count();
+    if n == 0 { return Err(MineFieldError::InvalidDimensions(w, h)); }
+    if m as usize >= n { return Err(MineFieldError::TooManyMines(m)); }
+    let mut f = MineField::try_from_seed(w, h, m, seed)?;
+    f.set_mask(v);
+    Ok(f)
+  }
+
+  /// from_pbm bitmap (P1 or P4, black 1 is playable)
+  #[cfg(all(feature = "pbm", feature = "rand"))]
+  pub fn from_pbm(b: &[u8], m: u16) -> Result<Self, MineFieldError> {
+    Self::from_mask(pbm_mask(b)?, m)
+  }
+}
+
+/// playable cells [r][c] from pbm bitmap
+#[cfg(feature = "pbm")]
+pub fn pbm_mask(b: &[u8]) -> Result<Vec<Vec<bool>>, MineFieldError> {
+  let mut p = 0; // read position
+  let magic = pbm_token(b, &mut p)?;
+  let w: usize = pbm_token(b, &mut p)?.parse()?;
+  let h: usize = pbm_token(b, &mut p)?.parse()?;
+  MineField::check_size(clamp(w), clamp(h))?; // before allocating w * h
+  let v: Vec<bool> = match magic.as_str() {
+  "P1" => { // pixels may be packed without spaces
+    let mut v = Vec::with_capacity(w * h);
+    while v.len() < w * h {
+      pbm_skip(b, &mut p);
+      match b.get(p) {
+      Some(b'0') => v.push(false),
+      Some(b'1') => v.push(true),
+      Some(&c) => Err(ParseError(format!("pbm: invalid pixel {}",
+        c as char)))?,
+      None => Err(ParseError("pbm: truncated".into()))?
+      }
+      p += 1;
+    }
+    v
+  },
+  "P4" => {
+    let s = p + 1; // single whitespace after the header
+    let n = w.div_ceil(8);
+    if b.len() < s + n * h { Err(ParseError("pbm: truncated".into()))? }
+    (0..w * h).map(|i| {
+      let (r, c) = (i / w, i % w);
+      b[s + r * n + c / 8] & (0x80 >> (c % 8)) != 0
+    }).collect()
+  },
+  _ => Err(ParseError(format!("pbm: unsupported {}", magic)))?
+  };
+  Ok(v.chunks(w.max(1)).map(|r| r.to_vec()).collect())
+}
+
+/// clamp a size to u16 (too large sizes fail check_size)
+fn clamp(n: usize) -> u16 { n.min(u16::MAX as usize) as u16 }
+
+/// skip whitespaces and comments in pbm
+#[cfg(feature = "pbm")]
+fn pbm_skip(b: &[u8], p: &mut usize) -> () {
+  loop {
+    match b.get(*p) {
+    Some(b'#') => while b.get(*p).is_some_and(|&c| c != b'\n') { *p += 1; },
+    Some(c) if c.is_ascii_whitespace() => *p += 1,
+    _ => break
+    }
+  }
+}
+
+/// next header token in pbm
+#[cfg(feature = "pbm")]
+fn pbm_token(b: &[u8], p: &mut usize) -> Result<String, MineFieldError> {
+  pbm_skip(b, p);
+  let s = *p;
+  while b.get(*p).is_some_and(|c| !c.is_ascii_whitespace()) { *p += 1; }
+  if s == *p { Err(ParseError("pbm: truncated header".into()))? }
+  Ok(String::from_utf8_lossy(&b[s..*p]).to_string())
+}
@@ -0,0 +1,51 @@
+//! style attribute bits of WR packets (Packet::st)
+//!
+//! the low byte is the style id (BASE for every cell), the high byte the
+//! attributes a backend may render (SGR on terminals, a cursor rectangle
+//! on GUI backends for CURSOR), backends ignoring them still show the
+//! cursor by its colors
+//!
+
+use crate::MineField;
+
+/// style id of every cell
+pub const BASE: u16 = 3;
+
+/// bold
+pub const BOLD: u16 = 0x0100;
+
+/// reverse video
+pub const REVERSE: u16 = 0x0200;
+
+/// underline
+pub const UNDERLINE: u16 = 0x0400;
+
+/// blink
+pub const BLINK: u16 = 0x0800;
+
+/// cell under the cursor (shown in the blink phase)
+pub const CURSOR: u16 = 0x1000;
+
+/// attribute bits of the style
+pub fn attrs(st: u16) -> u16 { st & 0xff00 }
+
+/// MineField
+impl MineField {
+  /// style of the cell (r, c) (cursor, detonated bold blink, misplaced
+  /// flag underline and correct flag bold at the end)
+  pub fn style(&self, r: u16, c: u16) -> u16 {
+    let Some(u) = self.f.at(r, c) else { return BASE; };
+    if self.paused || !self.is_visible(r, c) || !self.is_playable(r, c) {
+      return BASE;
+    }
+    let mut st = BASE;
+    if (r, c) == (self.r, self.c) && !self.is_success() && self.is_blink() {
+      st |= CURSOR;
+    }
+    if self.x == Some((r, c)) { st |= BOLD | BLINK; }
+    else if self.is_end() && Self::is_f(u) {
+      st |= if Self::is_mine(u) { BOLD } else { UNDERLINE };
+    }
+    st
+  }
+}
@@ -0,0 +1,176 @@
+//! cooperative board sharing by ordered deltas
+//!
+//! both boards are generated from the same seed (mines placed by the first
+//! click carried as `Start`), deltas are persisted as `n op args` lines
+//!
+//! conflict rules (applied in sequence order)
+//! - open wins over flag: an open clears the marks of the cell
+//! - a mark on an opened cell is ignored
+//! - the last mark of a closed cell wins
+//! - an open of an opened cell is ignored
+//! - the first state transition (explode, win, expire) wins
+//!
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::MineField;
+use crate::apply::MoveError;
+use crate::error::MineFieldError::{self, ParseError};
+use crate::replay::Move;
+
+/// Delta
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Delta {
+  /// mines placed by the first click (r, c)
+  Start(u16, u16),
+  /// cell (r, c) opened with the value (0 - 8, 15 defused mine)
+  Open(u16, u16, u8),
+  /// cell (r, c) marked (flag 0x40 / question 0x20 / none 0)
+  Mark(u16, u16, u8),
+  /// exploded at (r, c)
+  Explode(u16, u16),
+  /// won
+  Win,
+  /// time budget expired
+  Expire
+}
+
+/// Update (delta with the sequence number)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Update {
+  /// sequence number
+  pub n: u64,
+  /// delta
+  pub d: Delta
+}
+
+/// Update
+impl fmt::Display for Update {
+  /// fmt
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.d {
+    Delta::Start(r, c) => write!(f, "{} s {} {}", self.n, r, c),
+    Delta::Open(r, c, v) => write!(f, "{} o {} {} {}", self.n, r, c, v),
+    Delta::Mark(r, c, b) => write!(f, "{} m {} {} {}", self.n, r, c, b),
+    Delta::Explode(r, c) => write!(f, "{} x {} {}", self.n, r, c),
+    Delta::Win => write!(f, "{} w", self.n),
+    Delta::Expire => write!(f, "{} t", self.n)
+    }
+  }
+}
+
+/// Update
+impl FromStr for Update {
+  type Err = MineFieldError;
+
+  /// from_str
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let q: Vec<&str> = s.split_whitespace().collect();
+    let e = || ParseError(format!("delta: {}", s));
+    let a = |i: usize| -> Result<u16, MineFieldError> {
+      Ok(q.get(i).ok_or_else(e)?.parse()?)
+    };
+    let d = match (q.get(1).copied(), q.len()) {
+    (Some("s"), 4) => Delta::Start(a(2)?, a(3)?),
+    (Some("o"), 5) => Delta::Open(a(2)?, a(3)?, q[4].parse()?),
+    (Some("m"), 5) => Delta::Mark(a(2)?, a(3)?, q[4].parse()?),
+    (Some("x"), 4) => Delta::Explode(a(2)?, a(3)?),
+    (Some("w"), 2) => Delta::Win,
+    (Some("t"), 2) => Delta::Expire,
+    _ => Err(e())?
+    };
+    Ok(Update{n: q[0].parse()?, d})
+  }
+}
+
+/// Sync (one peer of the shared board)
+#[derive(Debug, Clone, Default)]
+pub struct Sync {
+  /// next sequence number to emit
+  pub n: u64,
+  /// next sequence number expected from the other peer
+  pub rx: u64
+}
+
+/// Sync
+impl Sync {
+  /// constructor
+  pub fn new() -> Self { Sync::default() }
+
+  /// emit a delta with the next sequence number
+  pub fn emit(&mut self, d: Delta) -> Update {
+    self.n += 1;
+    Update{n: self.n - 1, d}
+  }
+
+  /// play a local move and emit its deltas
+  pub fn play(&mut self, f: &mut MineField, m: Move) ->
+    Result<Vec<Update>, MoveError> {
+    let p = f.p;
+    let o = f.apply_move(m)?;
+    let mut v = vec![];
+    if let (false, Some((r, c))) = (p, f.first) {
+      v.push(self.emit(Delta::Start(r, c)));
+    }
+    for &(r, c, x) in &o.opened { v.push(self.emit(Delta::Open(r, c, x))); }
+    for &(r, c, b) in &o.marked { v.push(self.emit(Delta::Mark(r, c, b))); }
+    if o.exploded {
+      let (r, c) = f.x.unwrap_or((f.r, f.c));
+      v.push(self.emit(Delta::Explode(r, c)));
+    }
+    if o.won { v.push(self.emit(Delta::Win)); }
+    Ok(v)
+  }
+
+  /// apply an update from the other peer (false: ignored by the rules)
+  pub fn apply(&mut self, f: &mut MineField, u: Update) ->
+    Result<bool, MineFieldError> {
+    if u.n != self.rx {
+      Err(ParseError(format!("sync: expected {} got {}", self.rx, u.n)))?
+    }
+    match u.d {
+    Delta::Start(r, c) | Delta::Open(r, c, _) | Delta::Mark(r, c, _) |
+    Delta::Explode(r, c) => { f.check(r, c)?; },
+    Delta::Win | Delta::Expire => ()
+    }
+    self.rx += 1;
+    if f.is_end() { return Ok(false); } // first state transition wins
+    Ok(match u.d {
+    Delta::Start(r, c) => {
+      if f.p { return Ok(false); }
+      let (y, x) = (f.r, f.c);
+      f.goto(r, c);
+      f.start();
+      f.goto(y, x);
+      f.tm.start();
+      true
+    },
+    Delta::Open(r, c, _) => {
+      if !f.p { Err(ParseError("sync: open before start".into()))? }
+      if f.is_opened(r, c) { return Ok(false); }
+      let u = &mut f.f[r as usize][c as usize];
+      *u &= !0x60; // open wins over flag
+      MineField::set_o(u, false);
+      if MineField::is_mine(*u) { f.hits += 1; } // defused
+      else { f.s += 1; }
+      true
+    },
+    Delta::Mark(r, c, b) => {
+      if f.is_opened(r, c) { return Ok(false); }
+      let u = &mut f.f[r as usize][c as usize];
+      *u = (*u & !0x60) | (b & 0x60);
+      true
+    },
+    Delta::Explode(r, c) => {
+      f.x = Some((r, c));
+      f.explosion();
+      true
+    },
+    Delta::Win => { f.success(); true },
+    Delta::Expire => { f.expire(); true }
+    })
+  }
+}
@@ -0,0 +1,76 @@
+//! scripted game harness for behavior tests of frontends (feature testing)
+//!
+//! board stencil `*` mine, `-` masked out, others safe, visible stencil by
+//! tiles `#` closed, `F` flag, `?` question, `.` empty, `1`-`8` numbers,
+//! `*` mine, `X` exploded, `D` defused, `W` wrong flag, `-` void, `~` fog,
+//! `P` paused, lines are trimmed and blank lines skipped
+//!
+
+use crate::MineField;
+use crate::error::MineFieldError;
+use crate::replay::Move;
+use crate::tile::Tile;
+
+/// lines of a stencil (trimmed, blank lines skipped)
+fn lines(s: &str) -> Vec<&str> {
+  s.lines().map(|l| l.trim()).filter(|l| !l.is_empty()).collect()
+}
+
+/// board of the stencil (mines placed, nothing opened)
+pub fn board(s: &str) -> MineField {
+  let v = lines(s);
+  let w = v.iter().map(|l| l.chars().count()).max().unwrap_or(0) as u16;
+  let q = v.iter().enumerate().flat_map(|(r, l)| l.chars().enumerate()
+    .filter(|&(_, ch)| ch == '*').map(move |(c, _)| (r as u16, c as u16)))
+    .collect::<Vec<_>>();
+  let mut f = MineField::with_mines(w, v.len() as u16, &q);
+  if v.iter().any(|l| l.contains('-')) {
+    f.set_mask(v.iter().map(|l| {
+      let mut m = l.chars().map(|ch| ch != '-').collect::<Vec<_>>();
+      m.resize(w as usize, false);
+      m
+    }).collect());
+  }
+  f
+}
+
+/// char of the tile in the visible stencil
+pub fn ch(t: Tile) -> char {
+  match t {
+  Tile::Closed => '#',
+  Tile::Flag => 'F',
+  Tile::Question => '?',
+  Tile::Number(0) => '.',
+  Tile::Number(n) => (b'0' + n) as char,
+  Tile::Mine => '*',
+  Tile::Exploded => 'X',
+  Tile::Defused => 'D',
+  Tile::WrongFlag => 'W',
+  Tile::Void => '-',
+  Tile::Fog => '~',
+  Tile::Paused => 'P'
+  }
+}
+
+/// visible stencil of the field (rows joined by '\n')
+pub fn visible(f: &MineField) -> String {
+  (0..f.h).map(|r| (0..f.w).map(|c|
+    f.tile(r, c).map_or('-', ch)).collect::<String>())
+    .collect::<Vec<_>>().join("\n")
+}
+
+/// play moves in notation separated by whitespaces (see replay)
+pub fn play(f: &mut MineField, moves: &str) -> Result<(), MineFieldError> {
+  for m in moves.split_whitespace() { m.parse::<Move>()?.play(f); }
+  Ok(())
+}
+
+/// play moves on the board stencil and assert the visible stencil
+/// (panics with both stencils when they differ, returns the field)
+pub fn assert_script(b: &str, moves: &str, expected: &str) -> MineField {
+  let mut f = board(b);
+  if let Err(e) = play(&mut f, moves) { panic!("moves: {}", e); }
+  let (a, e) = (visible(&f), lines(expected).join("\n"));
+  assert!(a == e, "visible board\n{}\nexpected\n{}", a, e);
+  f
+}
@@ -0,0 +1,112 @@
+//! color theme (semantic roles to abstract color id pairs (bgc, fgc))
+//!
+
+/// Theme
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+  /// closed cell
+  pub closed: (u16, u16),
+  /// opened cell without mines around
+  pub open: (u16, u16),
+  /// opened cell with 1-8 mines around
+  pub num: [(u16, u16); 8],
+  /// flagged cell
+  pub flag: (u16, u16),
+  /// cell marked with a question
+  pub question: (u16, u16),
+  /// mine
+  pub mine: (u16, u16),
+  /// force opened cell at ending
+  pub ended: (u16, u16),
+  /// detonated mine
+  pub detonated: (u16, u16),
+  /// misplaced flag at ending
+  pub wrong: (u16, u16),
+  /// mine defused by a life
+  pub defused: (u16, u16),
+  /// cell hidden by the fog of war
+  pub fog: (u16, u16),
+  /// cell masked out of the board shape
+  pub void: (u16, u16),
+  /// mine revealed through a closed cell (see MineField::reveal_mines)
+  pub peek: (u16, u16),
+  /// cursor over the true cell (None: inverse colors of the cell)
+  pub cursor: Option<(u16, u16)>
+}
+
+/// Theme
+impl Default for Theme {
+  /// default (ids 0-5: closed, opened, force opened)
+  fn default() -> Self {
+    Theme{closed: (0, 1), open: (2, 3), num: [(2, 3); 8], flag: (0, 5),
+      question: (0, 3), mine: (4, 5), ended: (4, 5), detonated: (5, 4),
+      wrong: (1, 0), defused: (2, 5), fog: (0, 1), void: (2, 3),
+      peek: (0, 4), cursor: None}
+  }
+}
+
+/// Theme
+impl Theme {
+  /// classic windows style (ids 6-13: numbers 1-8, 14: flag, see classic_rgb)
+  pub fn classic() -> Self {
+    let mut num = [(2, 3); 8];
+    for (i, p) in num.iter_mut().enumerate() { p.1 = 6 + i as u16; }
+    Theme{num, flag: (0, 14), question: (0, 12), detonated: (8, 12),
+      wrong: (2, 14), defused: (2, 12), fog: (3, 2), ..Theme::default()}
+  }
+
+  /// rgb palette for ids 0-14 used by classic
+  pub fn classic_rgb() -> Vec<(u8, u8, u8)> {
+    vec![(128, 128, 128), (255, 255, 255), (192, 192, 192), (64, 64, 64),
+      (128, 0, 0), (255, 255, 0),
+      (0, 0, 255), (0, 128, 0), (255, 0, 0), (0, 0, 128),
+      (128, 0, 0), (0, 128, 128), (0, 0, 0), (128, 128, 128),
+      (255, 0, 0)]
+  }
+
+  /// colorblind safe (ids 15-22: Okabe-Ito numbers 1-8, 23: flag, see
+  /// colorblind_rgb)
+  pub fn colorblind() -> Self {
+    let mut num = [(2, 3); 8];
+    for (i, p) in num.iter_mut().enumerate() { p.1 = 15 + i as u16; }
+    Theme{num, flag: (0, 23), question: (0, 22), detonated: (8, 22),
+      wrong: (2, 23), defused: (2, 22), ..Theme::classic()}
+  }
+
+  /// rgb palette for ids 0-23 used by colorblind (0-14 as classic)
+  pub fn colorblind_rgb() -> Vec<(u8, u8, u8)> {
+    let mut p = Theme::classic_rgb();
+    p.extend([(0, 114, 178), (0, 158, 115), (213, 94, 0),
+      (204, 121, 167), (230, 159, 0), (86, 180, 233), (240, 228, 66),
+      (0, 0, 0), (213, 94, 0)]);
+    p
+  }
+
+  /// high contrast monochrome (ids 0: black, 1: white, use with
+  /// Glyphs::high_contrast, see high_contrast_rgb)
+  pub fn high_contrast() -> Self {
+    let (n, i) = ((0, 1), (1, 0)); // normal, inverse
+    Theme{closed: n, open: n, num: [n; 8], flag: i, question: i, mine: i,
+      ended: n, detonated: i, wrong: i, defused: n, fog: n, void: n, peek: i,
+      cursor: Some(i)}
+  }
+
+  /// rgb palette for ids 0-1 used by high_contrast
+  pub fn high_contrast_rgb() -> Vec<(u8, u8, u8)> {
+    vec![(0, 0, 0), (255, 255, 255)]
+  }
+
+  /// colors for a cell
+  pub fn get(&self, u: u8) -> (u16, u16) {
+    let v = u & 0x0f;
+    if u & 0x10 == 0 {
+      if u & 0x40 != 0 { self.flag }
+      else if u & 0x20 != 0 { self.question }
+      else { self.closed }
+    }
+    else if v == 0x0f { self.mine }
+    else if u & 0x80 != 0 { self.ended }
+    else if v == 0 { self.open }
+    else { self.num[(v - 1) as usize & 7] }
+  }
+}
@@ -0,0 +1,62 @@
+//! semantic tile ids for sprite based frontends (instead of glyphs)
+//!
+
+use crate::MineField;
+use crate::error::MineFieldError;
+
+/// Tile (what a cell shows, the cursor and layers are not included)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tile {
+  /// closed cell
+  Closed,
+  /// flag on a closed cell
+  Flag,
+  /// question on a closed cell
+  Question,
+  /// opened cell with mines around (0: empty)
+  Number(u8),
+  /// mine shown at the ending
+  Mine,
+  /// mine hit while lives remain
+  Defused,
+  /// detonated mine
+  Exploded,
+  /// flag on a cell without mine at the ending
+  WrongFlag,
+  /// masked out of the playable shape
+  Void,
+  /// hidden by the fog of war
+  Fog,
+  /// concealed while paused
+  Paused
+}
+
+/// MineField
+impl MineField {
+  /// tile at (r, c) (same decisions as c without the cursor)
+  pub fn tile(&self, r: u16, c: u16) -> Result<Tile, MineFieldError> {
+    self.check(r, c)?;
+    if self.paused { return Ok(Tile::Paused); }
+    if !self.is_visible(r, c) { return Ok(Tile::Fog); }
+    if !self.is_playable(r, c) { return Ok(Tile::Void); }
+    if self.x == Some((r, c)) { return Ok(Tile::Exploded); }
+    let u = self.f[r as usize][c as usize];
+    let u = if self.hidden.contains(&(r, c)) { u & !0x10 } else { u };
+    let v = Self::get_v(u);
+    Ok(if Self::is_e(u) && Self::is_f(u) && !Self::is_mine(v) {
+      Tile::WrongFlag
+    } else if Self::is_o(u) {
+      if !Self::is_mine(v) { Tile::Number(v) }
+      else if Self::is_e(u) { Tile::Mine }
+      else { Tile::Defused }
+    } else if Self::is_f(u) { Tile::Flag }
+    else if Self::is_q(u) && self.cfg.question { Tile::Question }
+    else { Tile::Closed })
+  }
+
+  /// tiles [y][x] of the viewport
+  pub fn tiles(&self) -> Result<Vec<Vec<Tile>>, MineFieldError> {
+    (0..self.vp.h).map(|y| (0..self.vp.w).map(|x|
+      self.tile(self.vp.y + y, self.vp.x + x)).collect()).collect()
+  }
+}
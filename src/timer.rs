@@ -0,0 +1,84 @@
+//! game timer
+//!
+
+use std::time::{Duration, Instant};
+
+use crate::MineField;
+
+/// TimerEvent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerEvent {
+  /// time budget (Config::time_limit) expired
+  TimeExpired
+}
+
+/// Splits (speedrun split times)
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Splits {
+  /// first open
+  pub first: Option<Duration>,
+  /// 25%, 50%, 75% of 3BV completed
+  pub q: [Option<Duration>; 3],
+  /// final (game ended)
+  pub end: Option<Duration>,
+  /// 3BV of the board (0: not placed yet)
+  pub bbbv: u32
+}
+
+/// Timer
+#[derive(Debug, Clone, Default)]
+pub struct Timer {
+  /// accumulated while stopped
+  pub acc: Duration,
+  /// running since
+  pub at: Option<Instant>
+}
+
+/// Timer
+impl Timer {
+  /// start (or restart after stop)
+  pub fn start(&mut self) -> () {
+    if self.at.is_none() { self.at = Some(Instant::now()); }
+  }
+
+  /// stop
+  pub fn stop(&mut self) -> () {
+    if let Some(t) = self.at.take() { self.acc += t.elapsed(); }
+  }
+
+  /// reset
+  pub fn reset(&mut self) -> () { *self = Timer::default(); }
+
+  /// is_running
+  pub fn is_running(&self) -> bool { self.at.is_some() }
+
+  /// elapsed
+  pub fn elapsed(&self) -> Duration {
+    self.acc + self.at.map_or(Duration::ZERO, |t| t.elapsed())
+  }
+}
+
+/// MineField
+impl MineField {
+  /// set_splits (false: no tracking)
+  pub fn set_splits(&mut self, b: bool) -> () {
+    self.sp = if b { Some(Splits::default()) } else { None };
+  }
+
+  /// update_splits (called after each action when tracking)
+  pub fn update_splits(&mut self) -> () {
+    if self.sp.is_none() || !self.p { return; }
+    let t = self.elapsed();
+    let (opened, end) = (self.opened(), self.is_end());
+    let bbbv = match self.sp { Some(s) if s.bbbv > 0 => s.bbbv,
+      _ => self.bbbv() };
+    let done = self.bbbv_done();
+    let Some(sp) = &mut self.sp else { return; };
+    sp.bbbv = bbbv;
+    if sp.first.is_none() && opened > 0 { sp.first = Some(t); }
+    for (i, q) in sp.q.iter_mut().enumerate() {
+      if q.is_none() && done * 4 >= bbbv * (i as u32 + 1) { *q = Some(t); }
+    }
+    if sp.end.is_none() && end { sp.end = Some(t); }
+  }
+}
@@ -0,0 +1,53 @@
+//! treasure hunt variant (collectible items on safe cells, a parallel layer
+//! to the packed field)
+//!
+
+use crate::MineField;
+
+/// Item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Item {
+  /// treasure
+  Treasure,
+  /// power up
+  PowerUp
+}
+
+/// Found (item revealed by an open)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Found {
+  /// item
+  pub item: Item,
+  /// row
+  pub r: u16,
+  /// column
+  pub c: u16
+}
+
+/// MineField
+impl MineField {
+  /// place_items cfg.treasures and cfg.powerups on the first safe cells of
+  /// the shuffled positions p (not on the cursor)
+  pub fn place_items(&mut self, p: &[u16]) -> () {
+    let n = (self.cfg.treasures, self.cfg.powerups);
+    let mut q = p.iter().map(|&i| (i / self.w, i % self.w))
+      .filter(|&(r, c)| !Self::is_mine(self.f[r as usize][c as usize])
+        && (r, c) != (self.r, self.c) && self.is_playable(r, c))
+      .collect::<Vec<_>>().into_iter();
+    for (k, item) in [(n.0, Item::Treasure), (n.1, Item::PowerUp)] {
+      for p in q.by_ref().take(k as usize) { self.items.insert(p, item); }
+    }
+  }
+
+  /// collect items on opened cells (queued to take_found)
+  pub fn collect(&mut self, q: &[Vec<(u16, u16)>]) -> () {
+    for &(r, c) in q.iter().flatten() {
+      let Some(item) = self.items.remove(&(r, c)) else { continue; };
+      if let Some(fb) = &mut self.fb { fb.on_item(r, c, item); }
+      self.found.push(Found{item, r, c});
+    }
+  }
+
+  /// take_found items since the last call
+  pub fn take_found(&mut self) -> Vec<Found> { std::mem::take(&mut self.found) }
+}
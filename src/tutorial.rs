@@ -0,0 +1,205 @@
+//! guided tutorial (scripted boards with step prompts)
+//!
+//! lessons in text, `lesson NAME` starts a lesson, `board ROW` adds a row
+//! (`*` mine, others safe), `setup MOVES` are played before the first
+//! step, `step PROMPT` adds a step and `expect MOVES` the moves accepted
+//! for it (move notation of replay separated by whitespaces), `#` comment
+//!
+
+use crate::MineField;
+use crate::error::MineFieldError::{self, ParseError};
+use crate::messages::Messages;
+use crate::replay::Move;
+
+/// lessons of the basics (open, flag, chord)
+pub const BASICS: &str = "\
+lesson open
+board ...
+board ...
+board ..*
+step open the top left corner, empty cells open their neighbors
+expect o:a1
+
+lesson flag
+board ..*..
+setup o:a1
+step flag the cell the 1 at b1 points to
+expect f:c1
+step the mine is flagged, open the last corner
+expect o:e1
+
+lesson chord
+board ..*..
+board .....
+setup o:a1
+step flag the only closed cell around the 1 at b1
+expect f:c1
+step chord the 1 at b2, it has its flag so the rest is safe
+expect #:b2
+step chord the 1 at c2 as well
+expect #:c2
+step chord the 1 at d2 to open the rest
+expect #:d2
+";
+
+/// Step
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+  /// prompt
+  pub prompt: String,
+  /// moves accepted
+  pub expect: Vec<Move>
+}
+
+/// Lesson
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lesson {
+  /// name
+  pub name: String,
+  /// rows of the board (`*` mine)
+  pub board: Vec<String>,
+  /// moves played before the first step
+  pub setup: Vec<Move>,
+  /// steps
+  pub steps: Vec<Step>
+}
+
+/// Lesson
+impl Lesson {
+  /// field of the board with the setup played
+  pub fn field(&self) -> MineField {
+    let w = self.board.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let q = self.board.iter().enumerate().flat_map(|(r, l)| l.chars()
+      .enumerate().filter(|&(_, ch)| ch == '*')
+      .map(move |(c, _)| (r as u16, c as u16))).collect::<Vec<_>>();
+    let mut f = MineField::with_mines(w as u16, self.board.len() as u16, &q);
+    for m in &self.setup { m.play(&mut f); }
+    f
+  }
+}
+
+/// moves separated by whitespaces
+fn moves(s: &str) -> Result<Vec<Move>, MineFieldError> {
+  s.split_whitespace().map(|m| m.parse::<Move>()).collect()
+}
+
+/// parse lessons (see the module format)
+pub fn parse(s: &str) -> Result<Vec<Lesson>, MineFieldError> {
+  let mut v: Vec<Lesson> = vec![];
+  for (i, l) in s.lines().enumerate() {
+    let l = l.trim();
+    if l.is_empty() || l.starts_with('#') { continue; }
+    let (k, a) = l.split_once(' ').unwrap_or((l, ""));
+    if k == "lesson" {
+      v.push(Lesson{name: a.to_string(), board: vec![], setup: vec![],
+        steps: vec![]});
+      continue;
+    }
+    let e = || ParseError(format!("line {}: {}", i + 1, l));
+    let Some(p) = v.last_mut() else { Err(e())? };
+    match k {
+    "board" => p.board.push(a.to_string()),
+    "setup" => p.setup.extend(moves(a)?),
+    "step" => p.steps.push(Step{prompt: a.to_string(), expect: vec![]}),
+    "expect" => match p.steps.last_mut() {
+      Some(t) => t.expect.extend(moves(a)?),
+      None => Err(e())?
+      },
+    _ => Err(e())?
+    }
+  }
+  if let Some(p) = v.iter().find(|p| p.board.is_empty() || p.steps.is_empty()
+    || p.steps.iter().any(|t| t.expect.is_empty())) {
+    Err(ParseError(format!("lesson {}: board, steps and expect required",
+      p.name)))?
+  }
+  Ok(v)
+}
+
+/// Verdict of an action
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+  /// not the expected move (not played)
+  Wrong,
+  /// expected move played, next step
+  Correct,
+  /// last step of the lesson done (see next_lesson)
+  LessonDone,
+  /// last step of the last lesson done
+  Finished
+}
+
+/// Tutorial
+pub struct Tutorial {
+  /// lessons
+  pub lessons: Vec<Lesson>,
+  /// current lesson
+  pub lesson: usize,
+  /// current step (steps.len(): lesson done)
+  pub step: usize,
+  /// wrong actions
+  pub wrong: u32,
+  /// field of the current lesson
+  pub field: MineField
+}
+
+/// Tutorial
+impl Tutorial {
+  /// constructor (at least a lesson)
+  pub fn new(lessons: Vec<Lesson>) -> Self {
+    assert!(!lessons.is_empty(), "tutorial needs a lesson");
+    let field = lessons[0].field();
+    Tutorial{lessons, lesson: 0, step: 0, wrong: 0, field}
+  }
+
+  /// basics (see BASICS)
+  pub fn basics() -> Self {
+    Tutorial::new(parse(BASICS).expect("BASICS must be valid"))
+  }
+
+  /// current lesson
+  pub fn current(&self) -> &Lesson { &self.lessons[self.lesson] }
+
+  /// prompt of the current step (None: lesson done)
+  pub fn prompt(&self) -> Option<&str> {
+    self.current().steps.get(self.step).map(|t| t.prompt.as_str())
+  }
+
+  /// prompt of the current step by the messages
+  pub fn prompt_with(&self, ms: &impl Messages) -> Option<String> {
+    self.prompt().map(|p| ms.tutorial(&self.current().name, self.step, p))
+  }
+
+  /// act a move (played only when expected by the current step)
+  pub fn act(&mut self, m: Move) -> Verdict {
+    let Some(t) = self.current().steps.get(self.step) else {
+      self.wrong += 1;
+      return Verdict::Wrong;
+    };
+    if !t.expect.contains(&m) {
+      self.wrong += 1;
+      return Verdict::Wrong;
+    }
+    m.play(&mut self.field);
+    self.step += 1;
+    if self.step < self.current().steps.len() { Verdict::Correct }
+    else if self.lesson + 1 < self.lessons.len() { Verdict::LessonDone }
+    else { Verdict::Finished }
+  }
+
+  /// next_lesson after the current one is done (false: not done or last)
+  pub fn next_lesson(&mut self) -> bool {
+    if self.prompt().is_some() || self.lesson + 1 >= self.lessons.len() {
+      return false;
+    }
+    self.lesson += 1;
+    self.step = 0;
+    self.field = self.current().field();
+    true
+  }
+
+  /// is_finished (last step of the last lesson done)
+  pub fn is_finished(&self) -> bool {
+    self.prompt().is_none() && self.lesson + 1 == self.lessons.len()
+  }
+}
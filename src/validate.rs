@@ -0,0 +1,89 @@
+//! internal consistency checks (property tests and fuzzing)
+//!
+
+use std::error::Error;
+use std::fmt;
+
+use crate::MineField;
+use crate::cell::RawCell;
+
+/// InvariantError
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvariantError {
+  /// storage is not w x h (cells)
+  Size(usize),
+  /// invalid packed cell (r, c, bits)
+  Cell(u16, u16, u8),
+  /// mines placed differ from m (placed)
+  Mines(u16),
+  /// number differs from the mines around (r, c, number, mines)
+  Number(u16, u16, u8, u8),
+  /// opened counter in s differs from opened cells (s, cells)
+  Opened(u16, u16),
+  /// opened cell flagged before the ending (r, c)
+  OpenFlag(u16, u16),
+  /// cursor out of the field (r, c)
+  Cursor(u16, u16)
+}
+
+/// InvariantError
+impl fmt::Display for InvariantError {
+  /// fmt
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+    InvariantError::Size(n) => write!(f, "storage of {} cells", n),
+    InvariantError::Cell(r, c, u) =>
+      write!(f, "invalid cell ({}, {}) {:#04x}", r, c, u),
+    InvariantError::Mines(n) => write!(f, "{} mines placed", n),
+    InvariantError::Number(r, c, v, n) =>
+      write!(f, "number {} at ({}, {}) with {} mines around", v, r, c, n),
+    InvariantError::Opened(s, n) =>
+      write!(f, "opened counter {} with {} cells opened", s, n),
+    InvariantError::OpenFlag(r, c) =>
+      write!(f, "flag on opened ({}, {})", r, c),
+    InvariantError::Cursor(r, c) => write!(f, "cursor ({}, {}) out", r, c)
+    }
+  }
+}
+
+/// InvariantError
+impl Error for InvariantError {}
+
+/// MineField
+impl MineField {
+  /// validate internal consistency (mines count m once placed, numbers of
+  /// the mines around, opened counter in s, no flag on an opened cell but
+  /// at the ending, cursor in the field)
+  pub fn validate(&self) -> Result<(), InvariantError> {
+    let n = self.f.as_slice().len();
+    if n != self.w as usize * self.h as usize {
+      return Err(InvariantError::Size(n));
+    }
+    if self.r >= self.h || self.c >= self.w {
+      return Err(InvariantError::Cursor(self.r, self.c));
+    }
+    let (mut mines, mut opened) = (0u16, 0u16);
+    for (r, c, u) in self.cells() {
+      let b = u8::from(u);
+      if RawCell::try_from(b).is_err() {
+        return Err(InvariantError::Cell(r, c, b));
+      }
+      if u.is_mine() { mines += 1; }
+      else {
+        let k = Self::get_k(self.w, self.h, &self.f, r, c);
+        if k != u.value() {
+          return Err(InvariantError::Number(r, c, u.value(), k));
+        }
+      }
+      if u.is_open() && !u.is_forced() {
+        if u.is_flag() { return Err(InvariantError::OpenFlag(r, c)); }
+        if !u.is_mine() { opened += 1; }
+      }
+    }
+    if self.p && mines != self.m { return Err(InvariantError::Mines(mines)); }
+    if opened != self.opened() {
+      return Err(InvariantError::Opened(self.opened(), opened));
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,105 @@
+//! versus mode (two players racing on the same board)
+//!
+
+use std::time::Duration;
+
+use crate::MineField;
+use crate::apply::MoveError;
+use crate::config::Difficulty;
+use crate::replay::Move;
+use crate::timer::TimerEvent;
+
+/// VersusEvent (p: player 0 or 1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersusEvent {
+  /// player opened n cells
+  Opened(usize, u16),
+  /// player exploded
+  Exploded(usize),
+  /// player won
+  Won(usize),
+  /// player ran out of time
+  Expired(usize)
+}
+
+/// Progress
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+  /// 3BV completed
+  pub done: u32,
+  /// 3BV of the board
+  pub bbbv: u32,
+  /// elapsed time
+  pub t: Duration
+}
+
+/// Versus
+pub struct Versus {
+  /// fields of the players (same seed, mines placed at once, the center is
+  /// safe)
+  pub f: [MineField; 2]
+}
+
+/// Versus
+impl Versus {
+  /// constructor
+  pub fn new(d: Difficulty, seed: u64) -> Self {
+    let g = || {
+      let mut f = MineField::from_seed(d.w, d.h, d.m, seed);
+      f.goto(d.h / 2, d.w / 2);
+      f.start();
+      f
+    };
+    Versus{f: [g(), g()]}
+  }
+
+  /// play a move of the player and return the events
+  pub fn play(&mut self, p: usize, m: Move) ->
+    Result<Vec<VersusEvent>, MoveError> {
+    let o = self.f[p].apply_move(m)?;
+    let mut v = vec![];
+    if !o.opened.is_empty() {
+      v.push(VersusEvent::Opened(p, o.opened.len() as u16));
+    }
+    if o.exploded { v.push(VersusEvent::Exploded(p)); }
+    if o.won { v.push(VersusEvent::Won(p)); }
+    Ok(v)
+  }
+
+  /// check_time of both players
+  pub fn check_time(&mut self) -> Vec<VersusEvent> {
+    (0..2).filter(|&p| self.f[p].check_time() == Some(TimerEvent::TimeExpired))
+      .map(VersusEvent::Expired).collect()
+  }
+
+  /// progress of the player
+  pub fn progress(&self, p: usize) -> Progress {
+    let f = &self.f[p];
+    Progress{done: f.bbbv_done(), bbbv: f.bbbv(), t: f.elapsed()}
+  }
+
+  /// leader by 3BV completed (None: even)
+  pub fn leader(&self) -> Option<usize> {
+    let (a, b) = (self.progress(0).done, self.progress(1).done);
+    if a > b { Some(0) } else if b > a { Some(1) } else { None }
+  }
+
+  /// winner (first to win, or the survivor when the other lost)
+  pub fn winner(&self) -> Option<usize> {
+    let lost = |p: usize| self.f[p].is_end() && !self.f[p].is_success();
+    match (self.f[0].is_success(), self.f[1].is_success()) {
+    (true, true) => Some(if self.f[0].elapsed() <= self.f[1].elapsed() { 0 }
+      else { 1 }),
+    (true, false) => Some(0),
+    (false, true) => Some(1),
+    _ => if lost(1) && !lost(0) { Some(0) }
+      else if lost(0) && !lost(1) { Some(1) }
+      else { None }
+    }
+  }
+
+  /// is_over (both ended, or a winner decided)
+  pub fn is_over(&self) -> bool {
+    self.f.iter().all(|f| f.is_end()) || self.winner().is_some()
+  }
+}
@@ -0,0 +1,61 @@
+//! viewport (visible part of a large board)
+//!
+
+/// Viewport
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+  /// offset column
+  pub x: u16,
+  /// offset row
+  pub y: u16,
+  /// width (cells)
+  pub w: u16,
+  /// height (cells)
+  pub h: u16
+}
+
+/// Viewport
+impl Viewport {
+  /// constructor
+  pub fn new(w: u16, h: u16) -> Self { Viewport{x: 0, y: 0, w, h} }
+
+  /// contains (r, c)
+  pub fn contains(&self, r: u16, c: u16) -> bool {
+    r >= self.y && r - self.y < self.h && c >= self.x && c - self.x < self.w
+  }
+
+  /// resize to w x h cells (at least 1) in the board bw x bh
+  pub fn resize(&mut self, w: u16, h: u16, bw: u16, bh: u16) -> () {
+    self.w = w.max(1);
+    self.h = h.max(1);
+    self.clamp(bw, bh);
+  }
+
+  /// clamp size and offset into the board bw x bh
+  pub fn clamp(&mut self, bw: u16, bh: u16) -> () {
+    self.w = self.w.min(bw);
+    self.h = self.h.min(bh);
+    self.x = self.x.min(bw - self.w);
+    self.y = self.y.min(bh - self.h);
+  }
+
+  /// scroll to keep (r, c) visible (true when scrolled)
+  pub fn follow(&mut self, r: u16, c: u16) -> bool {
+    let o = (self.x, self.y);
+    if c < self.x { self.x = c; }
+    else if c >= self.x + self.w { self.x = c + 1 - self.w; }
+    if r < self.y { self.y = r; }
+    else if r >= self.y + self.h { self.y = r + 1 - self.h; }
+    o != (self.x, self.y)
+  }
+
+  /// scroll by (dx, dy) in the board bw x bh (true when scrolled)
+  pub fn scroll(&mut self, dx: i32, dy: i32, bw: u16, bh: u16) -> bool {
+    let o = (self.x, self.y);
+    let mx = bw.saturating_sub(self.w) as i32;
+    let my = bh.saturating_sub(self.h) as i32;
+    self.x = (self.x as i32 + dx).clamp(0, mx) as u16;
+    self.y = (self.y as i32 + dy).clamp(0, my) as u16;
+    o != (self.x, self.y)
+  }
+}
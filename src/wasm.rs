@@ -0,0 +1,132 @@
+//! wasm / web adapter
+//!
+//! exported to js by a cdylib crate depending on minefield with `wasm`
+//!
+//! state json
+//!
+//! - {"w": w, "h": h, "m": m, "state": "playing" | "won" | "lost",
+//!   "opened": n, "r": cursor row, "c": cursor column, "cells": [[...]]}
+//! - cells: 0-8 opened, 15 opened mine, -1 closed, -2 flag, -3 question
+//!
+
+use std::error::Error;
+
+use wasm_bindgen::prelude::*;
+
+use mvc_rs::{TPacket, TView};
+
+use crate::MineField;
+use crate::blink::Blinker;
+
+/// json string
+pub fn json_str(s: &str) -> String {
+  let mut o = String::from("\"");
+  for ch in s.chars() {
+    match ch {
+    '"' => o.push_str("\\\""),
+    '\\' => o.push_str("\\\\"),
+    c if (c as u32) < 0x20 => o.push_str(&format!("\\u{:04x}", c as u32)),
+    c => o.push(c)
+    }
+  }
+  o.push('"');
+  o
+}
+
+/// MessageWR (collects cell updates instead of drawing)
+#[derive(Debug, Clone, Default)]
+pub struct MessageWR {
+  /// updates (x, y, bgc, fgc, glyph)
+  pub q: Vec<(u16, u16, u16, u16, String)>
+}
+
+/// MessageWR
+impl MessageWR {
+  /// drain updates as json [{"x", "y", "bgc", "fgc", "s"}, ...]
+  pub fn take_json(&mut self) -> String {
+    let v = self.q.drain(..).map(|(x, y, b, f, s)|
+      format!("{{\"x\":{},\"y\":{},\"bgc\":{},\"fgc\":{},\"s\":{}}}",
+        x, y, b, f, json_str(&s))).collect::<Vec<_>>();
+    format!("[{}]", v.join(","))
+  }
+}
+
+/// trait TView for MessageWR
+impl TView<u16> for MessageWR {
+  /// wr
+  fn wr(&mut self, p: impl TPacket) -> Result<(), Box<dyn Error>> {
+    let v = p.to_vec();
+    self.q.push((v[0], v[1], v[3], v[4], p.as_str().to_string()));
+    Ok(())
+  }
+  /// reg
+  fn reg(&mut self, _c: Vec<u16>) -> () {}
+  /// col
+  fn col(&self, n: u16) -> u16 { n }
+}
+
+/// MineField
+impl MineField {
+  /// state json
+  pub fn state_json(&self) -> String {
+    let st = if self.is_success() { "won" }
+      else if self.is_explosion() || self.expired { "lost" }
+      else { "playing" };
+    let cells = self.f.iter().map(|v| format!("[{}]", v.iter().map(|&u|
+      if Self::is_o(u) { Self::get_v(u) as i8 }
+      else if Self::is_f(u) { -2 }
+      else if Self::is_q(u) { -3 }
+      else { -1 }).map(|k| k.to_string()).collect::<Vec<_>>().join(",")))
+      .collect::<Vec<_>>().join(",");
+    format!("{{\"w\":{},\"h\":{},\"m\":{},\"state\":\"{}\",\"opened\":{},\
+      \"r\":{},\"c\":{},\"cells\":[{}]}}",
+      self.w, self.h, self.m, st, self.opened(), self.r, self.c, cells)
+  }
+}
+
+/// WasmField (js friendly wrapper)
+#[wasm_bindgen]
+pub struct WasmField {
+  /// field
+  m: MineField,
+  /// updates
+  v: MessageWR,
+  /// blink timing
+  b: Blinker
+}
+
+/// WasmField
+#[wasm_bindgen]
+impl WasmField {
+  /// constructor
+  #[wasm_bindgen(constructor)]
+  pub fn new(w: u16, h: u16, m: u16) -> Self {
+    WasmField{m: MineField::new(w, h, m), v: MessageWR::default(),
+      b: Blinker::default()}
+  }
+
+  /// open (x, y)
+  pub fn open(&mut self, x: u16, y: u16) -> bool {
+    self.m.goto(y, x) && self.m.click()
+  }
+
+  /// flag (x, y) by the mark cycle
+  pub fn flag(&mut self, x: u16, y: u16) -> bool {
+    self.m.goto(y, x) && self.m.mark()
+  }
+
+  /// tick blink cursor
+  pub fn tick(&mut self) -> Result<(), JsError> {
+    self.b.tick(&mut self.m, &mut self.v)
+      .map_err(|e| JsError::new(&e.to_string()))
+  }
+
+  /// cell updates json since the last call
+  pub fn updates_json(&mut self) -> Result<String, JsError> {
+    self.m.refresh_dirty(&mut self.v).map_err(|e| JsError::new(&e.to_string()))?;
+    Ok(self.v.take_json())
+  }
+
+  /// state json
+  pub fn state_json(&self) -> String { self.m.state_json() }
+}
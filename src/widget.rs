@@ -0,0 +1,167 @@
+//! framed widgets drawn by WR (message box and selectable menu)
+//!
+
+use mvc_rs::TView;
+
+use crate::MineField;
+use crate::config::Difficulty;
+use crate::error::MineFieldError;
+use crate::input::Input;
+use crate::messages::{English, Messages, Msg};
+use crate::panel::wr_str;
+
+/// MessageBox (lines in a frame)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageBox {
+  /// lines inside the frame
+  pub lines: Vec<String>,
+  /// frame colors (bgc, fgc)
+  pub frame: (u16, u16),
+  /// text colors (bgc, fgc)
+  pub text: (u16, u16)
+}
+
+/// MessageBox
+impl MessageBox {
+  /// constructor
+  pub fn new(lines: Vec<String>) -> Self {
+    MessageBox{lines, frame: (4, 5), text: (2, 3)}
+  }
+
+  /// game over box of a field at the end (result, time and keys)
+  pub fn game_over(m: &MineField) -> Self { Self::game_over_with(m, &English) }
+
+  /// game over box by the messages
+  pub fn game_over_with(m: &MineField, ms: &impl Messages) -> Self {
+    let r = if m.is_success() { Msg::YouWin } else { Msg::GameOver };
+    MessageBox::new(vec![
+      format!("{} {}", ms.text(r), ms.time(m.elapsed())),
+      ms.text(Msg::Keys)])
+  }
+
+  /// size (w, h) with frame
+  pub fn size(&self) -> (u16, u16) {
+    let w = self.lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    (w as u16 + 4, self.lines.len() as u16 + 2)
+  }
+
+  /// render at (x, y) (line i with the text colors of hi(i))
+  pub fn render_with<T>(&self, g: &mut impl TView<T>, x: u16, y: u16,
+    hi: impl Fn(usize) -> Option<(u16, u16)>) -> Result<(), MineFieldError> {
+    let (w, h) = self.size();
+    let (fb, ff) = self.frame;
+    let hr = format!("+{}+", "-".repeat(w as usize - 2));
+    wr_str(g, x, y, fb, ff, &hr)?;
+    for (i, l) in self.lines.iter().enumerate() {
+      let (tb, tf) = hi(i).unwrap_or(self.text);
+      let y = y + 1 + i as u16;
+      wr_str(g, x, y, fb, ff, "|")?;
+      wr_str(g, x + 1, y, tb, tf, &format!(" {:<1$} ", l, w as usize - 4))?;
+      wr_str(g, x + w - 1, y, fb, ff, "|")?;
+    }
+    wr_str(g, x, y + h - 1, fb, ff, &hr)?;
+    Ok(())
+  }
+
+  /// render at (x, y)
+  pub fn render_at<T>(&self, g: &mut impl TView<T>, x: u16, y: u16) ->
+    Result<(), MineFieldError> {
+    self.render_with(g, x, y, |_| None)
+  }
+
+  /// render centered on the viewport of the field
+  pub fn render_on<T>(&self, m: &MineField, g: &mut impl TView<T>) ->
+    Result<(), MineFieldError> {
+    self.render(g, m.vp.w * m.cw(), m.vp.h)
+  }
+
+  /// render centered on the screen sw x sh
+  pub fn render<T>(&self, g: &mut impl TView<T>, sw: u16, sh: u16) ->
+    Result<(), MineFieldError> {
+    let (w, h) = self.size();
+    self.render_at(g, sw.saturating_sub(w) / 2, sh.saturating_sub(h) / 2)
+  }
+}
+
+/// MenuEvent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuEvent {
+  /// nothing
+  None,
+  /// selection moved
+  Moved,
+  /// item chosen
+  Chosen(usize),
+  /// cancelled
+  Cancel
+}
+
+/// Menu (title and items, one selected)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Menu {
+  /// title
+  pub title: String,
+  /// items
+  pub items: Vec<String>,
+  /// selected item
+  pub sel: usize,
+  /// box (frame and text colors)
+  pub mb: MessageBox,
+  /// selected item colors (bgc, fgc)
+  pub hi: (u16, u16)
+}
+
+/// Menu
+impl Menu {
+  /// constructor
+  pub fn new(title: &str, items: Vec<String>) -> Self {
+    Menu{title: title.to_string(), items, sel: 0,
+      mb: MessageBox::new(vec![]), hi: (3, 2)}
+  }
+
+  /// difficulty menu (beginner, intermediate, expert)
+  pub fn difficulty() -> Self {
+    let v = [("beginner", Difficulty::beginner()),
+      ("intermediate", Difficulty::intermediate()),
+      ("expert", Difficulty::expert())];
+    Menu::new("difficulty", v.iter().map(|(s, d)|
+      format!("{:<12} {}x{} {}", s, d.w, d.h, d.m)).collect())
+  }
+
+  /// difficulty of the item i of Menu::difficulty
+  pub fn difficulty_of(i: usize) -> Option<Difficulty> {
+    [Difficulty::beginner(), Difficulty::intermediate(),
+      Difficulty::expert()].get(i).copied()
+  }
+
+  /// handle an input (up / down wrap, open chooses, quit cancels)
+  pub fn handle(&mut self, i: Input) -> MenuEvent {
+    let n = self.items.len();
+    if n == 0 { return if i == Input::Quit { MenuEvent::Cancel }
+      else { MenuEvent::None }; }
+    match i {
+    Input::Up => { self.sel = (self.sel + n - 1) % n; MenuEvent::Moved },
+    Input::Down => { self.sel = (self.sel + 1) % n; MenuEvent::Moved },
+    Input::Open => MenuEvent::Chosen(self.sel),
+    Input::Quit => MenuEvent::Cancel,
+    _ => MenuEvent::None
+    }
+  }
+
+  /// message box of the menu (title, blank line, items)
+  pub fn message_box(&self) -> MessageBox {
+    let mut lines = vec![self.title.clone(), String::new()];
+    lines.extend(self.items.iter().cloned());
+    MessageBox{lines, ..self.mb.clone()}
+  }
+
+  /// render centered on the screen sw x sh (the selected item highlighted)
+  pub fn render<T>(&self, g: &mut impl TView<T>, sw: u16, sh: u16) ->
+    Result<(), MineFieldError> {
+    let b = self.message_box();
+    let (w, h) = b.size();
+    let s = self.sel + 2;
+    b.render_with(g, sw.saturating_sub(w) / 2, sh.saturating_sub(h) / 2,
+      |i| if i == s { Some(self.hi) } else { None })
+  }
+}
@@ -0,0 +1,225 @@
+//! compact binary wire format of board snapshots and sync deltas
+//!
+//! header `MF` version kind, integers little endian, counters as varint
+//!
+//! snapshot: w h m s (u16) hidden (u8), cell states run length encoded as
+//! bytes `state << 6 | run - 1` (state 0 closed, 1 opened, 2 flag,
+//! 3 question), then cell values 4 bit packed for every cell (opened cells
+//! only when hidden)
+//!
+//! deltas: count, then seq tag args (u16) of each update
+//!
+
+use crate::MineField;
+use crate::error::MineFieldError::{self, ParseError};
+use crate::sync::{Delta, Update};
+
+/// wire format version
+pub const VERSION: u8 = 1;
+
+/// kind snapshot
+pub const SNAPSHOT: u8 = 1;
+
+/// kind deltas
+pub const DELTAS: u8 = 2;
+
+/// Snapshot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+  /// area width
+  pub w: u16,
+  /// area height
+  pub h: u16,
+  /// mines
+  pub m: u16,
+  /// status (see MineField::s)
+  pub s: u16,
+  /// mine locations and numbers of closed cells stripped (values 0)
+  pub hidden: bool,
+  /// cells (same bits as MineField::f without the ending bit)
+  pub f: Vec<Vec<u8>>
+}
+
+/// Snapshot
+impl Snapshot {
+  /// snapshot of the field (hide: strip closed cells for spectators)
+  pub fn of(f: &MineField, hide: bool) -> Self {
+    let g = f.f.iter().map(|v| v.iter().map(|&u| {
+      let u = u & 0x7f;
+      if hide && !MineField::is_o(u) { u & 0x60 } else { u }
+    }).collect()).collect();
+    Snapshot{w: f.w, h: f.h, m: f.m, s: f.s, hidden: hide, f: g}
+  }
+
+  /// encode
+  pub fn encode(&self) -> Vec<u8> {
+    let mut b = header(SNAPSHOT);
+    for n in [self.w, self.h, self.m, self.s] {
+      b.extend_from_slice(&n.to_le_bytes());
+    }
+    b.push(self.hidden as u8);
+    let cells = || self.f.iter().flatten().copied();
+    let state = |u: u8| if MineField::is_o(u) { 1 }
+      else if MineField::is_f(u) { 2 } else if MineField::is_q(u) { 3 }
+      else { 0 };
+    let mut run: Option<(u8, u8)> = None;
+    for u in cells() {
+      let k = state(u);
+      run = match run {
+      Some((j, n)) if j == k && n < 64 => Some((j, n + 1)),
+      Some((j, n)) => { b.push(j << 6 | (n - 1)); Some((k, 1)) },
+      None => Some((k, 1))
+      };
+    }
+    if let Some((j, n)) = run { b.push(j << 6 | (n - 1)); }
+    let v: Vec<u8> = cells().filter(|&u| !self.hidden || MineField::is_o(u))
+      .map(|u| u & 0x0f).collect();
+    b.extend(v.chunks(2).map(|q| q[0] | q.get(1).map_or(0, |&x| x << 4)));
+    b
+  }
+
+  /// decode
+  pub fn decode(b: &[u8]) -> Result<Self, MineFieldError> {
+    let mut r = Reader::new(b, SNAPSHOT)?;
+    let (w, h, m, s) = (r.u16()?, r.u16()?, r.u16()?, r.u16()?);
+    let hidden = r.u8()? != 0;
+    let n = MineField::check_size(w, h)? as usize;
+    if m as usize >= n { return Err(MineFieldError::TooManyMines(m)); }
+    let mut st = Vec::with_capacity(n);
+    while st.len() < n {
+      let u = r.u8()?;
+      st.extend(std::iter::repeat_n(u >> 6, (u & 0x3f) as usize + 1));
+    }
+    if st.len() != n { Err(ParseError("wire: cell runs overflow".into()))? }
+    let o = st.iter().filter(|&&j| j == 1).count();
+    if (s & 0x3fff) as usize > o {
+      Err(ParseError("wire: status exceeds opened cells".into()))?
+    }
+    let k = st.iter().filter(|&&j| !hidden || j == 1).count();
+    let mut v = Vec::with_capacity(k);
+    while v.len() < k {
+      let u = r.u8()?;
+      v.push(u & 0x0f);
+      v.push(u >> 4);
+    }
+    let mut v = v.into_iter();
+    let mut f = vec![vec![0u8; w as usize]; h as usize];
+    for (i, &j) in st.iter().enumerate() {
+      let x = if !hidden || j == 1 { v.next().unwrap_or(0) } else { 0 };
+      f[i / w as usize][i % w as usize] = x | [0, 0x10, 0x40, 0x20][j as usize];
+    }
+    r.end()?;
+    Ok(Snapshot{w, h, m, s, hidden, f})
+  }
+}
+
+/// header of the kind
+fn header(k: u8) -> Vec<u8> { vec![b'M', b'F', VERSION, k] }
+
+/// put varint
+fn put_varint(b: &mut Vec<u8>, mut n: u64) -> () {
+  while n >= 0x80 {
+    b.push(n as u8 | 0x80);
+    n >>= 7;
+  }
+  b.push(n as u8);
+}
+
+/// Reader
+struct Reader<'a> {
+  /// bytes
+  b: &'a [u8],
+  /// position
+  i: usize
+}
+
+/// Reader
+impl<'a> Reader<'a> {
+  /// constructor checking the header
+  fn new(b: &'a [u8], k: u8) -> Result<Self, MineFieldError> {
+    if b.len() < 4 || &b[..2] != b"MF" {
+      Err(ParseError("wire: bad header".into()))?
+    }
+    if b[2] != VERSION {
+      Err(ParseError(format!("wire: unsupported version {}", b[2])))?
+    }
+    if b[3] != k {
+      Err(ParseError(format!("wire: unexpected kind {}", b[3])))?
+    }
+    Ok(Reader{b, i: 4})
+  }
+
+  /// u8
+  fn u8(&mut self) -> Result<u8, MineFieldError> {
+    let u = *self.b.get(self.i)
+      .ok_or_else(|| ParseError("wire: truncated".into()))?;
+    self.i += 1;
+    Ok(u)
+  }
+
+  /// u16
+  fn u16(&mut self) -> Result<u16, MineFieldError> {
+    Ok(u16::from_le_bytes([self.u8()?, self.u8()?]))
+  }
+
+  /// varint
+  fn varint(&mut self) -> Result<u64, MineFieldError> {
+    let mut n = 0u64;
+    for k in 0..10 {
+      let u = self.u8()?;
+      n |= ((u & 0x7f) as u64) << (7 * k);
+      if u & 0x80 == 0 { return Ok(n); }
+    }
+    Err(ParseError("wire: varint too long".into()))?
+  }
+
+  /// end (no trailing bytes)
+  fn end(&self) -> Result<(), MineFieldError> {
+    if self.i != self.b.len() {
+      Err(ParseError("wire: trailing bytes".into()))?
+    }
+    Ok(())
+  }
+}
+
+/// encode updates
+pub fn encode_updates(v: &[Update]) -> Vec<u8> {
+  let mut b = header(DELTAS);
+  put_varint(&mut b, v.len() as u64);
+  for u in v {
+    put_varint(&mut b, u.n);
+    let (k, q): (u8, &[u16]) = match u.d {
+    Delta::Start(r, c) => (0, &[r, c]),
+    Delta::Open(r, c, x) => (1, &[r, c, x as u16]),
+    Delta::Mark(r, c, x) => (2, &[r, c, x as u16]),
+    Delta::Explode(r, c) => (3, &[r, c]),
+    Delta::Win => (4, &[]),
+    Delta::Expire => (5, &[])
+    };
+    b.push(k);
+    for n in q { b.extend_from_slice(&n.to_le_bytes()); }
+  }
+  b
+}
+
+/// decode updates
+pub fn decode_updates(b: &[u8]) -> Result<Vec<Update>, MineFieldError> {
+  let mut r = Reader::new(b, DELTAS)?;
+  let k = r.varint()?;
+  let mut v = vec![];
+  for _ in 0..k {
+    let n = r.varint()?;
+    let d = match r.u8()? {
+    0 => Delta::Start(r.u16()?, r.u16()?),
+    1 => Delta::Open(r.u16()?, r.u16()?, r.u16()? as u8),
+    2 => Delta::Mark(r.u16()?, r.u16()?, r.u16()? as u8),
+    3 => Delta::Explode(r.u16()?, r.u16()?),
+    4 => Delta::Win,
+    5 => Delta::Expire,
+    t => Err(ParseError(format!("wire: unknown delta {}", t)))?
+    };
+    v.push(Update{n, d});
+  }
+  r.end()?;
+  Ok(v)
+}